@@ -1,8 +1,72 @@
 use proc_macro::TokenStream;
-use proc_macro2::{Literal, TokenTree};
+use proc_macro2::{Ident, Literal, TokenTree};
 use proc_macro_error::{abort, proc_macro_error};
 use quote::quote;
-use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Type};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, GenericArgument, PathArguments, Type,
+};
+
+/// Returns the asset type `A` if `ty` is `Handle<A>`, `MeshMaterial2d<A>`,
+/// `MeshMaterial3d<A>` or `MaterialNode<A>`.
+fn asset_type(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if !["Handle", "MeshMaterial2d", "MeshMaterial3d", "MaterialNode"]
+        .iter()
+        .any(|x| segment.ident == x)
+    {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) if args.args.len() == 1 => Some(ty.clone()),
+        _ => None,
+    }
+}
+
+/// Returns fields marked with `#[opacity]`, separated into
+/// alpha fields and asset handle fields with their asset type.
+fn opacity_fields(data: Data, name: &Ident) -> (Vec<TokenTree>, Vec<(TokenTree, Type)>) {
+    let mut fields = Vec::new();
+    let mut handles = Vec::new();
+    let Data::Struct(s) = data else {
+        abort!(name.span(), "Only supports struct.")
+    };
+    match s.fields {
+        syn::Fields::Named(fields_named) => {
+            for field in fields_named.named {
+                for attribute in &field.attrs {
+                    if attribute.path().is_ident("opacity") {
+                        let ident = TokenTree::Ident(field.ident.clone().unwrap());
+                        match asset_type(&field.ty) {
+                            Some(ty) => handles.push((ident, ty)),
+                            None => fields.push(ident),
+                        }
+                    }
+                }
+            }
+        }
+        syn::Fields::Unnamed(fields_unnamed) => {
+            for (index, field) in fields_unnamed.unnamed.into_iter().enumerate() {
+                for attribute in &field.attrs {
+                    if attribute.path().is_ident("opacity") {
+                        let index = TokenTree::Literal(Literal::usize_unsuffixed(index));
+                        match asset_type(&field.ty) {
+                            Some(ty) => handles.push((index, ty)),
+                            None => fields.push(index),
+                        }
+                    }
+                }
+            }
+        }
+        syn::Fields::Unit => (),
+    }
+    (fields, handles)
+}
 
 /// Declare a `Component` or `Asset` as affected by opacity.
 ///
@@ -15,6 +79,11 @@ use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Type};
 ///   Makes `bevy_mod_opacity` set its value as alpha,
 ///   valid on `f32` or bevy's color types.
 ///
+///   On a component, also valid on `Handle<A>`, `MeshMaterial2d<A>`, `MeshMaterial3d<A>`
+///   and `MaterialNode<A>` where `A` is an `OpacityAsset`, the asset is written to directly.
+///   Assets are written through `OpacityAssets<A>`, so each asset is only written to when its opacity changes.
+///   Since this requires a context, the component cannot be registered with `register_opacity_parallel`.
+///
 /// # Type Attributes
 ///
 /// * `#[opacity(asset)]`
@@ -36,33 +105,9 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
     let mut asset = false;
     let mut extends = Vec::new();
     let mut masks = Vec::new();
-    let mut fields = Vec::new();
     let name = input.ident;
 
-    let Data::Struct(s) = input.data else {
-        abort!(name.span(), "Only supports struct.")
-    };
-    match s.fields {
-        syn::Fields::Named(fields_named) => {
-            for field in fields_named.named {
-                for attribute in field.attrs {
-                    if attribute.path().is_ident("opacity") {
-                        fields.push(TokenTree::Ident(field.ident.clone().unwrap()));
-                    }
-                }
-            }
-        }
-        syn::Fields::Unnamed(fields_unnamed) => {
-            for (index, field) in fields_unnamed.unnamed.into_iter().enumerate() {
-                for attribute in field.attrs {
-                    if attribute.path().is_ident("opacity") {
-                        fields.push(TokenTree::Literal(Literal::usize_unsuffixed(index)));
-                    }
-                }
-            }
-        }
-        syn::Fields::Unit => (),
-    }
+    let (fields, handles) = opacity_fields(input.data, &name);
 
     for attribute in &input.attrs {
         if !attribute.path().is_ident("opacity") {
@@ -89,6 +134,12 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
     }
     let crate0 = quote! {::bevy_mod_opacity};
     if asset || !extends.is_empty() || !masks.is_empty() {
+        if let Some((field, _)) = handles.first() {
+            abort!(
+                field.span(),
+                "Asset handle fields are only supported on components."
+            )
+        }
         let mut result = quote! {};
 
         if asset {
@@ -131,17 +182,46 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
         }
         result.into()
     } else {
+        // Deduplicate asset types since each `OpacityAssets<A>` can only be borrowed once.
+        let mut assets: Vec<Type> = Vec::new();
+        let mut handle_fields = Vec::new();
+        let mut handle_indices = Vec::new();
+        for (field, ty) in handles {
+            let key = quote! {#ty}.to_string();
+            let index = match assets.iter().position(|x| quote! {#x}.to_string() == key) {
+                Some(index) => index,
+                None => {
+                    assets.push(ty);
+                    assets.len() - 1
+                }
+            };
+            handle_fields.push(field);
+            handle_indices.push(Literal::usize_unsuffixed(index));
+        }
+        let (cx, cx_ident) = if assets.is_empty() {
+            (quote! {()}, quote! {_})
+        } else {
+            (
+                quote! {(#(#crate0::OpacityAssets<'static, 'static, #assets>,)*)},
+                quote! {cx},
+            )
+        };
         quote! {
             const _: () =  {
                 impl #crate0::OpacityQuery for &mut #name {
-                    type Cx = ();
+                    type Cx = #cx;
 
                     fn apply_opacity(
                         this: &mut <Self as #crate0::QueryData>::Item<'_>,
-                        _: &mut (),
+                        #cx_ident: &mut <Self::Cx as #crate0::SystemParam>::Item<'_, '_>,
                         opacity: f32,
                     ) {
                         #(#crate0::set_alpha(&mut this.#fields, opacity);)*
+                        #(
+                            if let Some(asset) = cx.#handle_indices.get_mut(this.#handle_fields.id(), opacity, ()) {
+                                #crate0::OpacityAsset::apply_opacity(asset, opacity);
+                            }
+                        )*
                     }
                 }
             };
@@ -149,3 +229,41 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
         .into()
     }
 }
+
+/// Implement `OpacityAsset` for a `Material` or `Material2d`.
+///
+/// Equivalent to `#[derive(Opacity)]` with `#[opacity(asset)]`.
+///
+/// # Field Attributes
+///
+/// * `#[opacity]`
+///
+///   Makes `bevy_mod_opacity` set its value as alpha,
+///   valid on `f32` or bevy's color types.
+#[proc_macro_error]
+#[proc_macro_derive(OpacityAsset, attributes(opacity))]
+pub fn opacity_asset(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+    let name = input.ident;
+    let (fields, handles) = opacity_fields(input.data, &name);
+    if let Some((field, _)) = handles.first() {
+        abort!(
+            field.span(),
+            "Asset handle fields are only supported on components."
+        )
+    }
+    let crate0 = quote! {::bevy_mod_opacity};
+    quote! {
+        const _: () =  {
+            impl #crate0::OpacityAsset for #name {
+                fn apply_opacity(
+                    &mut self,
+                    opacity: f32,
+                ) {
+                    #(#crate0::set_alpha(&mut self.#fields, opacity);)*
+                }
+            }
+        };
+    }
+    .into()
+}
@@ -28,7 +28,7 @@ impl Drop for OnDelete {
 pub fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(OpacityPlugin)
+        .add_plugins(OpacityPlugin::default())
         .insert_resource(AmbientLight {
             color: Color::WHITE,
             brightness: 1000.,
@@ -86,7 +86,7 @@ pub fn init(mut commands: Commands, assets: Res<AssetServer>) {
                     },
                     BorderColor(Color::WHITE),
                     BorderRadius::all(Val::Px(20.)),
-                    UiOpacity::Border,
+                    UiOpacity::BORDER,
                 ))
                 .with_children(|build| {
                     build.spawn((Text::new("Made with"), style.clone()));
@@ -0,0 +1,67 @@
+use bevy::{
+    a11y::AccessibilityNode,
+    app::App,
+    ecs::schedule::{common_conditions::resource_changed, Condition, IntoScheduleConfigs},
+    prelude::{Commands, Component, Entity, Query, Res, Resource},
+};
+
+use crate::{OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectResource,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Entities with computed opacity less than or equal to this value have their
+/// [`AccessibilityNode`] hidden, so screen readers do not announce faded out menus.
+///
+/// The node is shown again when opacity rises above the threshold,
+/// nodes that were already hidden stay hidden.
+///
+/// Defaults to `0.0`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityAccessibilityThreshold(pub f32);
+
+/// Marks an [`AccessibilityNode`] hidden by this crate.
+#[derive(Debug, Component)]
+struct AccessibilityHidden;
+
+fn update_accessibility(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    threshold: Res<OpacityAccessibilityThreshold>,
+    mut nodes: Query<(Entity, &mut AccessibilityNode, Option<&AccessibilityHidden>)>,
+) {
+    for (entity, mut node, hidden) in &mut nodes {
+        let invisible = map
+            .get(entity)
+            .is_some_and(|opacity| opacity <= threshold.0);
+        match hidden {
+            None if invisible && !node.is_hidden() => {
+                node.set_hidden();
+                commands.entity(entity).try_insert(AccessibilityHidden);
+            }
+            Some(_) if !invisible => {
+                node.clear_hidden();
+                commands.entity(entity).try_remove::<AccessibilityHidden>();
+            }
+            _ => (),
+        }
+    }
+}
+
+pub fn opacity_plugin_accessibility(app: &mut App) {
+    app.init_resource::<OpacityAccessibilityThreshold>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityAccessibilityThreshold>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        update_accessibility
+            .run_if(
+                resource_changed::<OpacityMap>
+                    .or(resource_changed::<OpacityAccessibilityThreshold>),
+            )
+            .in_set(OpacitySet::Apply),
+    );
+}
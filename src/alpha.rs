@@ -4,22 +4,77 @@ use bevy::color::Alpha;
 pub struct BevyAlphaMarker;
 pub struct F32Marker;
 
-pub trait SetAlpha<M> {
-    fn set_alpha(&mut self, alpha: f32);
+/// Writes opacity to the alpha of a color, used by the built-in targets and the derive macros.
+///
+/// Implemented for every bevy color type like [`Color`](bevy::color::Color),
+/// [`Srgba`](bevy::color::Srgba) and [`LinearRgba`](bevy::color::LinearRgba), and for `f32`.
+/// Implement it with any marker type `M` for color wrappers that do not implement [`Alpha`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::OpacityAlpha;
+/// struct Tint(Srgba);
+///
+/// impl OpacityAlpha<()> for Tint {
+///     fn get_alpha(&self) -> f32 {
+///         self.0.alpha
+///     }
+///
+///     fn replace_alpha(&mut self, alpha: f32) {
+///         self.0.alpha = alpha;
+///     }
+/// }
+///
+/// let mut tint = Tint(Srgba::new(1.0, 1.0, 1.0, 0.5));
+/// let base = tint.get_alpha();
+/// tint.multiply_alpha(base, 0.5);
+/// assert_eq!(tint.get_alpha(), 0.25);
+/// // Applying again from the same base does not compound.
+/// tint.multiply_alpha(base, 0.5);
+/// assert_eq!(tint.get_alpha(), 0.25);
+/// ```
+pub trait OpacityAlpha<M> {
+    /// Returns the alpha value.
+    fn get_alpha(&self) -> f32;
+
+    /// Replace the alpha value with `alpha`.
+    fn replace_alpha(&mut self, alpha: f32);
+
+    /// Set the alpha value to `base * opacity`, keeping authored transparency.
+    ///
+    /// `base` is the unfaded alpha, read once with [`OpacityAlpha::get_alpha`] and cached,
+    /// like the `Base*` components of this crate, since opacity is applied every frame.
+    fn multiply_alpha(&mut self, base: f32, opacity: f32) {
+        self.replace_alpha(base * opacity);
+    }
 }
 
-impl<T: Alpha> SetAlpha<BevyAlphaMarker> for T {
-    fn set_alpha(&mut self, alpha: f32) {
+impl<T: Alpha> OpacityAlpha<BevyAlphaMarker> for T {
+    fn get_alpha(&self) -> f32 {
+        Alpha::alpha(self)
+    }
+
+    fn replace_alpha(&mut self, alpha: f32) {
         Alpha::set_alpha(self, alpha);
     }
 }
 
-impl SetAlpha<F32Marker> for f32 {
-    fn set_alpha(&mut self, alpha: f32) {
+impl OpacityAlpha<F32Marker> for f32 {
+    fn get_alpha(&self) -> f32 {
+        *self
+    }
+
+    fn replace_alpha(&mut self, alpha: f32) {
         *self = alpha;
     }
 }
 
-pub fn set_alpha<T: SetAlpha<A>, A>(item: &mut T, alpha: f32) {
-    item.set_alpha(alpha);
+/// Replace the alpha of a color with `alpha`, see [`OpacityAlpha::replace_alpha`].
+pub fn set_alpha<T: OpacityAlpha<M>, M>(item: &mut T, alpha: f32) {
+    item.replace_alpha(alpha);
+}
+
+/// Set the alpha of a color to `base * opacity`, see [`OpacityAlpha::multiply_alpha`].
+pub fn multiply_alpha<T: OpacityAlpha<M>, M>(item: &mut T, base: f32, opacity: f32) {
+    item.multiply_alpha(base, opacity);
 }
@@ -0,0 +1,91 @@
+use bevy::animation::{
+    animatable::{Animatable, BlendInput},
+    animation_curves::{AnimatableProperty, EvaluatorId},
+    AnimationEntityMut, AnimationEvaluationError,
+};
+use bevy::math::StableInterpolate;
+use std::any::TypeId;
+
+use crate::Opacity;
+
+/// An [`AnimatableProperty`] of the current value of [`Opacity`],
+/// use with `AnimatableCurve` to key opacity in an `AnimationClip`.
+///
+/// Fades in progress continue from the animated value.
+/// To blend the entire state including fades, use [`OpacityStateProperty`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpacityProperty;
+
+impl AnimatableProperty for OpacityProperty {
+    type Property = f32;
+
+    fn get_mut<'a>(
+        &self,
+        entity: &'a mut AnimationEntityMut,
+    ) -> Result<&'a mut Self::Property, AnimationEvaluationError> {
+        let opacity = entity
+            .get_mut::<Opacity>()
+            .ok_or(AnimationEvaluationError::ComponentNotPresent(TypeId::of::<
+                Opacity,
+            >(
+            )))?
+            .into_inner();
+        Ok(&mut opacity.current)
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        EvaluatorId::Type(TypeId::of::<Self>())
+    }
+}
+
+/// An [`AnimatableProperty`] of the entire [`Opacity`] component, including its fade state.
+///
+/// Contributions of multiple animation graph nodes are combined with [`Animatable::blend`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpacityStateProperty;
+
+impl AnimatableProperty for OpacityStateProperty {
+    type Property = Opacity;
+
+    fn get_mut<'a>(
+        &self,
+        entity: &'a mut AnimationEntityMut,
+    ) -> Result<&'a mut Self::Property, AnimationEvaluationError> {
+        entity
+            .get_mut::<Opacity>()
+            .ok_or(AnimationEvaluationError::ComponentNotPresent(TypeId::of::<
+                Opacity,
+            >(
+            )))
+            .map(|x| x.into_inner())
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        EvaluatorId::Type(TypeId::of::<Self>())
+    }
+}
+
+/// Interpolates like [`StableInterpolate`].
+impl Animatable for Opacity {
+    fn interpolate(a: &Self, b: &Self, time: f32) -> Self {
+        a.interpolate_stable(b, time)
+    }
+
+    fn blend(inputs: impl Iterator<Item = BlendInput<Self>>) -> Self {
+        let mut value = Opacity::INVISIBLE;
+        for input in inputs {
+            if input.additive {
+                value.current += input.weight * input.value.current;
+                value.target += input.weight * input.value.target;
+                value.speed += input.weight * input.value.speed;
+                value.despawns |= input.value.despawns;
+                if input.value.motion != Default::default() {
+                    value.motion = input.value.motion;
+                }
+            } else {
+                value = Self::interpolate(&value, &input.value, input.weight);
+            }
+        }
+        value
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::{
+    app::App,
+    audio::{AudioSink, AudioSinkPlayback, SpatialAudioSink, Volume},
+    ecs::{
+        component::Mutable,
+        query::{Added, Without},
+        schedule::{common_conditions::resource_changed, Condition, IntoScheduleConfigs},
+    },
+    prelude::{Commands, Component, Entity, Query, Res},
+};
+
+use crate::{OpacityIgnore, OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// The unfaded volume of an [`AudioSink`] or [`SpatialAudioSink`].
+///
+/// Inserted automatically the first time opacity is applied,
+/// modify this instead of the sink's volume to change volume.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseVolume(pub Volume);
+
+fn sink_added<S: Component>(added: Query<(), Added<S>>) -> bool {
+    !added.is_empty()
+}
+
+fn fade_volume<S: AudioSinkPlayback + Component<Mutability = Mutable>>(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    mut query: Query<(Entity, &mut S, Option<&BaseVolume>), Without<OpacityIgnore>>,
+) {
+    for (entity, mut sink, base) in &mut query {
+        let Some(opacity) = map.get(entity) else {
+            continue;
+        };
+        let base = match base {
+            Some(base) => base.0,
+            None => {
+                let base = sink.volume();
+                commands.entity(entity).try_insert(BaseVolume(base));
+                base
+            }
+        };
+        sink.set_volume(Volume::Linear(base.to_linear() * opacity));
+    }
+}
+
+pub fn opacity_plugin_audio(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<BaseVolume>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            fade_volume::<AudioSink>
+                .run_if(resource_changed::<OpacityMap>.or(sink_added::<AudioSink>)),
+            fade_volume::<SpatialAudioSink>
+                .run_if(resource_changed::<OpacityMap>.or(sink_added::<SpatialAudioSink>)),
+        )
+            .in_set(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,152 @@
+use bevy::{
+    app::App,
+    asset::{Assets, Handle},
+    ecs::{
+        entity::EntityHashMap,
+        schedule::{common_conditions::resource_exists, IntoScheduleConfigs},
+        world::FromWorld,
+    },
+    prelude::{Component, Entity, Query, RemovedComponents, Res, ResMut, Resource, World},
+    render::storage::ShaderStorageBuffer,
+};
+
+use crate::{OpacityMap, OpacitySet};
+
+/// Marks an entity whose computed opacity is written to [`OpacityBuffer`],
+/// at the index returned by [`GpuOpacity::slot`].
+///
+/// The slot is assigned in [`OpacitySet::Apply`] after insertion and stays the same
+/// until the component is removed, then it can be reused by another entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+pub struct GpuOpacity {
+    slot: Option<u32>,
+}
+
+impl GpuOpacity {
+    /// Returns the index of this entity in [`OpacityBuffer`], `None` until assigned.
+    pub const fn slot(&self) -> Option<u32> {
+        self.slot
+    }
+}
+
+/// Opt-in [`Resource`] holding a storage buffer with the computed opacity of every [`GpuOpacity`] entity,
+/// so instanced and custom shaders can read fade values on the GPU instead of having assets written to.
+///
+/// Entities without a computed opacity read `1.0`. The buffer is only uploaded when a value changed.
+///
+/// Bind [`OpacityBuffer::handle`] in a material and index it with [`GpuOpacity::slot`],
+/// passed for example through instance data or a material uniform:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::{render_resource::AsBindGroup, storage::ShaderStorageBuffer};
+/// # use bevy_mod_opacity::OpacityBuffer;
+/// // @group(2) @binding(0) var<storage, read> opacities: array<f32>;
+/// // @group(2) @binding(1) var<uniform> slot: u32;
+/// #[derive(Asset, TypePath, AsBindGroup, Clone)]
+/// struct GrassMaterial {
+///     #[storage(0, read_only)]
+///     opacities: Handle<ShaderStorageBuffer>,
+///     #[uniform(1)]
+///     slot: u32,
+/// }
+///
+/// fn setup(app: &mut App) {
+///     app.init_resource::<OpacityBuffer>();
+/// }
+///
+/// fn material(buffer: &OpacityBuffer, slot: u32) -> GrassMaterial {
+///     GrassMaterial {
+///         opacities: buffer.handle().clone(),
+///         slot,
+///     }
+/// }
+/// ```
+#[derive(Debug, Resource)]
+pub struct OpacityBuffer {
+    handle: Handle<ShaderStorageBuffer>,
+    values: Vec<f32>,
+    slots: EntityHashMap<u32>,
+    free: Vec<u32>,
+}
+
+impl OpacityBuffer {
+    /// Creates the buffer as a new asset.
+    pub fn new(buffers: &mut Assets<ShaderStorageBuffer>) -> Self {
+        OpacityBuffer {
+            // Storage buffers cannot be empty.
+            handle: buffers.add(ShaderStorageBuffer::from(vec![1.0f32])),
+            values: Vec::new(),
+            slots: EntityHashMap::default(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Returns the handle of the storage buffer, an `array<f32>` indexed by [`GpuOpacity::slot`].
+    pub fn handle(&self) -> &Handle<ShaderStorageBuffer> {
+        &self.handle
+    }
+
+    /// Returns the value written for a slot.
+    pub fn get(&self, slot: u32) -> Option<f32> {
+        self.values.get(slot as usize).copied()
+    }
+}
+
+impl FromWorld for OpacityBuffer {
+    fn from_world(world: &mut World) -> Self {
+        OpacityBuffer::new(&mut world.resource_mut::<Assets<ShaderStorageBuffer>>())
+    }
+}
+
+fn write_opacity_buffer(
+    map: Res<OpacityMap>,
+    mut buffer: ResMut<OpacityBuffer>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    mut entities: Query<(Entity, &mut GpuOpacity)>,
+    mut removed: RemovedComponents<GpuOpacity>,
+) {
+    let buffer = buffer.as_mut();
+    for entity in removed.read() {
+        if let Some(slot) = buffer.slots.remove(&entity) {
+            buffer.free.push(slot);
+        }
+    }
+    let mut changed = false;
+    for (entity, mut gpu) in &mut entities {
+        let slot = match gpu.slot {
+            Some(slot) => slot,
+            None => {
+                let slot = buffer.free.pop().unwrap_or_else(|| {
+                    buffer.values.push(1.0);
+                    buffer.values.len() as u32 - 1
+                });
+                buffer.slots.insert(entity, slot);
+                gpu.slot = Some(slot);
+                changed = true;
+                slot
+            }
+        };
+        let opacity = map.get(entity).unwrap_or(1.0);
+        let value = &mut buffer.values[slot as usize];
+        if *value != opacity {
+            *value = opacity;
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+    if let Some(gpu) = buffers.get_mut(&buffer.handle) {
+        gpu.set_data(buffer.values.clone());
+    }
+}
+
+pub fn opacity_plugin_buffer(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        write_opacity_buffer
+            .run_if(resource_exists::<OpacityBuffer>)
+            .in_set(OpacitySet::Apply),
+    );
+}
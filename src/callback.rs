@@ -0,0 +1,111 @@
+use bevy::{
+    app::App,
+    ecs::{
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+        system::SystemId,
+    },
+    prelude::{Commands, Component, Entity, EntityWorldMut, In, Query, Res},
+};
+use std::fmt::{self, Debug};
+
+use crate::{Opacity, OpacityConfig, OpacitySet};
+
+enum FadeAction {
+    System(SystemId<In<Entity>>),
+    Command(Box<dyn FnOnce(EntityWorldMut) + Send + Sync>),
+}
+
+/// Runs an action once when the [`Opacity`] of this entity reaches `target`,
+/// before the entity is despawned by [`Opacity::fade_out`].
+///
+/// Removed without running when the fade is retargeted.
+/// Usually inserted by [`OpacityCommandsExt::fade_out_then`](crate::OpacityCommandsExt::fade_out_then).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{OnFadeComplete, Opacity};
+/// fn close(mut commands: Commands, window: Entity) {
+///     let mut opacity = Opacity::OPAQUE;
+///     opacity.interpolate_to(0.0, 0.3);
+///     commands.entity(window).insert((
+///         opacity,
+///         OnFadeComplete::command(0.0, |mut entity: EntityWorldMut| {
+///             entity.insert(Visibility::Hidden);
+///         }),
+///     ));
+/// }
+/// ```
+#[derive(Component)]
+pub struct OnFadeComplete {
+    target: f32,
+    action: Option<FadeAction>,
+}
+
+impl OnFadeComplete {
+    /// Run a registered system with this entity as input when the fade reaches `target`.
+    pub fn system(target: f32, system: SystemId<In<Entity>>) -> Self {
+        OnFadeComplete {
+            target,
+            action: Some(FadeAction::System(system)),
+        }
+    }
+
+    /// Run a command on this entity when the fade reaches `target`.
+    pub fn command(
+        target: f32,
+        command: impl FnOnce(EntityWorldMut) + Send + Sync + 'static,
+    ) -> Self {
+        OnFadeComplete {
+            target,
+            action: Some(FadeAction::Command(Box::new(command))),
+        }
+    }
+
+    /// Returns the opacity that triggers the action.
+    pub const fn target(&self) -> f32 {
+        self.target
+    }
+}
+
+impl Debug for OnFadeComplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnFadeComplete")
+            .field("target", &self.target)
+            .finish_non_exhaustive()
+    }
+}
+
+fn run_fade_callbacks(
+    mut commands: Commands,
+    config: Res<OpacityConfig>,
+    mut query: Query<(Entity, &Opacity, &mut OnFadeComplete)>,
+) {
+    for (entity, opacity, mut callback) in &mut query {
+        if opacity.target != callback.target {
+            commands.entity(entity).try_remove::<OnFadeComplete>();
+        } else if opacity.current == opacity.target {
+            commands.entity(entity).try_remove::<OnFadeComplete>();
+            match callback.action.take() {
+                Some(FadeAction::System(system)) => commands.run_system_with(system, entity),
+                Some(FadeAction::Command(command)) => {
+                    commands.entity(entity).queue(command);
+                }
+                None => (),
+            }
+        }
+        // Fades with a callback are not despawned by interpolation, so the action sees the entity.
+        if opacity.despawns && opacity.current <= 0.0 && config.despawn_on_fade_out {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+pub fn opacity_plugin_callback(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        run_fade_callbacks
+            .run_if(any_with_component::<OnFadeComplete>)
+            .after(OpacitySet::Fading)
+            .before(OpacitySet::PostFade),
+    );
+}
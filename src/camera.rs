@@ -0,0 +1,75 @@
+use bevy::{
+    app::App,
+    ecs::{query::QueryData, system::SystemParam},
+    prelude::{Commands, Component, Entity},
+    render::view::ColorGrading,
+};
+
+use crate::{OpacityExtension, OpacityQuery};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Fades the entire output of a camera to black by lowering the post-exposure of its [`ColorGrading`],
+/// driven by the computed opacity of the camera entity.
+///
+/// Much cheaper than fading every material in the scene and unaffected by [`AlphaMode`](bevy::render::alpha::AlphaMode).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{CameraFade, Opacity};
+/// # fn f(mut commands: Commands) {
+/// commands.spawn((Camera3d::default(), CameraFade, Opacity::new(0.0)));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+#[require(ColorGrading)]
+pub struct CameraFade;
+
+/// The unfaded post-exposure of a camera with [`CameraFade`].
+///
+/// Inserted automatically the first time opacity is applied,
+/// modify this instead of [`ColorGrading`] to change exposure.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseExposure(pub f32);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct CameraFadeQuery {
+    pub entity: Entity,
+    pub fade: &'static CameraFade,
+    pub grading: &'static mut ColorGrading,
+    pub base: Option<&'static BaseExposure>,
+}
+
+impl OpacityQuery for CameraFadeQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = this.grading.global.exposure;
+                cx.entity(this.entity).try_insert(BaseExposure(base));
+                base
+            }
+        };
+        // Exposure is in stops, so scaling brightness by `opacity` is an offset of `log2(opacity)`.
+        this.grading.global.exposure = base + opacity.max(f32::MIN_POSITIVE).log2();
+    }
+}
+
+pub fn opacity_plugin_camera(app: &mut App) {
+    app.register_opacity::<CameraFadeQuery>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<CameraFade>()
+        .register_type::<BaseExposure>();
+}
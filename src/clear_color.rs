@@ -0,0 +1,152 @@
+use bevy::{
+    app::App,
+    color::{Color, Mix},
+    ecs::{
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+        system::Command,
+    },
+    prelude::{
+        Camera, ClearColor, ClearColorConfig, Commands, Component, Entity, Event, EventWriter,
+        Query, ResMut, World,
+    },
+};
+
+use crate::{Opacity, OpacitySet};
+
+/// A [`Command`] that fades the [`ClearColor`] resource to a color,
+/// or the clear color of a single camera with [`FadeClearColor::for_camera`].
+///
+/// The fade is driven by an [`Opacity`] on a helper entity, so it follows
+/// [`OpacityTimeScale`](crate::OpacityTimeScale) like any other fade.
+/// A new fade of the same clear color starts from the current color and replaces the previous one.
+/// [`ClearColorFadeFinished`] is sent when the fade completes.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::FadeClearColor;
+/// fn nightfall(mut commands: Commands) {
+///     commands.queue(FadeClearColor::new(Color::srgb(0.02, 0.02, 0.1), 5.0));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FadeClearColor {
+    color: Color,
+    time: f32,
+    camera: Option<Entity>,
+}
+
+impl FadeClearColor {
+    /// Fade the [`ClearColor`] resource to `color` in `time` seconds.
+    pub const fn new(color: Color, time: f32) -> Self {
+        FadeClearColor {
+            color,
+            time,
+            camera: None,
+        }
+    }
+
+    /// Fade the clear color of a single camera instead,
+    /// cameras using [`ClearColorConfig::Default`] start from the [`ClearColor`] resource.
+    pub const fn for_camera(mut self, camera: Entity) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+}
+
+/// Sent when a [`FadeClearColor`] completes.
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct ClearColorFadeFinished {
+    /// The camera faded with [`FadeClearColor::for_camera`], `None` if [`ClearColor`] was faded.
+    pub camera: Option<Entity>,
+    pub color: Color,
+}
+
+/// Drives an ongoing [`FadeClearColor`] by the opacity of its entity.
+#[derive(Debug, Component)]
+struct ClearColorDriver {
+    camera: Option<Entity>,
+    from: Color,
+    to: Color,
+}
+
+impl Command for FadeClearColor {
+    fn apply(self, world: &mut World) {
+        let resource = world
+            .get_resource::<ClearColor>()
+            .map_or(Color::default(), |color| color.0);
+        let from = match self.camera {
+            Some(camera) => match world
+                .get::<Camera>(camera)
+                .map(|camera| &camera.clear_color)
+            {
+                Some(ClearColorConfig::Custom(color)) => *color,
+                Some(ClearColorConfig::Default) => resource,
+                Some(ClearColorConfig::None) | None => return,
+            },
+            None => resource,
+        };
+        let mut drivers = world.query::<(Entity, &ClearColorDriver)>();
+        let replaced: Vec<_> = drivers
+            .iter(world)
+            .filter(|(_, driver)| driver.camera == self.camera)
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in replaced {
+            world.despawn(entity);
+        }
+        world.spawn((
+            ClearColorDriver {
+                camera: self.camera,
+                from,
+                to: self.color,
+            },
+            Opacity::new_fade_in(self.time),
+        ));
+    }
+}
+
+fn drive_clear_color(
+    mut commands: Commands,
+    drivers: Query<(Entity, &Opacity, &ClearColorDriver)>,
+    mut clear_color: Option<ResMut<ClearColor>>,
+    mut cameras: Query<&mut Camera>,
+    mut events: EventWriter<ClearColorFadeFinished>,
+) {
+    for (entity, opacity, driver) in &drivers {
+        let finished = opacity.current == opacity.target;
+        let color = match finished {
+            true => driver.to,
+            false => driver.from.mix(&driver.to, opacity.current),
+        };
+        match driver.camera {
+            Some(camera) => {
+                if let Ok(mut camera) = cameras.get_mut(camera) {
+                    camera.clear_color = ClearColorConfig::Custom(color);
+                }
+            }
+            None => {
+                if let Some(clear_color) = clear_color.as_mut() {
+                    clear_color.0 = color;
+                }
+            }
+        }
+        if finished {
+            events.write(ClearColorFadeFinished {
+                camera: driver.camera,
+                color,
+            });
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+pub fn opacity_plugin_clear_color(app: &mut App) {
+    app.add_event::<ClearColorFadeFinished>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        drive_clear_color
+            .run_if(any_with_component::<ClearColorDriver>)
+            .after(OpacitySet::Fading)
+            .before(OpacitySet::PostFade),
+    );
+}
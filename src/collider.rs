@@ -0,0 +1,68 @@
+use bevy::{
+    app::App,
+    ecs::schedule::{common_conditions::resource_changed, Condition, IntoScheduleConfigs},
+    prelude::{Commands, Component, Entity, Query, Res, Resource, With, Without},
+};
+use std::marker::PhantomData;
+
+use crate::{OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectResource,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Colliders of entities with computed opacity less than or equal to this value are disabled.
+///
+/// Defaults to `0.0`. An entity faded out with [`Opacity::fade_out`](crate::Opacity::fade_out)
+/// is despawned when it reaches `0.0`, raise the threshold to disable its collider while it fades.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityColliderThreshold(pub f32);
+
+/// Marks a disabled collider `D` inserted by this crate, so only those are enabled again.
+#[derive(Debug, Component)]
+struct FadedCollider<D>(PhantomData<D>);
+
+fn update_colliders<C: Component, D: Component + Default>(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    threshold: Res<OpacityColliderThreshold>,
+    colliders: Query<(), (With<C>, Without<D>)>,
+    disabled: Query<Entity, With<FadedCollider<D>>>,
+) {
+    for entity in &disabled {
+        if map
+            .get(entity)
+            .is_some_and(|opacity| opacity <= threshold.0)
+        {
+            continue;
+        }
+        commands
+            .entity(entity)
+            .try_remove::<(D, FadedCollider<D>)>();
+    }
+    for (entity, opacity) in map.iter() {
+        if opacity > threshold.0 {
+            continue;
+        }
+        if colliders.contains(entity) {
+            commands
+                .entity(entity)
+                .try_insert((D::default(), FadedCollider::<D>(PhantomData)));
+        }
+    }
+}
+
+/// Disables colliders `C` by inserting `D` below [`OpacityColliderThreshold`].
+pub fn opacity_plugin_colliders<C: Component, D: Component + Default>(app: &mut App) {
+    app.init_resource::<OpacityColliderThreshold>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityColliderThreshold>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        update_colliders::<C, D>
+            .run_if(resource_changed::<OpacityMap>.or(resource_changed::<OpacityColliderThreshold>))
+            .in_set(OpacitySet::Apply),
+    );
+}
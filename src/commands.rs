@@ -0,0 +1,205 @@
+use bevy::{
+    ecs::system::{EntityCommands, SystemId},
+    prelude::{ChildOf, Children, Entity, EntityWorldMut, In, World},
+};
+
+use crate::{
+    future::{FadeFuture, FadeWaiter},
+    DefaultFadeDurations, OnFadeComplete, Opacity, OpacityInherit, OpacityRoot, OpacitySnapshot,
+    OpacitySnapshots,
+};
+
+/// Extensions for [`EntityCommands`].
+///
+/// In subtree fades, descendants that do not inherit opacity from this entity,
+/// because of [`OpacityRoot`], [`OpacityInherit::Isolated`] or a parent with [`OpacityInherit::Skip`],
+/// are faded as well, [`Opacity`] is inserted on them if missing.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, OpacityCommandsExt};
+/// fn close_window(mut commands: Commands, window: Entity) {
+///     commands.entity(window).fade_out_subtree(0.3);
+/// }
+///
+/// fn start_cutscene(mut commands: Commands, hud: Entity) {
+///     commands.entity(hud).snapshot_opacity().insert(Opacity::INVISIBLE);
+/// }
+///
+/// fn end_cutscene(mut commands: Commands, hud: Entity) {
+///     commands.entity(hud).restore_opacity();
+/// }
+/// ```
+pub trait OpacityCommandsExt {
+    /// Interpolate this entity and its nested roots to `1.0`.
+    fn fade_in_subtree(&mut self, time: f32) -> &mut Self;
+    /// Fade out and despawn this entity, its nested roots are interpolated to `0.0`.
+    fn fade_out_subtree(&mut self, time: f32) -> &mut Self;
+    /// Store an [`OpacitySnapshot`] of this entity and its descendants in [`OpacitySnapshots`].
+    fn snapshot_opacity(&mut self) -> &mut Self;
+    /// Restore and remove the snapshot taken by [`snapshot_opacity`](Self::snapshot_opacity).
+    fn restore_opacity(&mut self) -> &mut Self;
+    /// Interpolate this entity to `opacity`, returns a [`FadeFuture`] resolved when the fade ends.
+    fn fade_to_async(&mut self, opacity: f32, time: f32) -> FadeFuture;
+    /// Fade out and despawn this entity, returns a [`FadeFuture`] resolved when the fade ends.
+    fn fade_out_async(&mut self, time: f32) -> FadeFuture;
+    /// Interpolate this entity to `opacity`, then run `system` with this entity as input.
+    fn fade_to_then(&mut self, opacity: f32, time: f32, system: SystemId<In<Entity>>) -> &mut Self;
+    /// Fade out this entity, then run `system` with this entity as input before it is despawned.
+    fn fade_out_then(&mut self, time: f32, system: SystemId<In<Entity>>) -> &mut Self;
+    /// Interpolate this entity to `1.0` in [`DefaultFadeDurations::show`] seconds,
+    /// [`Opacity`] is inserted if missing.
+    fn show(&mut self) -> &mut Self;
+    /// Interpolate this entity to `0.0` in [`DefaultFadeDurations::hide`] seconds without despawning it.
+    fn hide(&mut self) -> &mut Self;
+}
+
+impl OpacityCommandsExt for EntityCommands<'_> {
+    fn fade_in_subtree(&mut self, time: f32) -> &mut Self {
+        self.queue(move |entity: EntityWorldMut| {
+            fade_subtree(entity, |_, opacity| opacity.fade_in(time))
+        })
+    }
+
+    fn fade_out_subtree(&mut self, time: f32) -> &mut Self {
+        self.queue(move |entity: EntityWorldMut| {
+            let root = entity.id();
+            fade_subtree(entity, |entity, opacity| {
+                if entity == root {
+                    opacity.fade_out(time);
+                } else {
+                    opacity.interpolate_to(0.0, time);
+                }
+            })
+        })
+    }
+
+    fn snapshot_opacity(&mut self) -> &mut Self {
+        self.queue(|mut entity: EntityWorldMut| {
+            let root = entity.id();
+            entity.world_scope(|world| {
+                let snapshot = OpacitySnapshot::take(world, root);
+                world
+                    .get_resource_or_init::<OpacitySnapshots>()
+                    .0
+                    .insert(root, snapshot);
+            });
+        })
+    }
+
+    fn restore_opacity(&mut self) -> &mut Self {
+        self.queue(|mut entity: EntityWorldMut| {
+            let root = entity.id();
+            entity.world_scope(|world| {
+                let snapshot = world
+                    .get_resource_mut::<OpacitySnapshots>()
+                    .and_then(|mut snapshots| snapshots.0.remove(&root));
+                if let Some(snapshot) = snapshot {
+                    snapshot.restore(world);
+                }
+            });
+        })
+    }
+
+    fn fade_to_async(&mut self, opacity: f32, time: f32) -> FadeFuture {
+        let (waiter, future) = FadeWaiter::new(opacity);
+        self.queue(move |mut entity: EntityWorldMut| {
+            let mut current = entity.get::<Opacity>().copied().unwrap_or_default();
+            current.interpolate_to(opacity, time);
+            entity.insert((current, waiter));
+        });
+        future
+    }
+
+    fn fade_out_async(&mut self, time: f32) -> FadeFuture {
+        let (waiter, future) = FadeWaiter::new(0.0);
+        self.queue(move |mut entity: EntityWorldMut| {
+            let mut current = entity.get::<Opacity>().copied().unwrap_or_default();
+            current.fade_out(time);
+            entity.insert((current, waiter));
+        });
+        future
+    }
+
+    fn fade_to_then(&mut self, opacity: f32, time: f32, system: SystemId<In<Entity>>) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let mut current = entity.get::<Opacity>().copied().unwrap_or_default();
+            current.interpolate_to(opacity, time);
+            entity.insert((current, OnFadeComplete::system(opacity, system)));
+        })
+    }
+
+    fn fade_out_then(&mut self, time: f32, system: SystemId<In<Entity>>) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let mut current = entity.get::<Opacity>().copied().unwrap_or_default();
+            current.fade_out(time);
+            entity.insert((current, OnFadeComplete::system(0.0, system)));
+        })
+    }
+
+    fn show(&mut self) -> &mut Self {
+        self.queue(|entity: EntityWorldMut| {
+            fade_with_default(entity, 1.0, |durations| durations.show)
+        })
+    }
+
+    fn hide(&mut self) -> &mut Self {
+        self.queue(|entity: EntityWorldMut| {
+            fade_with_default(entity, 0.0, |durations| durations.hide)
+        })
+    }
+}
+
+/// Interpolate an entity to `opacity` in a duration of [`DefaultFadeDurations`].
+fn fade_with_default(
+    mut entity: EntityWorldMut,
+    opacity: f32,
+    time: impl Fn(&DefaultFadeDurations) -> f32,
+) {
+    let durations = entity
+        .world()
+        .get_resource::<DefaultFadeDurations>()
+        .copied()
+        .unwrap_or_default();
+    let mut current = entity.get::<Opacity>().copied().unwrap_or_default();
+    current.interpolate_to(opacity, time(&durations));
+    entity.insert(current);
+}
+
+/// Returns true if this entity does not inherit opacity from its parent.
+fn is_nested_root(world: &World, entity: Entity) -> bool {
+    if world.get::<OpacityRoot>(entity).is_some()
+        || world.get::<OpacityInherit>(entity) == Some(&OpacityInherit::Isolated)
+    {
+        return true;
+    }
+    world
+        .get::<ChildOf>(entity)
+        .and_then(|parent| world.get::<OpacityInherit>(parent.parent()))
+        == Some(&OpacityInherit::Skip)
+}
+
+fn fade_subtree(mut entity: EntityWorldMut, fade: impl Fn(Entity, &mut Opacity)) {
+    let root = entity.id();
+    entity.world_scope(|world| {
+        let mut targets = vec![root];
+        let mut stack = vec![root];
+        while let Some(entity) = stack.pop() {
+            let Some(children) = world.get::<Children>(entity) else {
+                continue;
+            };
+            for &child in children {
+                if is_nested_root(world, child) {
+                    targets.push(child);
+                }
+                stack.push(child);
+            }
+        }
+        for target in targets {
+            let mut target = world.entity_mut(target);
+            let mut opacity = target.get::<Opacity>().copied().unwrap_or_default();
+            fade(target.id(), &mut opacity);
+            target.insert(opacity);
+        }
+    });
+}
@@ -0,0 +1,190 @@
+use bevy::{
+    app::App,
+    ecs::{
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+        system::Command,
+    },
+    prelude::{
+        Commands, Component, Entity, Event, EventWriter, Query, Res, Visibility, With, World,
+    },
+};
+
+use crate::{FadePaused, Opacity, OpacityConfig, OpacitySet};
+
+/// A [`Command`] that fades out one tree while fading in another,
+/// [`CrossfadeFinished`] is sent on completion.
+///
+/// Both fades stay synchronized, pausing either entity with [`FadePaused`] pauses both,
+/// and both complete on the same frame.
+///
+/// By default `from` is despawned when the fade completes,
+/// call [`Crossfade::hide`] to set it to [`Visibility::Hidden`] instead.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::Crossfade;
+/// fn open_settings(mut commands: Commands, menu: Entity, settings: Entity) {
+///     commands.queue(Crossfade::new(menu, settings, 0.3).hide());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crossfade {
+    from: Entity,
+    to: Entity,
+    time: f32,
+    hide: bool,
+}
+
+impl Crossfade {
+    /// Fade out `from` and fade in `to` in `time` seconds.
+    pub const fn new(from: Entity, to: Entity, time: f32) -> Self {
+        Crossfade {
+            from,
+            to,
+            time,
+            hide: false,
+        }
+    }
+
+    /// Hide `from` on completion instead of despawning it.
+    pub const fn hide(mut self) -> Self {
+        self.hide = true;
+        self
+    }
+}
+
+/// Sent when a [`Crossfade`] completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct CrossfadeFinished {
+    pub from: Entity,
+    pub to: Entity,
+}
+
+/// Tracks an ongoing [`Crossfade`] on the entity faded in.
+///
+/// The opacity of `from` follows the progress of the entity faded in,
+/// so both stay synchronized when either is paused or time scaled.
+#[derive(Debug, Component)]
+struct Crossfading {
+    from: Entity,
+    hide: bool,
+    from_start: f32,
+    to_start: f32,
+    progress: f32,
+}
+
+impl Crossfading {
+    fn opacity_at(&self, progress: f32) -> f32 {
+        self.to_start + (1.0 - self.to_start) * progress
+    }
+
+    fn progress_of(&self, opacity: f32) -> f32 {
+        if self.to_start >= 1.0 {
+            return 1.0;
+        }
+        ((opacity - self.to_start) / (1.0 - self.to_start)).clamp(0.0, 1.0)
+    }
+}
+
+impl Command for Crossfade {
+    fn apply(self, world: &mut World) {
+        let mut from_start = 0.0;
+        if let Ok(mut from) = world.get_entity_mut(self.from) {
+            from_start = from.get::<Opacity>().map(Opacity::get).unwrap_or(1.0);
+            // A reversed crossfade must not hide the entity faded in.
+            from.insert(Opacity::new(from_start))
+                .remove::<Crossfading>();
+        }
+        let Ok(mut to) = world.get_entity_mut(self.to) else {
+            return;
+        };
+        let mut opacity = to.get::<Opacity>().copied().unwrap_or(Opacity::INVISIBLE);
+        let to_start = opacity.get();
+        opacity.interpolate_to(1.0, self.time);
+        to.insert((
+            opacity,
+            Crossfading {
+                from: self.from,
+                hide: self.hide,
+                from_start,
+                to_start,
+                progress: 0.0,
+            },
+        ));
+        if let Some(mut visibility) = to.get_mut::<Visibility>() {
+            if *visibility == Visibility::Hidden {
+                *visibility = Visibility::Inherited;
+            }
+        }
+    }
+}
+
+fn sync_crossfade(
+    mut query: Query<(Entity, &mut Crossfading)>,
+    mut opacities: Query<&mut Opacity>,
+    paused: Query<(), With<FadePaused>>,
+) {
+    for (entity, mut crossfading) in &mut query {
+        let Ok(mut to) = opacities.get_mut(entity) else {
+            continue;
+        };
+        // Pausing either side pauses both.
+        if paused.contains(entity) || paused.contains(crossfading.from) {
+            let opacity = crossfading.opacity_at(crossfading.progress);
+            if to.current != opacity {
+                to.current = opacity;
+            }
+        } else {
+            crossfading.progress = crossfading.progress_of(to.current);
+        }
+        if let Ok(mut from) = opacities.get_mut(crossfading.from) {
+            let opacity = crossfading.from_start * (1.0 - crossfading.progress);
+            if from.current != opacity {
+                from.set(opacity);
+            }
+        }
+    }
+}
+
+fn finish_crossfade(
+    mut commands: Commands,
+    config: Res<OpacityConfig>,
+    query: Query<(Entity, &Opacity, &Crossfading)>,
+    mut visibility: Query<&mut Visibility>,
+    mut events: EventWriter<CrossfadeFinished>,
+) {
+    for (entity, opacity, crossfading) in &query {
+        if opacity.current != opacity.target {
+            continue;
+        }
+        if crossfading.hide {
+            if let Ok(mut visibility) = visibility.get_mut(crossfading.from) {
+                *visibility = Visibility::Hidden;
+            }
+        } else if config.despawn_on_fade_out {
+            commands.entity(crossfading.from).try_despawn();
+        }
+        commands.entity(entity).try_remove::<Crossfading>();
+        events.write(CrossfadeFinished {
+            from: crossfading.from,
+            to: entity,
+        });
+    }
+}
+
+pub fn opacity_plugin_crossfade(app: &mut App) {
+    app.add_event::<CrossfadeFinished>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            sync_crossfade
+                .run_if(any_with_component::<Crossfading>)
+                .after(crate::Interpolation)
+                .in_set(OpacitySet::Fading),
+            finish_crossfade
+                .run_if(any_with_component::<Crossfading>)
+                .after(OpacitySet::Fading)
+                .before(OpacitySet::PostFade),
+        ),
+    );
+}
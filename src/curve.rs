@@ -0,0 +1,174 @@
+use bevy::{
+    app::App,
+    asset::{io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets, Handle, LoadContext},
+    ecs::{
+        query::QueryFilter,
+        schedule::{
+            common_conditions::{any_with_component, resource_exists},
+            Condition, IntoScheduleConfigs,
+        },
+    },
+    prelude::{Commands, Component, Entity, Query, Res, With, Without},
+    reflect::TypePath,
+    time::{Real, Time},
+};
+use serde::Deserialize;
+use std::fmt::{self, Display};
+
+use crate::{FadePaused, FadeUnscaled, Opacity, OpacitySet, OpacityTimeScale, ReducedMotion};
+
+/// An [`Asset`] of keyframed opacity over time, loaded from `.opacity.ron` files.
+///
+/// Opacity is linearly interpolated between keyframes of `(seconds, opacity)`,
+/// keyframes must be sorted by time.
+///
+/// ```text
+/// (keyframes: [(0.0, 0.0), (0.2, 1.0), (2.0, 1.0), (2.5, 0.0)])
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Asset, TypePath, Deserialize)]
+pub struct OpacityCurve {
+    pub keyframes: Vec<(f32, f32)>,
+}
+
+impl OpacityCurve {
+    /// Returns the time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|(time, _)| *time).unwrap_or(0.0)
+    }
+
+    /// Returns the opacity at a point in time, `1.0` if there are no keyframes.
+    pub fn sample(&self, time: f32) -> f32 {
+        let index = self.keyframes.partition_point(|(t, _)| *t <= time);
+        match (
+            index.checked_sub(1).map(|i| self.keyframes[i]),
+            self.keyframes.get(index).copied(),
+        ) {
+            (None, None) => 1.0,
+            (Some((_, opacity)), None) | (None, Some((_, opacity))) => opacity,
+            (Some((t0, o0)), Some((t1, o1))) => o0 + (o1 - o0) * (time - t0) / (t1 - t0),
+        }
+    }
+}
+
+/// Plays an [`OpacityCurve`] on this entity's [`Opacity`], removed when the curve ends.
+///
+/// The curve is sampled every frame, so edits to the asset apply to fades in progress.
+/// Like other fades, curves follow the clock of [`OpacityPlugin`](crate::OpacityPlugin),
+/// [`FadeUnscaled`], [`OpacityTimeScale`] and [`ReducedMotion`].
+#[derive(Debug, Clone, PartialEq, Component)]
+#[require(Opacity)]
+pub struct FadeCurve {
+    pub curve: Handle<OpacityCurve>,
+    /// Time in seconds since the curve started.
+    pub elapsed: f32,
+}
+
+impl FadeCurve {
+    /// Play a curve from the start.
+    pub const fn new(curve: Handle<OpacityCurve>) -> Self {
+        FadeCurve {
+            curve,
+            elapsed: 0.0,
+        }
+    }
+}
+
+/// Error loading an [`OpacityCurve`].
+#[derive(Debug)]
+pub enum OpacityCurveError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl Display for OpacityCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpacityCurveError::Io(e) => write!(f, "Could not read opacity curve: {e}"),
+            OpacityCurveError::Ron(e) => write!(f, "Could not parse opacity curve: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for OpacityCurveError {}
+
+/// [`AssetLoader`] of [`OpacityCurve`]s.
+#[derive(Debug, Default)]
+pub struct OpacityCurveLoader;
+
+impl AssetLoader for OpacityCurveLoader {
+    type Asset = OpacityCurve;
+    type Settings = ();
+    type Error = OpacityCurveError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &(),
+        _: &mut LoadContext<'_>,
+    ) -> Result<OpacityCurve, OpacityCurveError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(OpacityCurveError::Io)?;
+        ron::de::from_bytes(&bytes).map_err(OpacityCurveError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["opacity.ron"]
+    }
+}
+
+fn play_curves<C: Default + Send + Sync + 'static, F: QueryFilter>(
+    mut commands: Commands,
+    time: Res<Time<C>>,
+    scale: Res<OpacityTimeScale>,
+    reduced_motion: Res<ReducedMotion>,
+    curves: Res<Assets<OpacityCurve>>,
+    mut query: Query<(Entity, &mut FadeCurve, &mut Opacity), (F, Without<FadePaused>)>,
+) {
+    let dt = scale.scale_delta(time.delta_secs());
+    for (entity, mut fade, mut opacity) in &mut query {
+        // Wait for the curve to load.
+        let Some(curve) = curves.get(&fade.curve) else {
+            continue;
+        };
+        let duration = curve.duration();
+        // Play the curve as fast as reduced motion requires of a fade of the same length.
+        let speedup = (reduced_motion.speed(1.0 / duration) * duration).max(1.0);
+        if speedup.is_infinite() {
+            fade.elapsed = fade.elapsed.max(duration);
+        } else {
+            fade.elapsed += dt * speedup;
+        }
+        opacity.set(curve.sample(fade.elapsed));
+        if fade.elapsed >= duration {
+            commands.entity(entity).try_remove::<FadeCurve>();
+        }
+    }
+}
+
+/// Play [`FadeCurve`]s with the clock of [`OpacityPlugin`](crate::OpacityPlugin),
+/// or [`Time<Real>`] with [`FadeUnscaled`].
+pub(crate) fn opacity_plugin_curve_clock<C: Default + Send + Sync + 'static>(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            play_curves::<C, Without<FadeUnscaled>>,
+            play_curves::<Real, With<FadeUnscaled>>,
+        )
+            .run_if(any_with_component::<FadeCurve>.and(resource_exists::<Assets<OpacityCurve>>))
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
+
+/// Registers the asset and loader in [`Plugin::finish`](bevy::app::Plugin::finish),
+/// only if `AssetPlugin` was added.
+pub fn opacity_plugin_curve(app: &mut App) {
+    if !app.world().contains_resource::<AssetServer>() {
+        return;
+    }
+    app.init_asset::<OpacityCurve>()
+        .init_asset_loader::<OpacityCurveLoader>();
+}
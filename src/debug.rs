@@ -0,0 +1,77 @@
+use bevy::{
+    app::{App, Last},
+    ecs::{
+        component::Tick, entity::EntityHashMap, query::QueryData, query::QueryFilter,
+        query::QueryState,
+    },
+    log::warn,
+    prelude::{Added, Children, Entity, Local, Mut, Resource, World},
+};
+
+use crate::Opacity;
+
+/// Frames an entity has to get a registered type after [`Opacity`] is inserted,
+/// so scenes and assets spawning children later are not reported.
+const GRACE_FRAMES: u32 = 120;
+
+type TargetCheck = Box<dyn FnMut(&World, Entity) -> bool + Send + Sync>;
+
+/// A check for each registered type, returns true if an entity can be written to.
+#[derive(Default, Resource)]
+struct OpacityTargets(Vec<TargetCheck>);
+
+/// Record a registered type, entities matching `D` and `F` are opacity targets.
+pub(crate) fn register_target<D: QueryData + 'static, F: QueryFilter + 'static>(app: &mut App) {
+    let world = app.world_mut();
+    let mut state = QueryState::<D, F>::new(world);
+    world
+        .get_resource_or_init::<OpacityTargets>()
+        .0
+        .push(Box::new(move |world, entity| {
+            state.update_archetypes(world);
+            state.contains(entity, world, Tick::new(0), world.read_change_tick())
+        }));
+}
+
+fn has_target(world: &World, targets: &mut OpacityTargets, entity: Entity) -> bool {
+    let mut stack = vec![entity];
+    while let Some(entity) = stack.pop() {
+        if targets.0.iter_mut().any(|check| check(world, entity)) {
+            return true;
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children);
+        }
+    }
+    false
+}
+
+/// Warn once for each entity with [`Opacity`] that has no registered type in its subtree.
+fn warn_unsupported_targets(world: &mut World, mut pending: Local<EntityHashMap<u32>>) {
+    let mut added = world.query_filtered::<Entity, Added<Opacity>>();
+    pending.extend(added.iter(world).map(|entity| (entity, GRACE_FRAMES)));
+    if pending.is_empty() {
+        return;
+    }
+    world.resource_scope(|world, mut targets: Mut<OpacityTargets>| {
+        pending.retain(|entity, frames| {
+            if world.get::<Opacity>(*entity).is_none() || has_target(world, &mut targets, *entity) {
+                return false;
+            }
+            *frames = frames.saturating_sub(1);
+            if *frames > 0 {
+                return true;
+            }
+            warn!(
+                "Entity {entity} has `Opacity` but no registered type like `Sprite` in its subtree, \
+                it is not affected by opacity. Register types with `OpacityExtension`."
+            );
+            false
+        });
+    });
+}
+
+pub fn opacity_plugin_debug(app: &mut App) {
+    app.init_resource::<OpacityTargets>();
+    app.add_systems(Last, warn_unsupported_targets);
+}
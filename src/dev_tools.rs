@@ -0,0 +1,151 @@
+use bevy::{
+    app::App,
+    ecs::{query::QueryData, schedule::IntoScheduleConfigs},
+    platform::collections::HashSet,
+    prelude::{
+        Camera, Commands, Component, Entity, GlobalTransform, Has, Name, Query, Res, Resource,
+        Text, Vec2,
+    },
+    text::TextFont,
+    ui::{GlobalZIndex, Node, PositionType, Val},
+};
+
+use crate::{Opacity, OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectResource,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// [`Resource`] that shows a label next to each entity with [`Opacity`],
+/// displaying its local and computed opacity, fade direction and remaining time.
+///
+/// Enabled by default with the `dev_tools` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityDebugOverlay(pub bool);
+
+impl Default for OpacityDebugOverlay {
+    fn default() -> Self {
+        OpacityDebugOverlay(true)
+    }
+}
+
+/// A label of the debug overlay, describing an entity.
+#[derive(Debug, Component)]
+struct DebugLabel(Entity);
+
+/// An entity described by the debug overlay.
+#[derive(QueryData)]
+struct DebugSource {
+    entity: Entity,
+    opacity: &'static Opacity,
+    transform: &'static GlobalTransform,
+    is_ui: Has<Node>,
+    name: Option<&'static Name>,
+}
+
+impl DebugSourceItem<'_> {
+    /// Returns the position of the label in viewport coordinates.
+    fn position(&self, camera: Option<(&Camera, &GlobalTransform)>) -> Option<Vec2> {
+        if self.is_ui {
+            return Some(self.transform.translation().truncate());
+        }
+        let (camera, camera_transform) = camera?;
+        camera
+            .world_to_viewport(camera_transform, self.transform.translation())
+            .ok()
+    }
+
+    fn describe(&self, map: &OpacityMap) -> String {
+        let opacity = self.opacity;
+        let computed = map.get(self.entity).unwrap_or(opacity.get());
+        let name = match self.name {
+            Some(name) => name.to_string(),
+            None => self.entity.to_string(),
+        };
+        describe(&name, opacity, computed)
+    }
+}
+
+fn describe(name: &str, opacity: &Opacity, computed: f32) -> String {
+    let current = opacity.get();
+    if !opacity.is_fading() {
+        return format!("{name}: {current:.2} (computed {computed:.2})");
+    }
+    let direction = if opacity.get_target() > current {
+        "in"
+    } else {
+        "out"
+    };
+    format!(
+        "{name}: {current:.2} fading {direction} to {:.2} in {:.2}s (computed {computed:.2})",
+        opacity.get_target(),
+        opacity.remaining_time(),
+    )
+}
+
+fn update_overlay(
+    mut commands: Commands,
+    overlay: Res<OpacityDebugOverlay>,
+    map: Res<OpacityMap>,
+    sources: Query<DebugSource>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut labels: Query<(Entity, &DebugLabel, &mut Text, &mut Node)>,
+) {
+    let camera = cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .max_by_key(|(camera, _)| camera.order);
+    let mut labeled = HashSet::new();
+    for (label, DebugLabel(source), mut text, mut node) in &mut labels {
+        let Ok(source) = sources.get(*source) else {
+            commands.entity(label).try_despawn();
+            continue;
+        };
+        if !overlay.0 {
+            commands.entity(label).try_despawn();
+            continue;
+        }
+        labeled.insert(source.entity);
+        let description = source.describe(&map);
+        if text.0 != description {
+            text.0 = description;
+        }
+        if let Some(position) = source.position(camera) {
+            node.left = Val::Px(position.x);
+            node.top = Val::Px(position.y);
+        }
+    }
+    if !overlay.0 {
+        return;
+    }
+    for source in &sources {
+        if labeled.contains(&source.entity) {
+            continue;
+        }
+        let position = source.position(camera).unwrap_or_default();
+        commands.spawn((
+            DebugLabel(source.entity),
+            Text::new(source.describe(&map)),
+            TextFont::from_font_size(12.0),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(position.x),
+                top: Val::Px(position.y),
+                ..Default::default()
+            },
+            GlobalZIndex(i32::MAX - 1),
+        ));
+    }
+}
+
+pub fn opacity_plugin_dev_tools(app: &mut App) {
+    app.init_resource::<OpacityDebugOverlay>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityDebugOverlay>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        update_overlay.after(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,50 @@
+use bevy::{
+    app::App,
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    ecs::{change_detection::DetectChanges, schedule::IntoScheduleConfigs},
+    prelude::{Query, Res},
+};
+
+use crate::{Opacity, OpacityMap, OpacitySet};
+
+/// Paths of the diagnostics registered with the `diagnostics` feature.
+#[derive(Debug)]
+pub struct OpacityDiagnostics;
+
+impl OpacityDiagnostics {
+    /// Number of entities with [`Opacity`].
+    pub const ENTITIES: DiagnosticPath = DiagnosticPath::const_new("opacity/entities");
+    /// Number of entities with [`Opacity`] that are currently fading.
+    pub const FADING: DiagnosticPath = DiagnosticPath::const_new("opacity/fading");
+    /// Number of entities with a computed opacity.
+    pub const MAP_SIZE: DiagnosticPath = DiagnosticPath::const_new("opacity/map_size");
+    /// Number of computed opacities written to targets this frame.
+    pub const APPLIED: DiagnosticPath = DiagnosticPath::const_new("opacity/applied");
+}
+
+fn measure(mut diagnostics: Diagnostics, map: Res<OpacityMap>, query: Query<&Opacity>) {
+    diagnostics.add_measurement(&OpacityDiagnostics::ENTITIES, || query.iter().len() as f64);
+    diagnostics.add_measurement(&OpacityDiagnostics::FADING, || {
+        query.iter().filter(|opacity| opacity.is_fading()).count() as f64
+    });
+    diagnostics.add_measurement(&OpacityDiagnostics::MAP_SIZE, || map.0.len() as f64);
+    // Targets are only written to on frames where the map changed.
+    diagnostics.add_measurement(&OpacityDiagnostics::APPLIED, || {
+        if map.is_changed() {
+            map.0.len() as f64
+        } else {
+            0.0
+        }
+    });
+}
+
+pub fn opacity_plugin_diagnostics(app: &mut App) {
+    app.register_diagnostic(Diagnostic::new(OpacityDiagnostics::ENTITIES))
+        .register_diagnostic(Diagnostic::new(OpacityDiagnostics::FADING))
+        .register_diagnostic(Diagnostic::new(OpacityDiagnostics::MAP_SIZE))
+        .register_diagnostic(Diagnostic::new(OpacityDiagnostics::APPLIED));
+    app.add_systems(
+        crate::opacity_schedule(app),
+        measure.after(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,108 @@
+use bevy::{
+    app::App,
+    ecs::schedule::IntoScheduleConfigs,
+    prelude::{Camera, Component, GlobalTransform, Query, With, Without},
+};
+
+use crate::{Opacity, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Drives [`Opacity`] from the distance to a [`DistanceFadeReference`],
+/// or the active [`Camera`] with the highest order if none exists.
+///
+/// Opacity is `1.0` closer than `start`, `0.0` further than `end`
+/// and interpolated linearly in between.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+#[require(Opacity)]
+pub struct DistanceFade {
+    /// Distance at which fading starts.
+    pub start: f32,
+    /// Distance at which the entity becomes invisible.
+    pub end: f32,
+    /// Time in seconds to fade from `0.0` to `1.0` as the distance changes,
+    /// `0.0` applies changes immediately.
+    pub speed: f32,
+}
+
+impl DistanceFade {
+    /// Creates a distance fade that applies changes immediately.
+    pub const fn new(start: f32, end: f32) -> Self {
+        DistanceFade {
+            start,
+            end,
+            speed: 0.0,
+        }
+    }
+
+    /// Set the time in seconds to fade from `0.0` to `1.0`.
+    pub const fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Returns the target opacity at a distance.
+    pub fn opacity_at(&self, distance: f32) -> f32 {
+        if distance <= self.start {
+            1.0
+        } else if distance >= self.end {
+            0.0
+        } else {
+            1.0 - (distance - self.start) / (self.end - self.start)
+        }
+    }
+}
+
+/// Marker [`Component`] for the entity distance is measured from by [`DistanceFade`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct DistanceFadeReference;
+
+/// Returns the transform of the active [`Camera`] with the highest order.
+pub(crate) fn active_camera<'a>(
+    cameras: &'a Query<(&Camera, &GlobalTransform)>,
+) -> Option<&'a GlobalTransform> {
+    cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .max_by_key(|(camera, _)| camera.order)
+        .map(|(_, transform)| transform)
+}
+
+fn distance_fade(
+    references: Query<&GlobalTransform, With<DistanceFadeReference>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut query: Query<
+        (&DistanceFade, &GlobalTransform, &mut Opacity),
+        Without<DistanceFadeReference>,
+    >,
+) {
+    let reference = references.iter().next().or_else(|| active_camera(&cameras));
+    let Some(reference) = reference.map(GlobalTransform::translation) else {
+        return;
+    };
+    for (fade, transform, mut opacity) in &mut query {
+        let target = fade.opacity_at(transform.translation().distance(reference));
+        if opacity.get_target() == target {
+            continue;
+        }
+        if fade.speed <= 0.0 {
+            opacity.set(target);
+        } else {
+            opacity.interpolate_by_speed(target, fade.speed);
+        }
+    }
+}
+
+pub fn opacity_plugin_distance(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<DistanceFade>()
+        .register_type::<DistanceFadeReference>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        distance_fade
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
@@ -0,0 +1,100 @@
+use bevy::{
+    app::App,
+    ecs::schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+    math::curve::{Curve, EaseFunction},
+    prelude::{Component, Query, With},
+};
+
+use crate::{Motion, Opacity, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Eases every linear fade of this entity's [`Opacity`], like [`Opacity::fade_in`] or [`Opacity::interpolate_to`],
+/// so easing can be defined once per widget instead of on every fade.
+///
+/// The duration of fades is unchanged, fades retargeted midway start from the displayed opacity.
+/// [`Opacity::smooth_to`] and [`Opacity::spring_to`] are not affected.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, OpacityEasing};
+/// fn spawn_panel(mut commands: Commands) {
+///     commands.spawn((
+///         Node::default(),
+///         Opacity::new_fade_in(0.3),
+///         OpacityEasing(EaseFunction::CubicOut),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+#[require(Opacity, EasingState)]
+pub struct OpacityEasing(pub EaseFunction);
+
+/// Progress of the current fade of an entity with [`OpacityEasing`].
+#[derive(Debug, Clone, Copy, Component)]
+struct EasingState {
+    from: f32,
+    to: f32,
+    /// The uneased opacity advanced by interpolation.
+    linear: f32,
+    /// The eased opacity last written.
+    shown: f32,
+}
+
+impl Default for EasingState {
+    fn default() -> Self {
+        EasingState {
+            from: f32::NAN,
+            to: f32::NAN,
+            linear: f32::NAN,
+            shown: f32::NAN,
+        }
+    }
+}
+
+/// Restore the linear opacity before interpolation, or start tracking a new fade.
+fn restore_linear(mut query: Query<(&mut Opacity, &mut EasingState), With<OpacityEasing>>) {
+    for (mut opacity, mut state) in &mut query {
+        if opacity.current != state.shown || opacity.target != state.to {
+            *state = EasingState {
+                from: opacity.current,
+                to: opacity.target,
+                linear: opacity.current,
+                shown: opacity.current,
+            };
+        } else if opacity.is_fading() && opacity.motion == Motion::Linear {
+            opacity.current = state.linear;
+        }
+    }
+}
+
+fn apply_easing(mut query: Query<(&mut Opacity, &mut EasingState, &OpacityEasing)>) {
+    for (mut opacity, mut state, easing) in &mut query {
+        if opacity.motion != Motion::Linear
+            || (!opacity.is_fading() && opacity.current == state.shown)
+        {
+            continue;
+        }
+        state.linear = opacity.current;
+        if state.to != state.from {
+            let t = ((state.linear - state.from) / (state.to - state.from)).clamp(0.0, 1.0);
+            opacity.current = state.from + (state.to - state.from) * easing.0.sample_clamped(t);
+        }
+        state.shown = opacity.current;
+    }
+}
+
+pub fn opacity_plugin_easing(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityEasing>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            restore_linear.before(crate::Interpolation),
+            apply_easing.after(crate::Interpolation),
+        )
+            .run_if(any_with_component::<OpacityEasing>)
+            .in_set(OpacitySet::Fading),
+    );
+}
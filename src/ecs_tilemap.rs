@@ -0,0 +1,58 @@
+use bevy::{
+    app::App,
+    color::Alpha,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::QueryData,
+        system::{Commands, SystemParam},
+    },
+};
+use bevy_ecs_tilemap::tiles::TileColor;
+
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+use crate::{OpacityExtension, OpacityQuery};
+
+/// The unfaded alpha of a [`TileColor`].
+///
+/// Inserted automatically the first time opacity is applied,
+/// modify this to change the alpha of the tile.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseTileAlpha(pub f32);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct TileColorQuery {
+    pub entity: Entity,
+    pub color: &'static mut TileColor,
+    pub base: Option<&'static BaseTileAlpha>,
+}
+
+impl OpacityQuery for TileColorQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = this.color.0.alpha();
+                cx.entity(this.entity).try_insert(BaseTileAlpha(base));
+                base
+            }
+        };
+        this.color.0.set_alpha(base * opacity);
+    }
+}
+
+pub fn opacity_plugin_ecs_tilemap(app: &mut App) {
+    app.register_opacity::<TileColorQuery>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<BaseTileAlpha>();
+}
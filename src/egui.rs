@@ -0,0 +1,103 @@
+use bevy::{
+    app::App,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::QueryData,
+        system::{Commands, SystemParam},
+    },
+};
+use bevy_egui::{
+    egui::{style::WidgetVisuals, Color32, Visuals},
+    EguiContext,
+};
+
+use crate::{OpacityExtension, OpacityQuery};
+
+/// The unfaded [`Visuals`] of an [`EguiContext`].
+///
+/// Inserted automatically the first time opacity is applied,
+/// modify this instead of the style of the context to change its visuals.
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct BaseEguiVisuals(pub Visuals);
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct EguiContextQuery {
+    pub entity: Entity,
+    pub context: &'static mut EguiContext,
+    pub base: Option<&'static BaseEguiVisuals>,
+}
+
+fn fade_widget(widget: &mut WidgetVisuals, opacity: f32) {
+    for color in [
+        &mut widget.bg_fill,
+        &mut widget.weak_bg_fill,
+        &mut widget.bg_stroke.color,
+        &mut widget.fg_stroke.color,
+    ] {
+        *color = color.gamma_multiply(opacity);
+    }
+}
+
+fn fade_visuals(visuals: &mut Visuals, opacity: f32) {
+    let widgets = &mut visuals.widgets;
+    for widget in [
+        &mut widgets.noninteractive,
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        fade_widget(widget, opacity);
+    }
+    let colors: [&mut Color32; 14] = [
+        &mut visuals.selection.bg_fill,
+        &mut visuals.selection.stroke.color,
+        &mut visuals.hyperlink_color,
+        &mut visuals.faint_bg_color,
+        &mut visuals.extreme_bg_color,
+        &mut visuals.code_bg_color,
+        &mut visuals.warn_fg_color,
+        &mut visuals.error_fg_color,
+        &mut visuals.window_shadow.color,
+        &mut visuals.window_fill,
+        &mut visuals.window_stroke.color,
+        &mut visuals.panel_fill,
+        &mut visuals.popup_shadow.color,
+        &mut visuals.text_cursor.stroke.color,
+    ];
+    for color in colors {
+        *color = color.gamma_multiply(opacity);
+    }
+    if let Some(color) = &mut visuals.override_text_color {
+        *color = color.gamma_multiply(opacity);
+    }
+}
+
+impl OpacityQuery for EguiContextQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let ctx = this.context.get_mut();
+        let mut visuals = match this.base {
+            Some(base) => base.0.clone(),
+            None => {
+                let base = ctx.style().visuals.clone();
+                cx.entity(this.entity)
+                    .try_insert(BaseEguiVisuals(base.clone()));
+                base
+            }
+        };
+        fade_visuals(&mut visuals, opacity);
+        ctx.style_mut(|style| style.visuals = visuals);
+    }
+}
+
+pub fn opacity_plugin_egui(app: &mut App) {
+    app.register_opacity::<EguiContextQuery>();
+}
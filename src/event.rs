@@ -0,0 +1,112 @@
+use bevy::{
+    app::App,
+    ecs::{
+        entity::EntityHashMap,
+        schedule::{common_conditions::resource_changed, IntoScheduleConfigs},
+    },
+    platform::collections::hash_map::Entry,
+    prelude::{Entity, Event, EventWriter, Local, Res},
+};
+
+use crate::{OpacityConfig, OpacityMap, OpacitySet};
+
+/// Sent when the computed opacity of an entity changes
+/// by more than [`OpacityConfig::change_epsilon`].
+///
+/// Not sent when an entity enters or leaves [`OpacityMap`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::OpacityChanged;
+/// fn log_changes(mut events: EventReader<OpacityChanged>) {
+///     for event in events.read() {
+///         info!("{} faded from {} to {}", event.entity, event.old, event.new);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct OpacityChanged {
+    pub entity: Entity,
+    pub old: f32,
+    pub new: f32,
+}
+
+/// Sent when the computed opacity of an entity drops to or below
+/// [`OpacityConfig::visibility_threshold`].
+///
+/// Only transitions are sent, so an entity spawned at or below the threshold never
+/// sends this event, check [`OpacityMap`] after spawning instead.
+///
+/// Entities despawned by [`Opacity::fade_out`](crate::Opacity::fade_out) are removed
+/// before their final opacity is observed and do not send this event either,
+/// use [`OpacityCommandsExt::fade_out_then`](crate::OpacityCommandsExt::fade_out_then)
+/// to run a system before the entity is despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct BecameInvisible(pub Entity);
+
+/// Sent when the computed opacity of an entity rises above
+/// [`OpacityConfig::visibility_threshold`].
+///
+/// Like [`BecameInvisible`], not sent for the opacity an entity is spawned with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct BecameVisible(pub Entity);
+
+/// The last sent opacity and visibility of an entity.
+#[derive(Debug, Clone, Copy)]
+struct Sent {
+    opacity: f32,
+    visible: bool,
+}
+
+fn send_changes(
+    map: Res<OpacityMap>,
+    config: Res<OpacityConfig>,
+    mut last: Local<EntityHashMap<Sent>>,
+    mut changes: EventWriter<OpacityChanged>,
+    mut invisible: EventWriter<BecameInvisible>,
+    mut visible: EventWriter<BecameVisible>,
+) {
+    last.retain(|entity, _| map.get(*entity).is_some());
+    for (entity, new) in map.iter() {
+        let is_visible = new > config.visibility_threshold;
+        match last.entry(entity) {
+            Entry::Occupied(mut entry) => {
+                let sent = entry.get_mut();
+                if (new - sent.opacity).abs() > config.change_epsilon {
+                    changes.write(OpacityChanged {
+                        entity,
+                        old: sent.opacity,
+                        new,
+                    });
+                    sent.opacity = new;
+                }
+                if sent.visible != is_visible {
+                    if is_visible {
+                        visible.write(BecameVisible(entity));
+                    } else {
+                        invisible.write(BecameInvisible(entity));
+                    }
+                    sent.visible = is_visible;
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Sent {
+                    opacity: new,
+                    visible: is_visible,
+                });
+            }
+        }
+    }
+}
+
+pub fn opacity_plugin_event(app: &mut App) {
+    app.add_event::<OpacityChanged>()
+        .add_event::<BecameInvisible>()
+        .add_event::<BecameVisible>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        send_changes
+            .run_if(resource_changed::<OpacityMap>)
+            .in_set(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,135 @@
+use bevy::{
+    app::App,
+    prelude::{Component, Entity, Resource},
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        sync_world::{MainEntity, MainEntityHashMap},
+    },
+};
+
+use crate::OpacityMap;
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// The computed opacity of every entity in [`OpacityMap`], extracted to the render world.
+///
+/// Custom render pipelines and instanced renderers can read this during extraction or preparation
+/// to fade on the GPU instead of having their main world components or assets written to.
+/// Combine with [`OpacityPlugin::without_builtin`](crate::OpacityPlugin::without_builtin)
+/// if nothing in the main world needs to be written.
+///
+/// Only extracted on frames where [`OpacityMap`] changed.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::sync_world::MainEntity;
+/// # use bevy_mod_opacity::RenderOpacityMap;
+/// #[derive(Component)]
+/// struct InstanceOpacity(f32);
+///
+/// fn prepare_instances(
+///     mut instances: Query<(&MainEntity, &mut InstanceOpacity)>,
+///     opacity: Res<RenderOpacityMap>,
+/// ) {
+///     for (main_entity, mut instance) in &mut instances {
+///         instance.0 = opacity.get(*main_entity).unwrap_or(1.0);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Resource)]
+pub struct RenderOpacityMap(MainEntityHashMap<f32>);
+
+impl RenderOpacityMap {
+    /// Returns the computed opacity of a main world entity.
+    pub fn get(&self, entity: impl Into<MainEntity>) -> Option<f32> {
+        self.0.get(&entity.into()).copied()
+    }
+
+    /// Returns the opacity of a main world entity as seen by a view with an optional [`ViewOpacity`].
+    pub fn get_in_view(
+        &self,
+        view: Option<&ViewOpacity>,
+        entity: impl Into<MainEntity>,
+    ) -> Option<f32> {
+        let opacity = self.get(entity)?;
+        Some(view.map_or(opacity, |view| view.apply(opacity)))
+    }
+
+    /// Iterate over all main world entities with a computed opacity.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, f32)> + '_ {
+        self.0
+            .iter()
+            .map(|(entity, opacity)| (entity.id(), *opacity))
+    }
+}
+
+impl ExtractResource for RenderOpacityMap {
+    type Source = OpacityMap;
+
+    fn extract_resource(map: &OpacityMap) -> Self {
+        RenderOpacityMap(
+            map.iter()
+                .map(|(entity, opacity)| (MainEntity::from(entity), opacity))
+                .collect(),
+        )
+    }
+}
+
+/// How a camera sees computed opacity, for example a minimap that shows faded entities fully opaque.
+///
+/// Built-in types like [`Sprite`](bevy::sprite::Sprite) and materials are shared by every view,
+/// so they keep their computed opacity. This component is extracted to the render world
+/// alongside each camera, custom pipelines resolve per-view opacity with [`RenderOpacityMap::get_in_view`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::view::ExtractedView;
+/// # use bevy::render::sync_world::MainEntity;
+/// # use bevy_mod_opacity::{RenderOpacityMap, ViewOpacity};
+/// fn prepare_views(
+///     views: Query<Option<&ViewOpacity>, With<ExtractedView>>,
+///     instances: Query<&MainEntity>,
+///     opacity: Res<RenderOpacityMap>,
+/// ) {
+///     for view in &views {
+///         for main_entity in &instances {
+///             let _alpha = opacity.get_in_view(view, *main_entity).unwrap_or(1.0);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default, Component, ExtractComponent)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub enum ViewOpacity {
+    /// Entities have their computed opacity.
+    #[default]
+    Computed,
+    /// Entities are fully opaque, for example in a minimap.
+    Opaque,
+    /// Entities are at least this opaque, for example to reveal stealthed units through an x-ray view.
+    AtLeast(f32),
+}
+
+impl ViewOpacity {
+    /// Returns the opacity seen by this view for a computed opacity.
+    pub fn apply(&self, opacity: f32) -> f32 {
+        match *self {
+            ViewOpacity::Computed => opacity,
+            ViewOpacity::Opaque => 1.0,
+            ViewOpacity::AtLeast(min) => opacity.max(min),
+        }
+    }
+}
+
+pub fn opacity_plugin_extract(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<ViewOpacity>();
+    app.add_plugins((
+        ExtractResourcePlugin::<RenderOpacityMap>::default(),
+        ExtractComponentPlugin::<ViewOpacity>::default(),
+    ));
+}
@@ -0,0 +1,85 @@
+use bevy::{
+    app::App,
+    ecs::schedule::{common_conditions::resource_changed, Condition, IntoScheduleConfigs},
+    prelude::{Commands, Component, Entity, Query, Res, Resource, With, Without},
+    ui::{FocusPolicy, Interaction},
+};
+
+use crate::{OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectResource,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// UI nodes with computed opacity less than or equal to this value let clicks pass through
+/// with [`FocusPolicy::Pass`] and have their [`Interaction`] removed,
+/// so they are not hovered or pressed.
+///
+/// The original [`FocusPolicy`] and [`Interaction::None`] are restored
+/// when opacity rises above the threshold.
+///
+/// Defaults to `0.0`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::OpacityFocusThreshold;
+/// App::new().insert_resource(OpacityFocusThreshold(0.1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityFocusThreshold(pub f32);
+
+/// Stores the original [`FocusPolicy`] of a node while it is too transparent to interact with.
+#[derive(Debug, Component)]
+struct FocusDisabled(Option<FocusPolicy>);
+
+fn update_focus(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    threshold: Res<OpacityFocusThreshold>,
+    nodes: Query<Option<&FocusPolicy>, (With<Interaction>, Without<FocusDisabled>)>,
+    disabled: Query<(Entity, &FocusDisabled)>,
+) {
+    for (entity, FocusDisabled(previous)) in &disabled {
+        if map
+            .get(entity)
+            .is_some_and(|opacity| opacity <= threshold.0)
+        {
+            continue;
+        }
+        let mut entity = commands.entity(entity);
+        match previous {
+            Some(policy) => entity.try_insert(*policy),
+            None => entity.try_remove::<FocusPolicy>(),
+        };
+        entity
+            .try_insert(Interaction::None)
+            .try_remove::<FocusDisabled>();
+    }
+    for (entity, opacity) in map.iter() {
+        if opacity > threshold.0 {
+            continue;
+        }
+        if let Ok(previous) = nodes.get(entity) {
+            // `ui_focus_system` sets `Interaction` regardless of `FocusPolicy`,
+            // removing it keeps the node from being hovered or pressed.
+            commands
+                .entity(entity)
+                .try_insert((FocusPolicy::Pass, FocusDisabled(previous.copied())))
+                .try_remove::<Interaction>();
+        }
+    }
+}
+
+pub fn opacity_plugin_focus(app: &mut App) {
+    app.init_resource::<OpacityFocusThreshold>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityFocusThreshold>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        update_focus
+            .run_if(resource_changed::<OpacityMap>.or(resource_changed::<OpacityFocusThreshold>))
+            .in_set(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,135 @@
+use bevy::{
+    app::App,
+    ecs::{
+        component::HookContext,
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+        world::DeferredWorld,
+    },
+    prelude::{Commands, Component, Entity, Query, With, Without},
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{Opacity, OpacitySet};
+
+/// How a fade awaited with a [`FadeFuture`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FadeOutcome {
+    /// The target opacity was reached.
+    Completed,
+    /// The target was changed by another fade, [`Opacity`] was removed or the entity was despawned.
+    Cancelled,
+}
+
+#[derive(Debug, Default)]
+struct FadeState {
+    outcome: Option<FadeOutcome>,
+    waker: Option<Waker>,
+}
+
+fn resolve(state: &Mutex<FadeState>, outcome: FadeOutcome) {
+    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+    if state.outcome.is_none() {
+        state.outcome = Some(outcome);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A [`Future`] resolved when a fade started by
+/// [`OpacityCommandsExt::fade_to_async`](crate::OpacityCommandsExt::fade_to_async) finishes or is cancelled.
+///
+/// Can be awaited in tasks of any executor, or polled with [`FadeFuture::outcome`].
+#[derive(Debug, Clone)]
+pub struct FadeFuture(Arc<Mutex<FadeState>>);
+
+impl FadeFuture {
+    /// Returns how the fade ended, or `None` if it is still ongoing.
+    pub fn outcome(&self) -> Option<FadeOutcome> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).outcome
+    }
+}
+
+impl Future for FadeFuture {
+    type Output = FadeOutcome;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match state.outcome {
+            Some(outcome) => Poll::Ready(outcome),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Resolves a [`FadeFuture`] when the fade of this entity ends,
+/// cancels it when dropped before.
+#[derive(Debug, Component)]
+#[component(on_remove = waiter_removed)]
+pub(crate) struct FadeWaiter {
+    target: f32,
+    state: Arc<Mutex<FadeState>>,
+}
+
+impl FadeWaiter {
+    pub(crate) fn new(target: f32) -> (Self, FadeFuture) {
+        let state = Arc::default();
+        let future = FadeFuture(Arc::clone(&state));
+        (FadeWaiter { target, state }, future)
+    }
+}
+
+/// Resolves the fade if the entity is despawned when reaching its target, like [`Opacity::fade_out`].
+fn waiter_removed(world: DeferredWorld, context: HookContext) {
+    let (Some(waiter), Some(opacity)) = (
+        world.get::<FadeWaiter>(context.entity),
+        world.get::<Opacity>(context.entity),
+    ) else {
+        return;
+    };
+    if opacity.current == waiter.target {
+        resolve(&waiter.state, FadeOutcome::Completed);
+    }
+}
+
+impl Drop for FadeWaiter {
+    fn drop(&mut self) {
+        resolve(&self.state, FadeOutcome::Cancelled);
+    }
+}
+
+fn resolve_fades(
+    mut commands: Commands,
+    query: Query<(Entity, &Opacity, &FadeWaiter)>,
+    removed: Query<Entity, (With<FadeWaiter>, Without<Opacity>)>,
+) {
+    for entity in &removed {
+        commands.entity(entity).try_remove::<FadeWaiter>();
+    }
+    for (entity, opacity, waiter) in &query {
+        if opacity.target != waiter.target {
+            commands.entity(entity).try_remove::<FadeWaiter>();
+        } else if opacity.current == opacity.target {
+            resolve(&waiter.state, FadeOutcome::Completed);
+            commands.entity(entity).try_remove::<FadeWaiter>();
+        }
+    }
+}
+
+pub fn opacity_plugin_future(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        resolve_fades
+            .run_if(any_with_component::<FadeWaiter>)
+            .after(OpacitySet::Fading)
+            .before(OpacitySet::PostFade),
+    );
+}
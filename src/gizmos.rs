@@ -0,0 +1,249 @@
+use bevy::{
+    app::{App, Last},
+    asset::{AssetId, Assets, Handle},
+    color::{Alpha, ColorToComponents, LinearRgba},
+    ecs::{query::QueryData, schedule::IntoScheduleConfigs, system::SystemParam},
+    gizmos::{
+        config::GizmoConfigGroup, gizmos::GizmoBufferView, retained::Gizmo, GizmoAsset,
+        UpdateGizmoMeshes,
+    },
+    platform::collections::{HashMap, HashSet},
+    prelude::{Commands, Component, Entity, Local, Query, Res, ResMut, Resource},
+};
+use std::{any::TypeId, borrow::Cow, marker::PhantomData};
+
+use crate::{GlobalOpacity, OpacityGroups, OpacityMap, OpacityQuery};
+
+/// Tracks the original [`GizmoAsset`] of a [`Gizmo`] affected by opacity.
+///
+/// Since vertex colors of a [`GizmoAsset`] cannot be modified in place,
+/// a faded copy of the original asset is written to a separate handle.
+#[derive(Debug, Clone, Component)]
+pub struct GizmoOpacity {
+    base: Handle<GizmoAsset>,
+    faded: Handle<GizmoAsset>,
+}
+
+impl GizmoOpacity {
+    /// Returns the handle of the unfaded [`GizmoAsset`].
+    pub fn base(&self) -> &Handle<GizmoAsset> {
+        &self.base
+    }
+}
+
+fn fade_gizmo(asset: &GizmoAsset, opacity: f32) -> GizmoAsset {
+    let view = asset.buffer();
+    fade_gizmo_colors(&view, [view.list_colors, view.strip_colors], opacity)
+}
+
+/// Returns a copy of the vertex buffers with `[list_colors, strip_colors]` faded.
+fn fade_gizmo_colors(
+    view: &GizmoBufferView,
+    [list_colors, strip_colors]: [&[LinearRgba]; 2],
+    opacity: f32,
+) -> GizmoAsset {
+    let fade = |color: &LinearRgba| color.with_alpha(color.alpha() * opacity);
+    let mut result = GizmoAsset::new();
+    for (positions, colors) in view
+        .list_positions
+        .chunks_exact(2)
+        .zip(list_colors.chunks_exact(2))
+    {
+        result.line_gradient(
+            positions[0],
+            positions[1],
+            fade(&colors[0]),
+            fade(&colors[1]),
+        );
+    }
+    let mut strip = Vec::new();
+    for (position, color) in view.strip_positions.iter().zip(strip_colors) {
+        if position.is_nan() {
+            result.linestrip_gradient(strip.drain(..));
+        } else {
+            strip.push((*position, fade(color)));
+        }
+    }
+    result
+}
+
+/// Compares colors bitwise, since line strips are separated by `NaN`s.
+fn same_colors(a: &[LinearRgba], b: &[LinearRgba]) -> bool {
+    let bits = |color: &LinearRgba| color.to_f32_array().map(f32::to_bits);
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| bits(a) == bits(b))
+}
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct GizmoQuery {
+    pub entity: Entity,
+    pub gizmo: &'static mut Gizmo,
+    pub state: Option<&'static mut GizmoOpacity>,
+}
+
+impl OpacityQuery for GizmoQuery {
+    type Cx = (
+        Commands<'static, 'static>,
+        ResMut<'static, Assets<GizmoAsset>>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (commands, assets): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        // If the handle was replaced by the user, it becomes the new base.
+        let base = match &this.state {
+            Some(state) if state.faded == this.gizmo.handle => state.base.clone(),
+            _ => this.gizmo.handle.clone(),
+        };
+        let Some(faded_asset) = assets.get(&base).map(|x| fade_gizmo(x, opacity)) else {
+            return;
+        };
+        match this.state.as_deref_mut() {
+            Some(state) => {
+                assets.insert(&state.faded, faded_asset);
+                state.base = base;
+                this.gizmo.handle = state.faded.clone();
+            }
+            None => {
+                let faded = assets.add(faded_asset);
+                this.gizmo.handle = faded.clone();
+                commands
+                    .entity(this.entity)
+                    .try_insert(GizmoOpacity { base, faded });
+            }
+        }
+    }
+}
+
+/// The opacity immediate-mode [`Gizmos`](bevy::prelude::Gizmos) of a config group follow,
+/// see [`OpacityGizmoConfigExt`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum GizmoConfigFade {
+    /// Follow [`GlobalOpacity`].
+    #[default]
+    Global,
+    /// Follow the computed opacity of an entity,
+    /// the last value is kept if the entity has no opacity, for example after being despawned.
+    Entity(Entity),
+    /// Follow the opacity of a group in [`OpacityGroups`].
+    Group(Cow<'static, str>),
+}
+
+/// [`Resource`] holding the [`GizmoConfigFade`] of a gizmo config group,
+/// modify it to follow a different entity or group.
+#[derive(Debug, Resource)]
+pub struct GizmoConfigOpacity<C: GizmoConfigGroup> {
+    pub fade: GizmoConfigFade,
+    marker: PhantomData<C>,
+}
+
+impl<C: GizmoConfigGroup> GizmoConfigOpacity<C> {
+    /// Follow `fade`.
+    pub const fn new(fade: GizmoConfigFade) -> Self {
+        GizmoConfigOpacity {
+            fade,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Colors of an immediate-mode gizmo asset before and after it was faded.
+#[derive(Debug)]
+struct FadedColors {
+    base: [Vec<LinearRgba>; 2],
+    faded: [Vec<LinearRgba>; 2],
+}
+
+fn fade_gizmo_config<C: GizmoConfigGroup>(
+    config: Res<GizmoConfigOpacity<C>>,
+    (map, global, groups): (Res<OpacityMap>, Res<GlobalOpacity>, Res<OpacityGroups>),
+    retained: Query<&Gizmo>,
+    mut assets: ResMut<Assets<GizmoAsset>>,
+    mut last: Local<Option<f32>>,
+    mut faded: Local<HashMap<AssetId<GizmoAsset>, FadedColors>>,
+) {
+    let opacity = match &config.fade {
+        GizmoConfigFade::Global => Some(global.0),
+        GizmoConfigFade::Entity(entity) => map.get(*entity).or(*last),
+        GizmoConfigFade::Group(group) => Some(groups.get(group)),
+    };
+    *last = opacity;
+    let Some(opacity) = opacity.filter(|opacity| *opacity < 1.0) else {
+        faded.clear();
+        return;
+    };
+    // The handles of immediate-mode gizmos are private, find them by their config group.
+    // Retained gizmos are faded by their entity's opacity instead.
+    let retained: HashSet<_> = retained.iter().map(|gizmo| gizmo.handle.id()).collect();
+    let ids: HashSet<_> = assets
+        .iter()
+        .filter(|(id, asset)| asset.config_typeid() == TypeId::of::<C>() && !retained.contains(id))
+        .map(|(id, _)| id)
+        .collect();
+    faded.retain(|id, _| ids.contains(id));
+    for id in ids {
+        let Some(asset) = assets.get(id) else {
+            continue;
+        };
+        let view = asset.buffer();
+        let colors = [view.list_colors, view.strip_colors];
+        // Gizmos are redrawn into the same asset, if it was not redrawn since it was faded
+        // fade the original colors again instead of compounding.
+        let base = match faded.remove(&id) {
+            Some(state)
+                if same_colors(colors[0], &state.faded[0])
+                    && same_colors(colors[1], &state.faded[1]) =>
+            {
+                state.base
+            }
+            _ => colors.map(Vec::clone),
+        };
+        let mut result = fade_gizmo_colors(&view, [&base[0], &base[1]], opacity);
+        let view = result.buffer();
+        let state = FadedColors {
+            base,
+            faded: [view.list_colors.clone(), view.strip_colors.clone()],
+        };
+        if !same_colors(colors[0], &state.faded[0]) || !same_colors(colors[1], &state.faded[1]) {
+            let Some(asset) = assets.get_mut(id) else {
+                continue;
+            };
+            // Swap vertex buffers only, so the asset keeps its config group.
+            std::mem::swap(&mut **asset, &mut *result);
+        }
+        faded.insert(id, state);
+    }
+}
+
+/// Extensions for [`App`] to fade immediate-mode [`Gizmos`](bevy::prelude::Gizmos).
+pub trait OpacityGizmoConfigExt {
+    /// Scale the alpha of every gizmo drawn with config group `C` by an opacity,
+    /// for example so debug overlays fade with the scene.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::{GizmoConfigFade, OpacityGizmoConfigExt};
+    /// #[derive(Default, Reflect, GizmoConfigGroup)]
+    /// struct DebugGizmos;
+    ///
+    /// # fn f(app: &mut App) {
+    /// app.init_gizmo_group::<DebugGizmos>()
+    ///     .fade_gizmo_config::<DebugGizmos>(GizmoConfigFade::Group("debug".into()));
+    /// # }
+    /// ```
+    fn fade_gizmo_config<C: GizmoConfigGroup>(&mut self, fade: GizmoConfigFade) -> &mut Self;
+}
+
+impl OpacityGizmoConfigExt for App {
+    fn fade_gizmo_config<C: GizmoConfigGroup>(&mut self, fade: GizmoConfigFade) -> &mut Self {
+        self.insert_resource(GizmoConfigOpacity::<C>::new(fade));
+        self.add_systems(Last, fade_gizmo_config::<C>.after(UpdateGizmoMeshes));
+        self
+    }
+}
+
+pub fn opacity_plugin_gizmos(app: &mut App) {
+    crate::register_opacity_requiring::<GizmoQuery, Assets<GizmoAsset>>(app);
+}
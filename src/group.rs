@@ -0,0 +1,105 @@
+use bevy::{
+    app::App,
+    platform::collections::HashMap,
+    prelude::{Component, DetectChangesMut, Res, ResMut, Resource},
+    time::Time,
+};
+use std::borrow::Cow;
+
+use crate::{Opacity, OpacityTimeScale, ReducedMotion};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Adds this entity and its descendants to a named group,
+/// whose opacity is controlled by [`OpacityGroups`] independent of the hierarchy.
+///
+/// The group's opacity is multiplied into this entity's [`Opacity`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+#[require(Opacity)]
+pub struct OpacityGroup(pub Cow<'static, str>);
+
+impl OpacityGroup {
+    /// Creates a new opacity group marker.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        OpacityGroup(name.into())
+    }
+}
+
+/// [`Resource`] controlling the opacity of each [`OpacityGroup`].
+///
+/// Groups that were never set have opacity `1.0`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::OpacityGroups;
+/// fn hide_hud(mut groups: ResMut<OpacityGroups>) {
+///     groups.fade_out("hud", 0.3);
+/// }
+/// ```
+#[derive(Debug, Default, Resource)]
+pub struct OpacityGroups(HashMap<Cow<'static, str>, Opacity>);
+
+impl OpacityGroups {
+    /// Returns the current opacity of a group.
+    pub fn get(&self, group: &str) -> f32 {
+        self.0.get(group).map(Opacity::get).unwrap_or(1.0)
+    }
+
+    fn entry(&mut self, group: impl Into<Cow<'static, str>>) -> &mut Opacity {
+        self.0.entry(group.into()).or_default()
+    }
+
+    /// Set the opacity of a group and cancels interpolation.
+    pub fn set(&mut self, group: impl Into<Cow<'static, str>>, opacity: f32) {
+        self.entry(group).set(opacity);
+    }
+
+    /// Interpolate the opacity of a group to `1.0`.
+    pub fn fade_in(&mut self, group: impl Into<Cow<'static, str>>, time: f32) {
+        self.entry(group).fade_in(time);
+    }
+
+    /// Interpolate the opacity of a group to `0.0`, entities are not despawned.
+    pub fn fade_out(&mut self, group: impl Into<Cow<'static, str>>, time: f32) {
+        self.entry(group).interpolate_to(0.0, time);
+    }
+
+    /// Interpolate the opacity of a group to a specific value.
+    pub fn interpolate_to(&mut self, group: impl Into<Cow<'static, str>>, opacity: f32, time: f32) {
+        self.entry(group).interpolate_to(opacity, time);
+    }
+
+    /// Returns the opacity multiplier of an optional group.
+    pub(crate) fn multiplier(&self, group: Option<&OpacityGroup>) -> f32 {
+        group.map(|group| self.get(&group.0)).unwrap_or(1.0)
+    }
+}
+
+pub(crate) fn interpolate_groups<C: Default + Send + Sync + 'static>(
+    time: Res<Time<C>>,
+    scale: Res<OpacityTimeScale>,
+    reduced_motion: Res<ReducedMotion>,
+    mut groups: ResMut<OpacityGroups>,
+) {
+    let dt = scale.scale_delta(time.delta_secs());
+    let mut changed = false;
+    for opacity in groups.bypass_change_detection().0.values_mut() {
+        if opacity.speed != 0.0 {
+            opacity.advance(dt, &reduced_motion);
+            changed = true;
+        }
+    }
+    if changed {
+        groups.set_changed();
+    }
+}
+
+pub fn opacity_plugin_group(app: &mut App) {
+    app.init_resource::<OpacityGroups>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityGroup>();
+}
@@ -0,0 +1,36 @@
+use bevy::app::App;
+use bevy_hanabi::EffectProperties;
+
+use crate::{OpacityExtension, OpacityQuery};
+
+/// Name of the effect property opacity is written to, on every [`EffectProperties`].
+///
+/// Declare it in the effect and multiply it into the alpha of the particle color,
+/// effects that do not declare it are not affected.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_hanabi::prelude::*;
+/// # use bevy_mod_opacity::EFFECT_OPACITY;
+/// let writer = ExprWriter::new();
+/// let opacity = writer.add_property(EFFECT_OPACITY, 1.0.into());
+/// let color = writer
+///     .lit(Vec3::new(1.0, 0.5, 0.0))
+///     .vec4_xyz_w(writer.prop(opacity))
+///     .pack4x8unorm();
+/// let init_color = SetAttributeModifier::new(Attribute::COLOR, color.expr());
+/// ```
+pub const EFFECT_OPACITY: &str = "opacity";
+
+impl OpacityQuery for &mut EffectProperties {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        // Only upload properties to the GPU when the value changed.
+        EffectProperties::set_if_changed(this.reborrow(), EFFECT_OPACITY, opacity.into());
+    }
+}
+
+pub fn opacity_plugin_hanabi(app: &mut App) {
+    app.register_opacity_parallel::<&mut EffectProperties>();
+}
@@ -0,0 +1,49 @@
+use bevy::app::App;
+use bevy_inspector_egui::{
+    egui,
+    inspector_egui_impls::{InspectorEguiImpl, InspectorPrimitive},
+    reflect_inspector::InspectorUi,
+};
+use std::any::Any;
+
+use crate::Opacity;
+
+/// A slider for the current value, show and hide buttons and the fade in progress.
+impl InspectorPrimitive for Opacity {
+    fn ui(&mut self, ui: &mut egui::Ui, _: &dyn Any, _: egui::Id, _: InspectorUi<'_, '_>) -> bool {
+        let mut current = self.get();
+        let mut changed = ui.add(egui::Slider::new(&mut current, 0.0..=1.0)).changed();
+        if changed {
+            self.set(current);
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Show").clicked() {
+                self.show();
+                changed = true;
+            }
+            if ui.button("Hide").clicked() {
+                self.hide();
+                changed = true;
+            }
+        });
+        fade_label(self, ui);
+        changed
+    }
+
+    fn ui_readonly(&self, ui: &mut egui::Ui, _: &dyn Any, _: egui::Id, _: InspectorUi<'_, '_>) {
+        ui.add_enabled(false, egui::Slider::new(&mut self.get(), 0.0..=1.0));
+        fade_label(self, ui);
+    }
+}
+
+fn fade_label(opacity: &Opacity, ui: &mut egui::Ui) {
+    if opacity.is_despawning() {
+        ui.label(format!("despawns at {:.2}", opacity.get_target()));
+    } else if opacity.is_fading() {
+        ui.label(format!("fading to {:.2}", opacity.get_target()));
+    }
+}
+
+pub fn opacity_plugin_inspector(app: &mut App) {
+    app.register_type_data::<Opacity, InspectorEguiImpl>();
+}
@@ -0,0 +1,92 @@
+use bevy::{
+    app::App,
+    ecs::{
+        query::{Changed, Or},
+        schedule::IntoScheduleConfigs,
+    },
+    prelude::{Component, Query},
+    ui::Interaction,
+};
+use std::time::Duration;
+
+use crate::{Opacity, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Fades this entity to a different opacity for each state of its [`Interaction`],
+/// for example to highlight hovered buttons.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::FadeOnInteraction;
+/// # use std::time::Duration;
+/// fn spawn_button(mut commands: Commands) {
+///     commands.spawn((
+///         Button,
+///         FadeOnInteraction::new(0.6, 1.0, 0.8, Duration::from_millis(150)),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+#[require(Opacity, Interaction)]
+pub struct FadeOnInteraction {
+    /// Opacity when not interacted with.
+    pub none: f32,
+    /// Opacity when hovered.
+    pub hovered: f32,
+    /// Opacity when pressed.
+    pub pressed: f32,
+    /// Time to fade between states.
+    pub duration: Duration,
+}
+
+impl FadeOnInteraction {
+    /// Creates a new interaction fade.
+    pub const fn new(none: f32, hovered: f32, pressed: f32, duration: Duration) -> Self {
+        FadeOnInteraction {
+            none,
+            hovered,
+            pressed,
+            duration,
+        }
+    }
+
+    /// Returns the opacity of an interaction state.
+    pub const fn opacity(&self, interaction: Interaction) -> f32 {
+        match interaction {
+            Interaction::None => self.none,
+            Interaction::Hovered => self.hovered,
+            Interaction::Pressed => self.pressed,
+        }
+    }
+}
+
+type InteractionChanged = Or<(Changed<Interaction>, Changed<FadeOnInteraction>)>;
+
+fn fade_on_interaction(
+    mut query: Query<(&Interaction, &FadeOnInteraction, &mut Opacity), InteractionChanged>,
+) {
+    for (interaction, fade, mut opacity) in &mut query {
+        let target = fade.opacity(*interaction);
+        if opacity.get_target() == target {
+            continue;
+        }
+        if fade.duration.is_zero() {
+            opacity.set(target);
+        } else {
+            opacity.interpolate_to(target, fade.duration.as_secs_f32());
+        }
+    }
+}
+
+pub fn opacity_plugin_interaction(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<FadeOnInteraction>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        fade_on_interaction
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
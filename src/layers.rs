@@ -0,0 +1,64 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::{Changed, DetectChanges, Entity, Local, Query, Res, Resource},
+    render::view::RenderLayers,
+};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectResource, reflect::Reflect};
+
+/// Opt-in [`Resource`] that scopes [`GlobalOpacity`](crate::GlobalOpacity) to entities on these [`RenderLayers`],
+/// for example to fade the world while minimap and viewmodel layers stay opaque.
+///
+/// Entities without [`RenderLayers`] are on layer `0`. Unlike [`IgnoreGlobalOpacity`](crate::IgnoreGlobalOpacity),
+/// layers are checked on each entity and do not affect descendants.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::view::RenderLayers;
+/// # use bevy_mod_opacity::{GlobalOpacity, GlobalOpacityLayers};
+/// fn pause(mut commands: Commands) {
+///     commands.insert_resource(GlobalOpacity(0.3));
+///     commands.insert_resource(GlobalOpacityLayers(RenderLayers::layer(0)));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource))]
+pub struct GlobalOpacityLayers(pub RenderLayers);
+
+/// Entities [`GlobalOpacity`](crate::GlobalOpacity) applies to with [`GlobalOpacityLayers`].
+#[derive(SystemParam)]
+pub(crate) struct GlobalLayers<'w, 's> {
+    layers: Option<Res<'w, GlobalOpacityLayers>>,
+    query: Query<'w, 's, &'static RenderLayers>,
+    changed: Query<'w, 's, Entity, Changed<RenderLayers>>,
+    existed: Local<'s, bool>,
+}
+
+impl GlobalLayers<'_, '_> {
+    /// Returns true if [`GlobalOpacityLayers`] was inserted, changed or removed since the last call.
+    pub(crate) fn is_changed(&mut self) -> bool {
+        let exists = self.layers.is_some();
+        let changed = exists != *self.existed
+            || self
+                .layers
+                .as_ref()
+                .is_some_and(|layers| layers.is_changed());
+        *self.existed = exists;
+        changed
+    }
+
+    /// Returns true if [`GlobalOpacity`](crate::GlobalOpacity) applies to this entity.
+    pub(crate) fn contains(&self, entity: Entity) -> bool {
+        self.layers.as_ref().is_none_or(|layers| {
+            self.query
+                .get(entity)
+                .unwrap_or_default()
+                .intersects(&layers.0)
+        })
+    }
+
+    /// Entities whose [`RenderLayers`] changed, if [`GlobalOpacityLayers`] exists.
+    pub(crate) fn changed(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.changed.iter().filter(|_| self.layers.is_some())
+    }
+}
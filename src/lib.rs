@@ -1,52 +1,293 @@
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "accessibility")]
+mod accessibility;
 mod alpha;
-#[doc(hidden)]
-pub use alpha::set_alpha;
+#[cfg(feature = "animation")]
+mod animation;
+#[cfg(feature = "audio")]
+mod audio;
+#[cfg(feature = "render")]
+mod buffer;
+#[cfg(feature = "accessibility")]
+pub use accessibility::OpacityAccessibilityThreshold;
+pub use alpha::{multiply_alpha, set_alpha, OpacityAlpha};
 #[doc(hidden)]
 pub use bevy::asset::{Assets, Handle};
 #[allow(unused)]
 use bevy::color::Alpha;
 #[doc(hidden)]
 pub use bevy::ecs::query::QueryData;
+#[doc(hidden)]
+pub use bevy::ecs::system::{ResMut, SystemParam};
 
-use bevy::ecs::schedule::{ApplyDeferred, IntoScheduleConfigs};
+use bevy::ecs::change_detection::{DetectChanges, DetectChangesMut};
+use bevy::ecs::query::{Changed, Or, QueryFilter, Without};
+use bevy::ecs::removal_detection::RemovedComponents;
+use bevy::ecs::schedule::common_conditions::{any_with_component, resource_changed};
+use bevy::ecs::schedule::{
+    ApplyDeferred, Condition, InternedScheduleLabel, IntoScheduleConfigs, ScheduleLabel,
+};
 use bevy::ecs::system::Commands;
+use bevy::log::warn_once;
+use bevy::math::curve::{Curve, Ease, FunctionCurve, Interval};
+use bevy::math::StableInterpolate;
+#[cfg(feature = "reflect")]
+use bevy::prelude::{ReflectComponent, ReflectResource};
+#[cfg(feature = "reflect")]
+use bevy::reflect::std_traits::ReflectDefault;
 use bevy::reflect::Reflect;
-use bevy::time::{Time, Virtual};
+use bevy::time::{Real, Time, Virtual};
+use bevy::utils::Parallel;
 use bevy::{
-    app::{App, Plugin, PostUpdate},
-    asset::Asset,
+    app::{App, Last, Plugin, PostUpdate},
+    asset::{AsAssetId, Asset, AssetId},
     ecs::{
-        entity::EntityHashMap,
-        system::{StaticSystemParam, SystemParam},
+        component::{HookContext, Tick},
+        entity::{EntityHashMap, EntityHashSet},
+        error::ignore,
+        system::{StaticSystemParam, SystemChangeTick},
+        world::DeferredWorld,
+    },
+    platform::collections::HashMap,
+    prelude::{
+        ChildOf, Children, Component, Entity, EntityWorldMut, Has, Local, Query, Res, Resource,
+        SystemSet, With,
     },
-    prelude::{Children, Component, Entity, Query, Res, ResMut, Resource, SystemSet},
     transform::systems::{propagate_parent_transforms, sync_simple_transforms},
 };
 use std::marker::PhantomData;
+use std::ops::{Add, Deref, DerefMut, Mul, Sub};
+use std::time::Duration;
 
 #[cfg(feature = "derive")]
-pub use bevy_mod_opacity_derive::Opacity;
+pub use bevy_mod_opacity_derive::{Opacity, OpacityAsset};
 
+mod callback;
+#[cfg(feature = "render")]
+mod camera;
+#[cfg(feature = "render")]
+mod clear_color;
+#[cfg(any(
+    feature = "avian2d",
+    feature = "avian3d",
+    feature = "rapier2d",
+    feature = "rapier3d"
+))]
+mod collider;
+mod commands;
+#[cfg(feature = "render")]
+mod crossfade;
+#[cfg(feature = "curve")]
+mod curve;
+#[cfg(feature = "debug")]
+mod debug;
+#[cfg(feature = "dev_tools")]
+mod dev_tools;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+#[cfg(feature = "render")]
+mod distance;
+mod easing;
+#[cfg(feature = "ecs_tilemap")]
+mod ecs_tilemap;
+#[cfg(feature = "egui")]
+mod egui;
+mod event;
+#[cfg(feature = "render")]
+mod extract;
+#[cfg(feature = "ui")]
+mod focus;
+mod future;
+#[cfg(feature = "gizmos")]
+mod gizmos;
+mod group;
+#[cfg(feature = "hanabi")]
+mod hanabi;
+#[cfg(feature = "inspector")]
+mod inspector;
+#[cfg(feature = "ui")]
+mod interaction;
+#[cfg(feature = "render")]
+mod layers;
+#[cfg(feature = "render")]
+mod lod;
+#[cfg(feature = "lyon")]
+mod lyon;
+#[cfg(any(feature = "2d", feature = "3d", feature = "ui"))]
+mod mode;
+#[cfg(feature = "occlusion")]
+mod occlusion;
+#[cfg(feature = "3d")]
+mod opacity_ext;
 #[cfg(feature = "3d")]
 mod pbr;
+#[cfg(feature = "picking")]
+mod picking;
+#[cfg(feature = "post_process")]
+mod post_process;
+mod reflect;
+mod relationship;
+#[cfg(feature = "scene")]
+mod scene;
+#[cfg(feature = "ui")]
+mod screen_fade;
+mod sequence;
+#[cfg(feature = "3d")]
+mod shadow;
+#[cfg(feature = "render")]
+mod show;
+mod snapshot;
+mod spawn;
 #[cfg(feature = "2d")]
 mod sprite;
+mod stagger;
+#[cfg(feature = "state")]
+mod state;
+mod testing;
+#[cfg(any(feature = "2d", feature = "ui"))]
+mod text;
+mod transition;
+#[cfg(feature = "tweening")]
+mod tweening;
 #[cfg(feature = "ui")]
 mod ui;
+#[cfg(any(feature = "2d", feature = "3d"))]
+mod vertex;
+#[cfg(feature = "render")]
+mod visibility;
+#[cfg(all(feature = "wireframe", any(feature = "2d", feature = "3d")))]
+mod wireframe;
+#[cfg(feature = "animation")]
+pub use animation::{OpacityProperty, OpacityStateProperty};
+#[cfg(feature = "audio")]
+pub use audio::BaseVolume;
+#[cfg(feature = "render")]
+pub use buffer::{GpuOpacity, OpacityBuffer};
+pub use callback::OnFadeComplete;
+#[cfg(feature = "render")]
+pub use camera::{BaseExposure, CameraFade};
+#[cfg(feature = "render")]
+pub use clear_color::{ClearColorFadeFinished, FadeClearColor};
+#[cfg(any(
+    feature = "avian2d",
+    feature = "avian3d",
+    feature = "rapier2d",
+    feature = "rapier3d"
+))]
+pub use collider::OpacityColliderThreshold;
+pub use commands::OpacityCommandsExt;
+#[cfg(feature = "render")]
+pub use crossfade::{Crossfade, CrossfadeFinished};
+#[cfg(feature = "curve")]
+pub use curve::{FadeCurve, OpacityCurve, OpacityCurveError, OpacityCurveLoader};
+#[cfg(feature = "dev_tools")]
+pub use dev_tools::OpacityDebugOverlay;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::OpacityDiagnostics;
+#[cfg(feature = "render")]
+pub use distance::{DistanceFade, DistanceFadeReference};
+pub use easing::OpacityEasing;
+#[cfg(feature = "ecs_tilemap")]
+pub use ecs_tilemap::BaseTileAlpha;
+#[cfg(feature = "egui")]
+pub use egui::BaseEguiVisuals;
+pub use event::{BecameInvisible, BecameVisible, OpacityChanged};
+#[cfg(feature = "render")]
+pub use extract::{RenderOpacityMap, ViewOpacity};
+#[cfg(feature = "ui")]
+pub use focus::OpacityFocusThreshold;
+pub use future::{FadeFuture, FadeOutcome};
+#[cfg(feature = "gizmos")]
+pub use gizmos::{GizmoConfigFade, GizmoConfigOpacity, GizmoOpacity, OpacityGizmoConfigExt};
+pub use group::{OpacityGroup, OpacityGroups};
+#[cfg(feature = "hanabi")]
+pub use hanabi::EFFECT_OPACITY;
+#[cfg(feature = "ui")]
+pub use interaction::FadeOnInteraction;
+#[cfg(feature = "render")]
+pub use layers::GlobalOpacityLayers;
+#[cfg(feature = "render")]
+pub use lod::LodCrossfade;
+#[cfg(feature = "lyon")]
+pub use lyon::BaseShapeAlpha;
+#[cfg(any(feature = "2d", feature = "3d", feature = "ui"))]
+pub use mode::{BaseColor, OpacityMode, OpacityTint};
+#[cfg(any(feature = "2d", feature = "3d"))]
+pub use mode::{BaseMaterialAlphas, BaseMaterialColors};
+#[cfg(feature = "occlusion")]
+pub use occlusion::{OccluderFade, OcclusionTarget};
+#[cfg(feature = "3d")]
+pub use opacity_ext::OpacityExt;
+#[cfg(feature = "3d")]
+pub use pbr::{
+    AmbientFade, BaseAlphaCutoffs, BaseAlphaModes, BaseAmbientBrightness, BaseFogAlpha,
+    BaseFogDensity, BaseLightIntensity, BaseSkyboxBrightness, BaseStandardMaterial,
+    BaseStandardMaterials, OpacityLight, OpacityMaterialExtension, StandardMaterialOpacityPolicy,
+};
+#[cfg(feature = "picking")]
+pub use picking::OpacityPickingThreshold;
+#[cfg(feature = "post_process")]
+pub use post_process::PostProcessFade;
+#[cfg(feature = "ui")]
+pub use screen_fade::{ScreenFade, ScreenFadeFinished};
+pub use sequence::{
+    OpacityChain, OpacitySequence, OpacitySequenceEvent, OpacityStep, OpacityThen, StepTrigger,
+};
 #[cfg(feature = "3d")]
-pub use pbr::OpacityMaterialExtension;
+pub use shadow::OpacityShadowThreshold;
+#[cfg(feature = "render")]
+pub use show::FadeOnVisibility;
+pub use snapshot::{OpacitySnapshot, OpacitySnapshots};
+pub use spawn::{FadeInOnAdd, FadeOutAfter};
+pub use stagger::StaggeredFade;
+#[cfg(feature = "state")]
+pub use state::FadeOutOnExit;
+pub use testing::OpacityTestExt;
+#[cfg(any(feature = "2d", feature = "ui"))]
+pub use text::TextOpacity;
+pub use transition::UiTransition;
+#[cfg(feature = "tweening")]
+pub use tweening::OpacityLens;
 #[cfg(feature = "ui")]
-pub use ui::UiOpacity;
+pub use ui::{BaseBoxShadowAlpha, BaseTextShadowAlpha, UiOpacity};
+#[cfg(any(feature = "2d", feature = "3d"))]
+pub use vertex::{BaseVertexAlpha, VertexColorOpacity};
+#[cfg(feature = "render")]
+pub use visibility::OpacityVisibility;
 
 /// [`Component`] of opacity of this entity and its children.
-#[derive(Debug, Clone, Copy, Component, PartialEq, PartialOrd, Reflect)]
+#[derive(Debug, Clone, Copy, Component, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+#[component(on_add = queue_late_opacity)]
 pub struct Opacity {
     current: f32,
     target: f32,
+    #[cfg_attr(feature = "reflect", reflect(default))]
     speed: f32,
+    #[cfg_attr(feature = "reflect", reflect(default))]
     despawns: bool,
+    #[cfg_attr(feature = "reflect", reflect(default))]
+    motion: Motion,
+}
+
+/// How [`Opacity`] moves toward its target.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Default))]
+#[cfg_attr(
+    feature = "serde_full",
+    derive(::serde::Serialize, ::serde::Deserialize)
+)]
+enum Motion {
+    /// `speed` is the change in opacity per second.
+    #[default]
+    Linear,
+    /// `speed` is the decay rate of exponential smoothing.
+    Smooth,
+    /// `speed` is the angular frequency of a damped spring.
+    Spring { velocity: f32, damping: f32 },
+    /// `speed` is the direction of the fade, its duration is read from [`DefaultFadeDurations`] on the next update.
+    Deferred,
 }
 
 impl Opacity {
@@ -62,6 +303,7 @@ impl Opacity {
             target: opacity,
             speed: 0.0,
             despawns: false,
+            motion: Motion::Linear,
         }
     }
 
@@ -100,29 +342,65 @@ impl Opacity {
         self.despawns
     }
 
+    /// Returns true if opacity is interpolating toward its target.
+    pub const fn is_fading(&self) -> bool {
+        self.speed != 0.0
+    }
+
+    /// Returns the time in seconds until the target is reached, `0.0` if not fading.
+    ///
+    /// Does not account for [`OpacityTimeScale`] or [`ReducedMotion`].
+    pub fn remaining_time(&self) -> f32 {
+        if self.speed == 0.0 {
+            return 0.0;
+        }
+        let distance = (self.target - self.current).abs() / SMOOTH_EPSILON;
+        match self.motion {
+            // Before a default duration is resolved, assume one second from `0.0` to `1.0`.
+            Motion::Linear | Motion::Deferred => {
+                ((self.target - self.current) / self.speed).max(0.0)
+            }
+            Motion::Smooth => (distance.ln() / self.speed).max(0.0),
+            Motion::Spring { damping, .. } => {
+                // Decay rate of the slowest mode of the spring.
+                let decay = if damping <= 1.0 {
+                    damping * self.speed
+                } else {
+                    self.speed * (damping - (damping * damping - 1.0).sqrt())
+                };
+                (distance.ln() / decay).max(0.0)
+            }
+        }
+    }
+
     /// Set opacity to `0.0` and interpolate to `1.0`.
     pub const fn new_fade_in(time: f32) -> Opacity {
         Opacity {
             current: 0.0,
             target: 1.0,
-            speed: 1.0 / time,
+            speed: fade_in_speed(time),
             despawns: false,
+            motion: Motion::Linear,
         }
     }
 
     /// Interpolate to `1.0`.
     pub const fn and_fade_in(mut self, time: f32) -> Self {
         self.target = 1.0;
-        self.speed = 1.0 / time;
+        self.speed = fade_in_speed(time);
         self.despawns = false;
+        self.motion = Motion::Linear;
         self
     }
 
     /// Interpolate opacity to `1.0`.
+    ///
+    /// Like every fade, a `time` of `0.0` completes on the next update.
     pub fn fade_in(&mut self, time: f32) {
         self.target = 1.0;
         self.despawns = false;
-        self.speed = 1.0 / time;
+        self.speed = fade_speed(1.0, time);
+        self.motion = Motion::Linear;
     }
 
     /// Interpolate opacity to `0.0` and despawns the entity when that happens.
@@ -132,21 +410,195 @@ impl Opacity {
     pub fn fade_out(&mut self, time: f32) {
         self.target = 0.0;
         self.despawns = true;
-        self.speed = -1.0 / time;
+        self.speed = fade_speed(-1.0, time);
+        self.motion = Motion::Linear;
     }
 
     /// Interpolate opacity to a specific value.
     pub fn interpolate_to(&mut self, opacity: f32, time: f32) {
         self.target = opacity;
         self.despawns = false;
-        self.speed = (opacity - self.current) / time;
+        self.speed = fade_speed(opacity - self.current, time);
+        self.motion = Motion::Linear;
     }
 
     /// Interpolate opacity to a specific value.
     pub fn interpolate_by_speed(&mut self, opacity: f32, time_zero_to_one: f32) {
         self.target = opacity;
         self.despawns = false;
-        self.speed = (opacity - self.current).signum() / time_zero_to_one;
+        self.speed = fade_speed((opacity - self.current).signum(), time_zero_to_one);
+        self.motion = Motion::Linear;
+    }
+
+    /// Interpolate opacity to `1.0` in `duration`, see [`Opacity::fade_in`].
+    pub fn fade_in_for(&mut self, duration: Duration) {
+        self.fade_in(duration.as_secs_f32());
+    }
+
+    /// Interpolate opacity to `0.0` in `duration` and despawns the entity, see [`Opacity::fade_out`].
+    pub fn fade_out_for(&mut self, duration: Duration) {
+        self.fade_out(duration.as_secs_f32());
+    }
+
+    /// Interpolate opacity to a specific value in `duration`.
+    pub fn interpolate_to_for(&mut self, opacity: f32, duration: Duration) {
+        self.interpolate_to(opacity, duration.as_secs_f32());
+    }
+
+    /// Interpolate opacity to `1.0` in [`DefaultFadeDurations::show`] seconds.
+    pub fn show(&mut self) {
+        self.fade_with_default(1.0);
+    }
+
+    /// Interpolate opacity to `0.0` in [`DefaultFadeDurations::hide`] seconds, the entity is not despawned.
+    pub fn hide(&mut self) {
+        self.fade_with_default(0.0);
+    }
+
+    fn fade_with_default(&mut self, opacity: f32) {
+        self.target = opacity;
+        self.despawns = false;
+        self.speed = if opacity == self.current {
+            0.0
+        } else {
+            (opacity - self.current).signum()
+        };
+        self.motion = Motion::Deferred;
+    }
+
+    /// Replace a fade started by [`Opacity::show`] or [`Opacity::hide`] with a linear fade of the default duration.
+    fn resolve_default(&mut self, durations: &DefaultFadeDurations) {
+        if self.motion != Motion::Deferred {
+            return;
+        }
+        let time = if self.target >= self.current {
+            durations.show
+        } else {
+            durations.hide
+        };
+        self.interpolate_to(self.target, time);
+    }
+
+    /// Exponentially ease opacity toward a value, can be called every frame with a moving target.
+    ///
+    /// A `decay_rate` of around `10.0` reaches the target in about half a second,
+    /// see [`StableInterpolate::smooth_nudge`].
+    pub fn smooth_to(&mut self, opacity: f32, decay_rate: f32) {
+        self.target = opacity;
+        self.despawns = false;
+        self.motion = Motion::Smooth;
+        self.speed = if opacity == self.current {
+            0.0
+        } else {
+            decay_rate
+        };
+    }
+
+    /// Move opacity toward a value with a critically damped spring,
+    /// velocity is kept when retargeting so rapidly toggled fades stay continuous.
+    ///
+    /// `frequency` is the angular frequency of the spring in radians per second,
+    /// a value of around `10.0` settles in about half a second.
+    pub fn spring_to(&mut self, opacity: f32, frequency: f32) {
+        self.spring_to_with_damping(opacity, frequency, 1.0);
+    }
+
+    /// Move opacity toward a value with a spring,
+    /// a `damping` ratio below `1.0` overshoots and bounces, above `1.0` approaches slower.
+    pub fn spring_to_with_damping(&mut self, opacity: f32, frequency: f32, damping: f32) {
+        let velocity = match self.motion {
+            _ if self.speed == 0.0 => 0.0,
+            Motion::Linear => self.speed,
+            Motion::Deferred => 0.0,
+            Motion::Smooth => (self.target - self.current) * self.speed,
+            Motion::Spring { velocity, .. } => velocity,
+        };
+        self.target = opacity;
+        self.despawns = false;
+        self.speed = frequency;
+        self.motion = Motion::Spring { velocity, damping };
+    }
+
+    /// Advance interpolation by `dt` seconds, with speed modified by [`ReducedMotion`].
+    fn advance(&mut self, dt: f32, reduced_motion: &ReducedMotion) {
+        let speed = reduced_motion.speed(self.speed);
+        // Instant fades complete on the next update, even if no time passed.
+        if speed.is_infinite() {
+            self.current = self.target;
+            self.speed = 0.0;
+            return;
+        }
+        if dt == 0.0 {
+            return;
+        }
+        match &mut self.motion {
+            Motion::Linear | Motion::Deferred => (),
+            Motion::Smooth => {
+                self.current.smooth_nudge(&self.target, speed, dt);
+                if (self.target - self.current).abs() < SMOOTH_EPSILON {
+                    self.current = self.target;
+                    self.speed = 0.0;
+                }
+                return;
+            }
+            Motion::Spring { velocity, damping } => {
+                // Implicit euler, stable for any frame time.
+                let stiffness = speed * speed;
+                *velocity = (*velocity + dt * stiffness * (self.target - self.current))
+                    / (1.0 + 2.0 * *damping * speed * dt + stiffness * dt * dt);
+                self.current += *velocity * dt;
+                if (self.target - self.current).abs() < SMOOTH_EPSILON
+                    && velocity.abs() < SMOOTH_EPSILON
+                {
+                    self.current = self.target;
+                    self.speed = 0.0;
+                    self.motion = Motion::Linear;
+                }
+                return;
+            }
+        }
+        self.current += speed * dt;
+        if (self.speed > 0.0 && self.current >= self.target)
+            || (self.speed < 0.0 && self.current <= self.target)
+        {
+            self.current = self.target;
+            self.speed = 0.0;
+        }
+    }
+}
+
+/// Distance to the target at which [`Opacity::smooth_to`] and [`Opacity::spring_to`] snap and stop.
+const SMOOTH_EPSILON: f32 = 0.001;
+
+/// Returns `time` if it is a valid fade duration in seconds,
+/// otherwise warns and returns `0.0` so the fade completes immediately.
+fn validate_time(time: f32) -> f32 {
+    if time.is_finite() && time >= 0.0 {
+        time
+    } else {
+        warn_once!(
+            "Invalid fade duration {time}, expected a finite duration of zero or more seconds."
+        );
+        0.0
+    }
+}
+
+/// Returns the speed covering `distance` in `time` seconds,
+/// an infinite speed completes the fade on the next update.
+fn fade_speed(distance: f32, time: f32) -> f32 {
+    match validate_time(time) {
+        0.0 if distance < 0.0 => f32::NEG_INFINITY,
+        0.0 => f32::INFINITY,
+        time => distance / time,
+    }
+}
+
+/// [`fade_speed`] of a fade from `0.0` to `1.0` in const contexts.
+const fn fade_in_speed(time: f32) -> f32 {
+    if time > 0.0 {
+        1.0 / time
+    } else {
+        f32::INFINITY
     }
 }
 
@@ -159,7 +611,76 @@ impl Default for Opacity {
     }
 }
 
-#[cfg(feature = "serde")]
+impl From<f32> for Opacity {
+    fn from(opacity: f32) -> Self {
+        Opacity::new(opacity)
+    }
+}
+
+/// Interpolates `current`, `target` and fade speed,
+/// `despawns` and the kind of motion are taken from the closer of the two values.
+impl StableInterpolate for Opacity {
+    fn interpolate_stable(&self, other: &Self, t: f32) -> Self {
+        Opacity {
+            current: self.current.interpolate_stable(&other.current, t),
+            target: self.target.interpolate_stable(&other.target, t),
+            speed: self.speed.interpolate_stable(&other.speed, t),
+            despawns: if t < 0.5 {
+                self.despawns
+            } else {
+                other.despawns
+            },
+            motion: if t < 0.5 { self.motion } else { other.motion },
+        }
+    }
+}
+
+impl Ease for Opacity {
+    fn interpolating_curve_unbounded(start: Self, end: Self) -> impl Curve<Self> {
+        FunctionCurve::new(Interval::EVERYWHERE, move |t| {
+            start.interpolate_stable(&end, t)
+        })
+    }
+}
+
+/// Adds opacity values, the result is not fading.
+impl Add for Opacity {
+    type Output = Opacity;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Opacity::new(self.current + rhs.current)
+    }
+}
+
+/// Subtracts opacity values, the result is not fading.
+impl Sub for Opacity {
+    type Output = Opacity;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Opacity::new(self.current - rhs.current)
+    }
+}
+
+/// Multiplies opacity values like propagation does, the result is not fading.
+impl Mul for Opacity {
+    type Output = Opacity;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Opacity::new(self.current * rhs.current)
+    }
+}
+
+/// Scales the opacity value, the result is not fading.
+impl Mul<f32> for Opacity {
+    type Output = Opacity;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Opacity::new(self.current * rhs)
+    }
+}
+
+/// Serializes only the target opacity, loaded values are not interpolated.
+#[cfg(all(feature = "serde", not(feature = "serde_full")))]
 const _: () = {
     use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -176,200 +697,1884 @@ const _: () = {
     }
 };
 
-/// A map of entity to opacity, if not present, the entity does not have an opacity root node.
-/// This means the entity is out of the scope of this crate and should not be handled.
-#[derive(Debug, Resource, Default)]
-pub struct OpacityMap(EntityHashMap<f32>);
+/// Serializes the entire state, fades in progress and pending despawns are preserved.
+#[cfg(feature = "serde_full")]
+const _: () = {
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-/// [`SystemSet`] of opacity,
-/// runs in [`PostUpdate`] between transform propagation and visibility calculation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, SystemSet)]
-pub enum OpacitySet {
-    Fading,
-    PostFade,
-    Calculate,
-    Apply,
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Opacity")]
+    struct OpacityState {
+        current: f32,
+        target: f32,
+        #[serde(default)]
+        speed: f32,
+        #[serde(default)]
+        despawns: bool,
+        #[serde(default)]
+        motion: Motion,
+    }
+
+    impl Serialize for Opacity {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            OpacityState {
+                current: self.current,
+                target: self.target,
+                speed: self.speed,
+                despawns: self.despawns,
+                motion: self.motion,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Opacity {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let state = OpacityState::deserialize(deserializer)?;
+            Ok(Opacity {
+                current: state.current,
+                target: state.target,
+                speed: state.speed,
+                despawns: state.despawns,
+                motion: state.motion,
+            })
+        }
+    }
+};
+
+/// A global multiplier applied to every computed opacity value,
+/// for example to dim the entire world behind a pause menu.
+///
+/// Entities with [`IgnoreGlobalOpacity`] and their descendants are not affected.
+///
+/// Defaults to `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct GlobalOpacity(pub f32);
+
+impl Default for GlobalOpacity {
+    fn default() -> Self {
+        GlobalOpacity(1.0)
+    }
 }
 
-/// A [`QueryData`] with an opacity value.
-pub trait OpacityQuery: QueryData + Send + Sync {
-    type Cx: SystemParam;
+/// Marker [`Component`] that exempts this entity and its descendants from [`GlobalOpacity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct IgnoreGlobalOpacity;
 
-    fn apply_opacity(
-        this: &mut Self::Item<'_>,
-        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
-        opacity: f32,
-    );
+/// Marker [`Component`] that keeps the authored alpha of this entity,
+/// registered types and built-in targets on it are not written to.
+///
+/// Unlike [`OpacityInherit::Skip`], descendants still inherit opacity through this entity
+/// and its computed opacity is still in [`OpacityMap`],
+/// custom systems reading the map can honor it with `Without<OpacityIgnore>`.
+/// Insert it before the entity is faded, values already written are not restored.
+///
+/// On a faded root, only the contents fade, for example a window frame that stays solid while loading:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, OpacityIgnore};
+/// fn spawn_window(mut commands: Commands) {
+///     commands.spawn((
+///         Node::default(),
+///         BackgroundColor(Color::BLACK),
+///         Opacity::new_fade_in(0.5),
+///         OpacityIgnore,
+///         children![Text::new("Loading")],
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct OpacityIgnore;
+
+/// Makes this entity and its descendants follow the computed opacity of another entity,
+/// for example a health bar following its owner outside of its hierarchy.
+///
+/// [`Opacity`] on this entity or its descendants is multiplied with the target's opacity.
+/// Chaining [`OpacityTarget`]s is not supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct OpacityTarget(pub Entity);
+
+/// Controls how this entity inherits opacity from its ancestors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub enum OpacityInherit {
+    /// Multiply with the opacity of ancestors.
+    #[default]
+    Inherit,
+    /// Ignore the opacity of ancestors, this entity and its descendants start at `1.0`.
+    Isolated,
+    /// This entity is not affected by opacity,
+    /// descendants ignore the opacity of ancestors of this entity.
+    Skip,
 }
 
-/// An [`Asset`] with an opacity value.
-pub trait OpacityAsset: Asset {
-    fn apply_opacity(&mut self, opacity: f32);
+/// Marker [`Component`] that pins the start of propagation to this entity,
+/// the opacity of its ancestors is ignored.
+///
+/// Without this marker, propagation starts at the topmost entity with [`Opacity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct OpacityRoot;
+
+/// Determines how the [`Opacity`] of this entity is combined with the opacity inherited from ancestors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub enum OpacityBlend {
+    /// Multiply with the inherited opacity.
+    #[default]
+    Multiply,
+    /// Use the smaller of the two values.
+    Min,
+    /// Use the larger of the two values.
+    Max,
+    /// Replace the inherited opacity.
+    Override,
 }
 
-fn interpolate(
-    mut commands: Commands,
-    time: Res<Time<Virtual>>,
-    mut query: Query<(Entity, &mut Opacity)>,
-) {
-    let dt = time.delta_secs();
-    for (entity, mut opacity) in &mut query {
-        match opacity.speed {
-            0.0 => continue,
-            s if s > 0.0 => {
-                opacity.current += opacity.speed * dt;
-                if opacity.current > opacity.target {
-                    opacity.current = opacity.target;
-                    opacity.speed = 0.0;
-                }
-            }
-            _ => {
-                opacity.current += opacity.speed * dt;
-                if opacity.current < opacity.target {
-                    opacity.current = opacity.target;
-                    opacity.speed = 0.0;
-                }
-            }
-        }
-        if opacity.despawns && opacity.current <= 0.0 {
-            commands.entity(entity).try_despawn();
+impl OpacityBlend {
+    /// Combine an inherited opacity with this entity's opacity.
+    pub fn blend(&self, inherited: f32, opacity: f32) -> f32 {
+        match self {
+            OpacityBlend::Multiply => inherited * opacity,
+            OpacityBlend::Min => inherited.min(opacity),
+            OpacityBlend::Max => inherited.max(opacity),
+            OpacityBlend::Override => opacity,
         }
     }
 }
 
-fn calculate_opacity(
-    mut map: ResMut<OpacityMap>,
-    query: Query<(Entity, &Opacity)>,
-    children: Query<&Children>,
-) {
-    map.0.clear();
-    let mut stack = Vec::new();
-    for (entity, opacity) in &query {
-        if map.0.contains_key(&entity) {
-            continue;
-        }
-        stack.push((entity, opacity.get()));
-        while let Some((entity, opacity)) = stack.pop() {
-            map.0.insert(entity, opacity);
-            if let Ok(children) = children.get(entity) {
-                for entity in children.iter().copied() {
-                    let op = query.get(entity).map(|(_, x)| x.get()).unwrap_or(1.);
-                    stack.push((entity, opacity * op));
-                }
-            }
-        }
+/// Scales how much of the inherited opacity this entity receives,
+/// without changing its own [`Opacity`].
+///
+/// At `1.0` the inherited opacity is used as is, at `0.5` this entity only fades half as much
+/// as its parent, at `0.0` it ignores the opacity of its ancestors.
+/// Unlike a child [`Opacity`], which multiplies, this scales the response to a fade.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct OpacityWeight(pub f32);
+
+impl Default for OpacityWeight {
+    fn default() -> Self {
+        OpacityWeight(1.0)
     }
 }
 
-/// Add support for writing opacity to a [`QueryData`].
-#[derive(Debug)]
-pub(crate) struct OpacityQueryPlugin<C: OpacityQuery>(PhantomData<C>);
-
-impl<C: OpacityQuery + 'static> Plugin for OpacityQueryPlugin<C> {
-    fn build(&self, app: &mut App) {
-        app.add_systems(
-            PostUpdate,
-            apply_opacity_query::<C>.in_set(OpacitySet::Apply),
-        );
+impl OpacityWeight {
+    /// Scale an inherited opacity by this weight.
+    pub fn scale(&self, inherited: f32) -> f32 {
+        1.0 - (1.0 - inherited) * self.0
     }
 }
 
-fn apply_opacity_query<Q: OpacityQuery>(
-    map: Res<OpacityMap>,
-    cx: StaticSystemParam<Q::Cx>,
-    mut query: Query<(Entity, Q)>,
-) {
-    let mut cx = cx.into_inner();
-    for (entity, mut component) in &mut query {
-        if let Some(opacity) = map.0.get(&entity) {
-            Q::apply_opacity(&mut component, &mut cx, *opacity);
-        }
+/// Clamps the computed opacity of this entity, for example to cap ghost units and placement previews,
+/// or to keep an important marker visible while the rest of its panel fades out.
+///
+/// The limit applies during propagation after inheritance and blending, so it holds even when
+/// ancestors are fully opaque or [`Opacity::fade_in`] targets `1.0`.
+/// Descendants inherit the limited value.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct OpacityLimit {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for OpacityLimit {
+    fn default() -> Self {
+        OpacityLimit { min: 0.0, max: 1.0 }
     }
 }
 
-/// Plugin for [`bevy_mod_opacity`](crate) that adds support for basic bevy types.
-pub struct OpacityPlugin;
+impl OpacityLimit {
+    /// Limit opacity to between `min` and `max`.
+    pub const fn new(min: f32, max: f32) -> Self {
+        OpacityLimit { min, max }
+    }
 
-/// Extensions for [`App`].
-pub trait OpacityExtension {
-    fn register_opacity<Q: OpacityQuery + 'static>(&mut self) -> &mut Self;
-    fn register_opacity_component<C: Component>(&mut self) -> &mut Self
-    where
-        &'static mut C: OpacityQuery;
-    #[cfg(feature = "2d")]
-    fn register_opacity_material2d<M: bevy::sprite::Material2d + OpacityAsset>(
-        &mut self,
-    ) -> &mut Self;
-    #[cfg(feature = "3d")]
-    fn register_opacity_material3d<M: bevy::pbr::Material + OpacityAsset>(&mut self) -> &mut Self;
+    /// Limit opacity to at most `max`.
+    pub const fn max(max: f32) -> Self {
+        OpacityLimit { min: 0.0, max }
+    }
+
+    /// Limit opacity to at least `min`.
+    pub const fn min(min: f32) -> Self {
+        OpacityLimit { min, max: 1.0 }
+    }
+
+    /// Clamp a computed opacity to this limit, `max` wins if `min` is larger.
+    pub fn clamp(&self, opacity: f32) -> f32 {
+        opacity.max(self.min).min(self.max)
+    }
 }
 
-impl OpacityExtension for App {
-    fn register_opacity<Q: OpacityQuery + 'static>(&mut self) -> &mut Self {
-        self.add_plugins(OpacityQueryPlugin::<Q>(PhantomData));
-        self
+/// A map of entity to opacity, if not present, the entity does not have an opacity root node.
+/// This means the entity is out of the scope of this crate and should not be handled.
+///
+/// Only updated on frames where an [`Opacity`] or the hierarchy changed,
+/// registered queries are only written to when this changes.
+/// Entries persist across frames, only subtrees containing a change are recomputed,
+/// changes to [`GlobalOpacity`] or [`OpacityGroups`] recompute every entry.
+///
+/// Can be modified in [`OpacitySet::Modify`] before values are written.
+#[derive(Debug, Resource, Default)]
+pub struct OpacityMap(EntityHashMap<f32>, EntityHashSet);
+
+impl OpacityMap {
+    /// Returns the computed opacity of an entity.
+    pub fn get(&self, entity: Entity) -> Option<f32> {
+        self.0.get(&entity).copied()
     }
 
-    fn register_opacity_component<C: Component>(&mut self) -> &mut Self
-    where
-        &'static mut C: OpacityQuery,
-    {
-        self.add_plugins(OpacityQueryPlugin::<&mut C>(PhantomData));
-        self
+    /// Iterate over all entities with a computed opacity.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, f32)> + '_ {
+        self.0.iter().map(|(entity, opacity)| (*entity, *opacity))
     }
 
-    #[cfg(feature = "2d")]
-    fn register_opacity_material2d<M: bevy::sprite::Material2d + OpacityAsset>(
-        &mut self,
-    ) -> &mut Self {
-        self.add_plugins(OpacityQueryPlugin::<&bevy::sprite::MeshMaterial2d<M>>(
-            PhantomData,
-        ));
-        self
+    /// Returns a mutable reference to the computed opacity of an entity.
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut f32> {
+        self.0.get_mut(&entity)
     }
 
-    #[cfg(feature = "3d")]
-    fn register_opacity_material3d<M: bevy::pbr::Material + OpacityAsset>(&mut self) -> &mut Self {
-        self.add_plugins(OpacityQueryPlugin::<&bevy::pbr::MeshMaterial3d<M>>(
-            PhantomData,
-        ));
-        self
+    /// Iterate over entities whose opacity was recomputed this frame.
+    ///
+    /// Other entries keep the value written to them in a previous frame,
+    /// modifying only these entries in [`OpacitySet::Modify`] applies each modification once.
+    pub fn iter_updated_mut(&mut self) -> impl Iterator<Item = (Entity, &mut f32)> + '_ {
+        let OpacityMap(map, updated) = self;
+        map.iter_mut()
+            .filter(|(entity, _)| updated.contains(*entity))
+            .map(|(entity, opacity)| (*entity, opacity))
+    }
+
+    fn insert(&mut self, entity: Entity, opacity: f32) {
+        self.0.insert(entity, opacity);
+        self.1.insert(entity);
     }
 }
 
-#[cfg(any(feature = "2d", feature = "ui"))]
-impl OpacityQuery for &mut bevy::text::TextColor {
-    type Cx = ();
+/// A [`SystemParam`] for reading opacity values computed by this crate.
+///
+/// Values are available after [`OpacitySet::Modify`].
+#[derive(SystemParam)]
+pub struct OpacityReader<'w> {
+    map: Res<'w, OpacityMap>,
+}
+
+impl OpacityReader<'_> {
+    /// Returns the computed opacity of an entity,
+    /// `None` if the entity does not have an [`Opacity`] ancestor.
+    pub fn get(&self, entity: Entity) -> Option<f32> {
+        self.map.get(entity)
+    }
+
+    /// Returns true if the entity has a computed opacity.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.map.0.contains_key(&entity)
+    }
+
+    /// Iterate over all entities with a computed opacity.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, f32)> + '_ {
+        self.map.iter()
+    }
+
+    /// Returns the number of entities with a computed opacity.
+    pub fn len(&self) -> usize {
+        self.map.0.len()
+    }
 
-    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
-        this.set_alpha(opacity);
+    /// Returns true if no entity has a computed opacity.
+    pub fn is_empty(&self) -> bool {
+        self.map.0.is_empty()
+    }
+}
+
+/// A [`SystemParam`] for fading many unrelated entities,
+/// [`Opacity`] is inserted as [`Opacity::OPAQUE`] on entities that do not have it.
+///
+/// Entities with [`Opacity`] are written to immediately, others when commands are applied.
+/// Entities that do not exist are ignored.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::OpacityWriter;
+/// #[derive(Component)]
+/// struct Enemy;
+///
+/// fn clear_wave(mut writer: OpacityWriter, enemies: Query<Entity, With<Enemy>>) {
+///     for enemy in &enemies {
+///         writer.fade_out(enemy, 0.5);
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct OpacityWriter<'w, 's> {
+    query: Query<'w, 's, &'static mut Opacity>,
+    commands: Commands<'w, 's>,
+}
+
+impl OpacityWriter<'_, '_> {
+    /// Modify the [`Opacity`] of an entity, inserting it if missing.
+    pub fn modify(&mut self, entity: Entity, f: impl FnOnce(&mut Opacity) + Send + 'static) {
+        if let Ok(mut opacity) = self.query.get_mut(entity) {
+            f(&mut opacity);
+            return;
+        }
+        self.commands.entity(entity).queue_handled(
+            move |mut entity: EntityWorldMut| {
+                let mut opacity = entity.get::<Opacity>().copied().unwrap_or_default();
+                f(&mut opacity);
+                entity.insert(opacity);
+            },
+            ignore,
+        );
+    }
+
+    /// Set the opacity of an entity and cancel interpolation, see [`Opacity::set`].
+    pub fn set(&mut self, entity: Entity, opacity: f32) {
+        self.modify(entity, move |current| current.set(opacity));
+    }
+
+    /// Interpolate the opacity of an entity to `1.0`, see [`Opacity::fade_in`].
+    pub fn fade_in(&mut self, entity: Entity, time: f32) {
+        self.modify(entity, move |opacity| opacity.fade_in(time));
+    }
+
+    /// Interpolate the opacity of an entity to `0.0` and despawn it, see [`Opacity::fade_out`].
+    pub fn fade_out(&mut self, entity: Entity, time: f32) {
+        self.modify(entity, move |opacity| opacity.fade_out(time));
+    }
+
+    /// Interpolate the opacity of an entity to a specific value, see [`Opacity::interpolate_to`].
+    pub fn interpolate_to(&mut self, entity: Entity, opacity: f32, time: f32) {
+        self.modify(entity, move |current| current.interpolate_to(opacity, time));
+    }
+
+    /// Interpolate the opacity of every entity to a specific value.
+    pub fn interpolate_many(
+        &mut self,
+        entities: impl IntoIterator<Item = Entity>,
+        opacity: f32,
+        time: f32,
+    ) {
+        for entity in entities {
+            self.interpolate_to(entity, opacity, time);
+        }
+    }
+}
+
+/// [`SystemSet`] of opacity,
+/// runs in [`PostUpdate`] between transform propagation and visibility calculation
+/// unless configured by [`OpacityPlugin::in_schedule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, SystemSet)]
+pub enum OpacitySet {
+    Fading,
+    PostFade,
+    Calculate,
+    /// Runs after [`OpacityMap`] is computed and before it is written,
+    /// systems here can post-process opacity through [`OpacityMap::iter_updated_mut`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::{OpacityMap, OpacitySet};
+    /// // Quantize opacity to bands for a stylized look.
+    /// fn quantize(mut map: ResMut<OpacityMap>) {
+    ///     for (_, opacity) in map.iter_updated_mut() {
+    ///         *opacity = (*opacity * 4.0).round() / 4.0;
+    ///     }
+    /// }
+    ///
+    /// # let mut app = App::new();
+    /// app.add_systems(
+    ///     PostUpdate,
+    ///     quantize
+    ///         .run_if(resource_changed::<OpacityMap>)
+    ///         .in_set(OpacitySet::Modify),
+    /// );
+    /// ```
+    Modify,
+    Apply,
+}
+
+/// A [`QueryData`] with an opacity value.
+pub trait OpacityQuery: QueryData + Send + Sync {
+    type Cx: SystemParam;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    );
+}
+
+/// An [`Asset`] with an opacity value.
+pub trait OpacityAsset: Asset {
+    fn apply_opacity(&mut self, opacity: f32);
+}
+
+/// Set of [`skip_small_updates`], after [`OpacitySet::Modify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub(crate) struct SkipUpdates;
+
+/// Set of the systems that advance fades,
+/// systems that drive fade targets run before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub(crate) struct Interpolation;
+
+/// Scales the speed of all fades independent of the clock driving them,
+/// for example to freeze fades in tools or fast-forward them in replays.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityTimeScale {
+    scale: f32,
+    paused: bool,
+}
+
+impl OpacityTimeScale {
+    /// Returns the speed multiplier of fades, ignoring pause state.
+    pub const fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Set the speed multiplier of fades.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    /// Returns true if fades are paused.
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause all fades.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume all fades.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns the scaled delta time.
+    pub fn scale_delta(&self, dt: f32) -> f32 {
+        if self.paused {
+            0.0
+        } else {
+            dt * self.scale
+        }
+    }
+}
+
+impl Default for OpacityTimeScale {
+    fn default() -> Self {
+        OpacityTimeScale {
+            scale: 1.0,
+            paused: false,
+        }
+    }
+}
+
+/// Accessibility option that shortens fades, for example to honor
+/// an operating system's reduced motion preference.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub enum ReducedMotion {
+    /// Fades play at their normal speed.
+    #[default]
+    Disabled,
+    /// Fades complete immediately.
+    Instant,
+    /// Fades are at least as fast as fading from `0.0` to `1.0` in this many seconds.
+    Capped(f32),
+}
+
+impl ReducedMotion {
+    /// Returns the modified fade speed in opacity per second.
+    pub fn speed(&self, speed: f32) -> f32 {
+        match *self {
+            ReducedMotion::Disabled => speed,
+            ReducedMotion::Instant => speed.signum() * f32::INFINITY,
+            ReducedMotion::Capped(time) => speed.signum() * speed.abs().max(1.0 / time),
+        }
+    }
+}
+
+/// Durations in seconds of [`Opacity::show`] and [`Opacity::hide`],
+/// so the feel of every default fade can be changed in one place.
+///
+/// Defaults to `0.25` seconds for both.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct DefaultFadeDurations {
+    pub show: f32,
+    pub hide: f32,
+}
+
+impl Default for DefaultFadeDurations {
+    fn default() -> Self {
+        DefaultFadeDurations {
+            show: 0.25,
+            hide: 0.25,
+        }
+    }
+}
+
+/// Marker [`Component`] that makes fades of this entity use [`Time<Real>`],
+/// unaffected by the speed or pause state of virtual time.
+///
+/// Only the fade of this entity's own [`Opacity`] is affected, not its descendants'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct FadeUnscaled;
+
+/// Marker [`Component`] that suspends the fade of this entity's [`Opacity`],
+/// the fade continues where it stopped when removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct FadePaused;
+
+fn interpolate<C: Default + Send + Sync + 'static, F: QueryFilter>(
+    mut commands: Commands,
+    time: Res<Time<C>>,
+    scale: Res<OpacityTimeScale>,
+    (reduced_motion, durations): (Res<ReducedMotion>, Res<DefaultFadeDurations>),
+    config: Res<OpacityConfig>,
+    mut query: Query<(Entity, &mut Opacity, Has<OnFadeComplete>), F>,
+) {
+    let dt = scale.scale_delta(time.delta_secs());
+    for (entity, mut opacity, callback) in &mut query {
+        if opacity.speed == 0.0 {
+            continue;
+        }
+        opacity.resolve_default(&durations);
+        opacity.advance(dt, &reduced_motion);
+        if opacity.despawns && opacity.current <= 0.0 && config.despawn_on_fade_out && !callback {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+/// Modifiers applied on top of hierarchical opacity.
+#[derive(SystemParam)]
+struct OpacityModifiers<'w, 's> {
+    global: Res<'w, GlobalOpacity>,
+    groups: Res<'w, OpacityGroups>,
+    ignored: Query<'w, 's, (), With<IgnoreGlobalOpacity>>,
+    proxies: Query<'w, 's, (Entity, &'static OpacityTarget)>,
+    #[cfg(feature = "render")]
+    layers: layers::GlobalLayers<'w, 's>,
+}
+
+impl OpacityModifiers<'_, '_> {
+    fn is_changed(&mut self) -> bool {
+        let changed = self.global.is_changed() || self.groups.is_changed();
+        #[cfg(feature = "render")]
+        let changed = self.layers.is_changed() || changed;
+        changed
+    }
+}
+
+/// An entity in the opacity hierarchy.
+#[derive(QueryData)]
+pub(crate) struct OpacityNode {
+    entity: Entity,
+    opacity: Option<&'static Opacity>,
+    group: Option<&'static OpacityGroup>,
+    inherit: Option<&'static OpacityInherit>,
+    blend: Option<&'static OpacityBlend>,
+    weight: Option<&'static OpacityWeight>,
+    limit: Option<&'static OpacityLimit>,
+    root: Has<OpacityRoot>,
+}
+
+impl OpacityNodeItem<'_> {
+    /// Returns the opacity of this entity without its ancestors.
+    fn local(&self, groups: &OpacityGroups) -> f32 {
+        self.opacity.map(Opacity::get).unwrap_or(1.0) * groups.multiplier(self.group)
+    }
+
+    /// Returns the opacity of this entity with an inherited value.
+    fn blend(&self, inherited: f32, groups: &OpacityGroups) -> f32 {
+        let inherited = self
+            .weight
+            .map_or(inherited, |weight| weight.scale(inherited));
+        let opacity = self
+            .blend
+            .copied()
+            .unwrap_or_default()
+            .blend(inherited, self.local(groups));
+        self.limit.map_or(opacity, |limit| limit.clamp(opacity))
+    }
+
+    fn inherit(&self) -> OpacityInherit {
+        self.inherit.copied().unwrap_or_default()
+    }
+
+    /// Returns true if this entity receives opacity from its parent.
+    fn inherits(&self) -> bool {
+        !self.root && self.inherit() == OpacityInherit::Inherit
+    }
+}
+
+/// Returns true if propagation starts at this entity.
+///
+/// Every entity is reached from exactly one root, so the result does not depend on iteration order.
+fn is_root(node: &OpacityNodeItem, nodes: &Query<OpacityNode>, parents: &Query<&ChildOf>) -> bool {
+    if node.root {
+        return node.inherit() != OpacityInherit::Skip;
+    }
+    match node.inherit() {
+        OpacityInherit::Skip => return false,
+        OpacityInherit::Isolated => return true,
+        OpacityInherit::Inherit => (),
+    }
+    for ancestor in nodes.iter_many(parents.iter_ancestors(node.entity)) {
+        if ancestor.root {
+            return ancestor.inherit() == OpacityInherit::Skip;
+        }
+        match ancestor.inherit() {
+            OpacityInherit::Skip => return true,
+            OpacityInherit::Isolated => return false,
+            OpacityInherit::Inherit if ancestor.opacity.is_some() => return false,
+            OpacityInherit::Inherit => (),
+        }
+    }
+    true
+}
+
+type MarkerChanged = Or<(
+    Changed<IgnoreGlobalOpacity>,
+    Changed<OpacityGroup>,
+    Changed<OpacityTarget>,
+    Changed<OpacityInherit>,
+    Changed<OpacityBlend>,
+    Changed<OpacityWeight>,
+    Changed<OpacityLimit>,
+    Changed<OpacityRoot>,
+)>;
+
+type RootFilter = Or<(With<Opacity>, With<OpacityInherit>, With<OpacityRoot>)>;
+
+/// Run condition for the propagation step, returns false on idle frames
+/// where no opacity or hierarchy has changed.
+fn opacity_changed(
+    mut modifiers: OpacityModifiers,
+    mut dirty: DirtyEntities,
+    #[cfg(feature = "render")] hidden: visibility::HiddenEntities,
+) -> bool {
+    let result = modifiers.is_changed() || !dirty.is_empty();
+    #[cfg(feature = "render")]
+    let result = result
+        || hidden.is_changed()
+        || hidden.changed().next().is_some()
+        || modifiers.layers.changed().next().is_some();
+    dirty.clear();
+    result
+}
+
+type Dirty = Or<(
+    Changed<Opacity>,
+    Changed<ChildOf>,
+    Changed<Children>,
+    MarkerChanged,
+)>;
+
+/// Entities whose propagation root must be recomputed.
+#[derive(SystemParam)]
+struct DirtyEntities<'w, 's> {
+    changed: Query<'w, 's, Entity, Dirty>,
+    removed: RemovedComponents<'w, 's, Opacity>,
+    removed_ignored: RemovedComponents<'w, 's, IgnoreGlobalOpacity>,
+    removed_targets: RemovedComponents<'w, 's, OpacityTarget>,
+    removed_parents: RemovedComponents<'w, 's, ChildOf>,
+}
+
+impl DirtyEntities<'_, '_> {
+    fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+            && self.removed.is_empty()
+            && self.removed_ignored.is_empty()
+            && self.removed_targets.is_empty()
+            && self.removed_parents.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.removed.clear();
+        self.removed_ignored.clear();
+        self.removed_targets.clear();
+        self.removed_parents.clear();
+    }
+
+    fn iter(&mut self) -> impl Iterator<Item = Entity> + '_ {
+        self.changed
+            .iter()
+            .chain(self.removed.read())
+            .chain(self.removed_ignored.read())
+            .chain(self.removed_targets.read())
+            .chain(self.removed_parents.read())
+    }
+}
+
+/// Entities in [`OpacityMap`] overwritten after propagation,
+/// by [`OpacityTarget`] or a registered relationship.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct ProxiedEntities(pub(crate) Vec<Entity>);
+
+/// Bookkeeping of [`calculate_opacity`] kept across frames,
+/// so only subtrees of changed roots are recomputed.
+#[derive(Default)]
+pub(crate) struct PropagationState {
+    /// The root each entity in [`OpacityMap`] was propagated from.
+    owners: EntityHashMap<Entity>,
+    /// The entities propagated from each root.
+    members: EntityHashMap<Vec<Entity>>,
+    dirty: EntityHashSet,
+    results: Vec<(Entity, Entity, f32)>,
+    queue: Parallel<Vec<(Entity, Entity, f32)>>,
+}
+
+impl PropagationState {
+    /// Remove every entity propagated from a root, returns the removed entities.
+    fn remove_root(&mut self, map: &mut OpacityMap, root: Entity) -> Vec<Entity> {
+        let members = self.members.remove(&root).unwrap_or_default();
+        for member in &members {
+            map.0.remove(member);
+            self.owners.remove(member);
+        }
+        members
+    }
+}
+
+/// Returns the root propagating to this entity.
+fn find_root(
+    entity: Entity,
+    roots: &Query<OpacityNode, RootFilter>,
+    nodes: &Query<OpacityNode>,
+    parents: &Query<&ChildOf>,
+) -> Option<Entity> {
+    std::iter::once(entity)
+        .chain(parents.iter_ancestors(entity))
+        .find(|entity| {
+            roots
+                .get(*entity)
+                .is_ok_and(|node| is_root(&node, nodes, parents))
+        })
+}
+
+pub(crate) fn calculate_opacity(
+    mut map: ResMut<OpacityMap>,
+    mut proxied: ResMut<ProxiedEntities>,
+    mut modifiers: OpacityModifiers,
+    mut dirty: DirtyEntities,
+    (roots, nodes, parents, children): (
+        Query<OpacityNode, RootFilter>,
+        Query<OpacityNode>,
+        Query<&ChildOf>,
+        Query<&Children>,
+    ),
+    #[cfg(feature = "render")] hidden: visibility::HiddenEntities,
+    mut state: Local<PropagationState>,
+) {
+    let full = modifiers.is_changed();
+    #[cfg(feature = "render")]
+    let full = full || hidden.is_changed();
+    #[cfg(feature = "render")]
+    let is_hidden = |entity| hidden.contains(entity);
+    #[cfg(not(feature = "render"))]
+    let is_hidden = |_: Entity| false;
+    let OpacityModifiers {
+        global,
+        groups,
+        ignored,
+        proxies,
+        #[cfg(feature = "render")]
+        layers,
+    } = modifiers;
+    #[cfg(feature = "render")]
+    let applies_global = |entity| layers.contains(entity);
+    #[cfg(not(feature = "render"))]
+    let applies_global = |_: Entity| true;
+    let state = &mut *state;
+    let propagate = |root: &OpacityNodeItem, buffer: &mut Vec<(Entity, Entity, f32)>| {
+        if is_hidden(root.entity) {
+            return;
+        }
+        let ignores_global = parents
+            .iter_ancestors(root.entity)
+            .any(|entity| ignored.contains(entity));
+        let mut stack = vec![(root.entity, root.local(&groups), ignores_global)];
+        while let Some((entity, opacity, ignores_global)) = stack.pop() {
+            let ignores_global = ignores_global || ignored.contains(entity);
+            if ignores_global || !applies_global(entity) {
+                buffer.push((root.entity, entity, opacity));
+            } else {
+                buffer.push((root.entity, entity, opacity * global.0));
+            }
+            let Ok(children) = children.get(entity) else {
+                continue;
+            };
+            for child in nodes.iter_many(children) {
+                if child.inherits() && !is_hidden(child.entity) {
+                    stack.push((child.entity, child.blend(opacity, &groups), ignores_global));
+                }
+            }
+        }
+    };
+    if full {
+        // Modifiers can affect every entity, rebuild everything.
+        map.0.clear();
+        state.owners.clear();
+        state.members.clear();
+        proxied.0.clear();
+        dirty.clear();
+        // Each root is propagated independently, similar to transform propagation.
+        roots.par_iter().for_each(|root| {
+            if is_root(&root, &nodes, &parents) {
+                state.queue.scope(|buffer| propagate(&root, buffer));
+            }
+        });
+    } else {
+        state.dirty.clear();
+        // Restore entities overwritten by proxies, they are overwritten again below.
+        for entity in proxied.0.drain(..) {
+            match state.owners.get(&entity) {
+                Some(root) => {
+                    state.dirty.insert(*root);
+                }
+                None => {
+                    map.0.remove(&entity);
+                }
+            }
+        }
+        let changed = dirty.iter();
+        #[cfg(feature = "render")]
+        let changed = changed.chain(hidden.changed()).chain(layers.changed());
+        for entity in changed {
+            if let Some(root) = state.owners.get(&entity) {
+                state.dirty.insert(*root);
+            }
+            if let Some(root) = find_root(entity, &roots, &nodes, &parents) {
+                state.dirty.insert(root);
+            }
+        }
+        // Entities of a dirty root may have become roots themselves.
+        let mut split = Vec::new();
+        for root in state.dirty.iter().copied().collect::<Vec<_>>() {
+            for member in state.remove_root(&mut map, root) {
+                if member != root
+                    && roots
+                        .get(member)
+                        .is_ok_and(|node| is_root(&node, &nodes, &parents))
+                {
+                    split.push(member);
+                }
+            }
+        }
+        state.dirty.extend(split);
+        for root in roots.iter_many(state.dirty.iter()) {
+            if is_root(&root, &nodes, &parents) {
+                state.queue.scope(|buffer| propagate(&root, buffer));
+            }
+        }
+    }
+    state.results.clear();
+    state.results.extend(state.queue.drain());
+    // Roots that are now propagated from a dirty root.
+    let absorbed: Vec<_> = state
+        .results
+        .iter()
+        .filter(|(root, entity, _)| root != entity && state.members.contains_key(entity))
+        .map(|(_, entity, _)| *entity)
+        .collect();
+    for root in absorbed {
+        state.remove_root(&mut map, root);
+    }
+    for (root, entity, opacity) in state.results.drain(..) {
+        map.insert(entity, opacity);
+        state.owners.insert(entity, root);
+        state.members.entry(root).or_default().push(entity);
+    }
+    for (proxy, OpacityTarget(target)) in &proxies {
+        if let (Some(opacity), Ok(proxy)) = (map.get(*target), nodes.get(proxy)) {
+            propagate_subtree(
+                &mut map,
+                &mut proxied,
+                proxy,
+                opacity,
+                &nodes,
+                &children,
+                &groups,
+            );
+        }
+    }
+}
+
+/// Overwrite the opacity of an entity and its descendants with an inherited value.
+pub(crate) fn propagate_subtree(
+    map: &mut OpacityMap,
+    proxied: &mut ProxiedEntities,
+    node: OpacityNodeItem,
+    inherited: f32,
+    nodes: &Query<OpacityNode>,
+    children: &Query<&Children>,
+    groups: &OpacityGroups,
+) {
+    let mut stack = vec![(node.entity, node.blend(inherited, groups))];
+    while let Some((entity, opacity)) = stack.pop() {
+        map.insert(entity, opacity);
+        proxied.0.push(entity);
+        let Ok(children) = children.get(entity) else {
+            continue;
+        };
+        for child in nodes.iter_many(children) {
+            if child.inherits() {
+                stack.push((child.entity, child.blend(opacity, groups)));
+            }
+        }
+    }
+}
+
+/// Opacity of entities spawned after [`OpacitySet::Apply`] ran this frame,
+/// written once in [`Last`] so they never render a frame at full opacity.
+///
+/// Proxies are not taken into account, the next propagation pass computes the exact value.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct LateOpacity {
+    /// Entities to write and whether to also write their descendants when they were propagated.
+    pending: Vec<(Entity, bool)>,
+    values: EntityHashMap<f32>,
+}
+
+impl LateOpacity {
+    /// Write the opacity of an entity and its descendants in [`Last`] if it was not propagated this frame.
+    pub(crate) fn queue(&mut self, entity: Entity) {
+        self.pending.push((entity, false));
+    }
+
+    /// Write the opacity of descendants of an entity in [`Last`] that were not propagated this frame.
+    #[cfg(feature = "scene")]
+    pub(crate) fn queue_descendants(&mut self, entity: Entity) {
+        self.pending.push((entity, true));
+    }
+}
+
+/// Set of the systems writing [`LateOpacity`], in [`Last`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub(crate) struct LateApply;
+
+fn queue_late_opacity(mut world: DeferredWorld, context: HookContext) {
+    if let Some(mut late) = world.get_resource_mut::<LateOpacity>() {
+        late.bypass_change_detection().queue(context.entity);
+    }
+}
+
+fn calculate_late_opacity(
+    map: Res<OpacityMap>,
+    mut late: ResMut<LateOpacity>,
+    modifiers: OpacityModifiers,
+    roots: Query<OpacityNode, RootFilter>,
+    (nodes, parents, children): (Query<OpacityNode>, Query<&ChildOf>, Query<&Children>),
+    #[cfg(feature = "render")] hidden: visibility::HiddenEntities,
+) {
+    #[cfg(feature = "render")]
+    let is_hidden = |entity| hidden.contains(entity);
+    #[cfg(not(feature = "render"))]
+    let is_hidden = |_: Entity| false;
+    let LateOpacity { pending, values } = late.bypass_change_detection();
+    values.clear();
+    for (entity, descendants) in pending.drain(..) {
+        if (!descendants && map.0.contains_key(&entity)) || values.contains_key(&entity) {
+            continue;
+        }
+        let Some(root) = find_root(entity, &roots, &nodes, &parents) else {
+            continue;
+        };
+        // Blend from the root down to the entity.
+        let path: Vec<_> = std::iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .take_while(|ancestor| *ancestor != root)
+            .chain(std::iter::once(root))
+            .collect();
+        if path.iter().any(|entity| is_hidden(*entity)) {
+            continue;
+        }
+        let mut opacity = 1.0;
+        for node in path
+            .iter()
+            .rev()
+            .filter_map(|entity| nodes.get(*entity).ok())
+        {
+            opacity = match node.entity == root {
+                true => node.local(&modifiers.groups),
+                false => node.blend(opacity, &modifiers.groups),
+            };
+        }
+        let ignores_global = std::iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .any(|entity| modifiers.ignored.contains(entity));
+        let mut stack = vec![(entity, opacity, ignores_global)];
+        while let Some((entity, opacity, ignores_global)) = stack.pop() {
+            let ignores_global = ignores_global || modifiers.ignored.contains(entity);
+            let exempt = ignores_global;
+            #[cfg(feature = "render")]
+            let exempt = exempt || !modifiers.layers.contains(entity);
+            let global = if exempt { 1.0 } else { modifiers.global.0 };
+            if !map.0.contains_key(&entity) {
+                values.insert(entity, opacity * global);
+            }
+            let Ok(children) = children.get(entity) else {
+                continue;
+            };
+            for child in nodes.iter_many(children) {
+                if child.inherits() && !is_hidden(child.entity) {
+                    let opacity = child.blend(opacity, &modifiers.groups);
+                    stack.push((child.entity, opacity, ignores_global));
+                }
+            }
+        }
+    }
+    if !values.is_empty() {
+        late.set_changed();
+    }
+}
+
+fn apply_late_opacity_query<Q: OpacityQuery, F: QueryFilter>(
+    late: Res<LateOpacity>,
+    cx: StaticSystemParam<Q::Cx>,
+    mut query: Query<Q, (F, Without<OpacityIgnore>)>,
+) {
+    let mut cx = cx.into_inner();
+    for (entity, opacity) in &late.values {
+        if let Ok(mut component) = query.get_mut(*entity) {
+            Q::apply_opacity(&mut component, &mut cx, *opacity);
+        }
+    }
+}
+
+/// Entities in [`OpacityMap`] whose change is below [`OpacityConfig::apply_epsilon`]
+/// or over [`OpacityConfig::apply_budget`], registered queries are not written to for these entities.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct SkippedUpdates {
+    applied: EntityHashMap<f32>,
+    skipped: EntityHashSet,
+    /// Writes limited by the budget, with their opacity, whether they are fading and their change.
+    deferred: Vec<(Entity, f32, bool, f32)>,
+    pending: bool,
+}
+
+impl SkippedUpdates {
+    fn contains(&self, entity: &Entity) -> bool {
+        self.skipped.contains(entity)
+    }
+}
+
+/// Run condition of [`skip_small_updates`] on frames with writes postponed by the budget.
+fn has_pending_updates(updates: Res<SkippedUpdates>) -> bool {
+    updates.pending
+}
+
+/// Forget which entities were recomputed so later [`OpacitySet::Modify`] runs do not modify them again.
+fn clear_updated(mut map: ResMut<OpacityMap>) {
+    if !map.1.is_empty() {
+        map.bypass_change_detection().1.clear();
+    }
+}
+
+/// Returns true if the nearest [`Opacity`] of this entity or its ancestors is fading.
+fn is_fading(entity: Entity, opacities: &Query<&Opacity>, parents: &Query<&ChildOf>) -> bool {
+    std::iter::once(entity)
+        .chain(parents.iter_ancestors(entity))
+        .find_map(|entity| opacities.get(entity).ok())
+        .is_some_and(Opacity::is_fading)
+}
+
+pub(crate) fn skip_small_updates(
+    mut map: ResMut<OpacityMap>,
+    config: Res<OpacityConfig>,
+    mut updates: ResMut<SkippedUpdates>,
+    opacities: Query<&Opacity>,
+    parents: Query<&ChildOf>,
+) {
+    let SkippedUpdates {
+        applied,
+        skipped,
+        deferred,
+        pending,
+    } = &mut *updates;
+    skipped.clear();
+    deferred.clear();
+    *pending = false;
+    if config.apply_epsilon <= 0.0 && config.apply_budget.is_none() {
+        applied.clear();
+        return;
+    }
+    applied.retain(|entity, _| map.0.contains_key(entity));
+    for (entity, opacity) in map.iter() {
+        let last = applied.entry(entity).or_insert(f32::NAN);
+        let change = (opacity - *last).abs();
+        // Always write fully opaque and fully transparent values so fades end exactly.
+        if change < config.apply_epsilon && opacity != 0.0 && opacity != 1.0 {
+            skipped.insert(entity);
+        } else if config.apply_budget.is_some() && !last.is_nan() {
+            if change == 0.0 {
+                skipped.insert(entity);
+            } else {
+                let fading = is_fading(entity, &opacities, &parents);
+                deferred.push((entity, opacity, fading, change));
+            }
+        } else {
+            *last = opacity;
+        }
+    }
+    let Some(budget) = config.apply_budget else {
+        return;
+    };
+    if deferred.is_empty() {
+        return;
+    }
+    if deferred.len() > budget {
+        // Fading entities first, then the largest changes, so every entity is eventually written.
+        deferred.select_nth_unstable_by(budget, |(_, _, a_fading, a), (_, _, b_fading, b)| {
+            b_fading.cmp(a_fading).then(b.total_cmp(a))
+        });
+        skipped.extend(deferred[budget..].iter().map(|(entity, ..)| *entity));
+        *pending = true;
+    }
+    for (entity, opacity, ..) in deferred.iter().take(budget) {
+        applied.insert(*entity, *opacity);
+    }
+    // Registered queries only run when the map changed.
+    map.set_changed();
+}
+
+/// The last opacity written to each asset and the run it was written in.
+struct AppliedAssets<A: Asset, K>(HashMap<AssetId<A>, (f32, K, Tick)>);
+
+impl<A: Asset, K> Default for AppliedAssets<A, K> {
+    fn default() -> Self {
+        AppliedAssets(HashMap::default())
+    }
+}
+
+/// [`Assets`] written to by an [`OpacityQuery`], use in [`OpacityQuery::Cx`].
+///
+/// Assets are only marked as modified and uploaded again when their opacity actually changed,
+/// `K` holds other inputs of the write, like an [`OpacityMode`](crate::OpacityMode).
+///
+/// Each asset is written to at most once per run. When entities sharing an asset
+/// have different opacities, the first entity in query order wins,
+/// give entities their own asset to fade them independently.
+#[derive(SystemParam)]
+pub struct OpacityAssets<'w, 's, A: Asset, K: PartialEq + Send + Sync + 'static = ()> {
+    assets: ResMut<'w, Assets<A>>,
+    applied: Local<'s, AppliedAssets<A, K>>,
+    tick: SystemChangeTick,
+}
+
+impl<A: Asset, K: PartialEq + Send + Sync + 'static> OpacityAssets<'_, '_, A, K> {
+    /// Returns the asset if `opacity` and `key` should be written to it.
+    pub fn get_mut(&mut self, id: impl Into<AssetId<A>>, opacity: f32, key: K) -> Option<&mut A> {
+        let id = id.into();
+        if !self.assets.contains(id) {
+            self.applied.0.remove(&id);
+            return None;
+        }
+        let tick = self.tick.this_run();
+        match self.applied.0.get_mut(&id) {
+            Some((_, _, written)) if *written == tick => return None,
+            Some((last, last_key, written)) if *last == opacity && *last_key == key => {
+                *written = tick;
+                return None;
+            }
+            _ => {
+                self.applied.0.insert(id, (opacity, key, tick));
+            }
+        }
+        self.assets.get_mut(id)
+    }
+}
+
+impl<A: Asset, K: PartialEq + Send + Sync + 'static> Deref for OpacityAssets<'_, '_, A, K> {
+    type Target = Assets<A>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.assets
+    }
+}
+
+impl<A: Asset, K: PartialEq + Send + Sync + 'static> DerefMut for OpacityAssets<'_, '_, A, K> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.assets
+    }
+}
+
+/// A [`QueryData`] of a [`Component`] holding a handle to an [`OpacityAsset`].
+#[derive(Debug, QueryData)]
+pub struct AssetHandleQuery<C: AsAssetId> {
+    pub component: &'static C,
+}
+
+impl<C: AsAssetId> OpacityQuery for AssetHandleQuery<C>
+where
+    C::Asset: OpacityAsset,
+{
+    type Cx = OpacityAssets<'static, 'static, C::Asset>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        if let Some(asset) = cx.get_mut(this.component.as_asset_id(), opacity, ()) {
+            asset.apply_opacity(opacity);
+        }
+    }
+}
+
+/// Add support for writing opacity to a [`QueryData`] of entities matching a [`QueryFilter`].
+#[derive(Debug)]
+pub(crate) struct OpacityQueryPlugin<C: OpacityQuery, F: QueryFilter = ()>(
+    PhantomData<(C, fn() -> F)>,
+);
+
+impl<C: OpacityQuery + 'static, F: QueryFilter + 'static> Plugin for OpacityQueryPlugin<C, F> {
+    fn build(&self, app: &mut App) {
+        #[cfg(feature = "debug")]
+        debug::register_target::<C, F>(app);
+        app.add_systems(
+            opacity_schedule(app),
+            apply_opacity_query::<C, F>
+                .run_if(resource_changed::<OpacityMap>)
+                .in_set(OpacitySet::Apply),
+        );
+        app.add_systems(
+            Last,
+            apply_late_opacity_query::<C, F>
+                .run_if(resource_changed::<LateOpacity>)
+                .in_set(LateApply),
+        );
+    }
+}
+
+/// Add support for writing opacity to a [`QueryData`] whose context requires a resource
+/// added by another plugin, for example [`Assets`] of a debug material.
+/// Nothing is written while the resource does not exist.
+#[cfg(any(
+    feature = "gizmos",
+    all(feature = "wireframe", any(feature = "2d", feature = "3d"))
+))]
+pub(crate) fn register_opacity_requiring<Q: OpacityQuery + 'static, R: Resource>(app: &mut App) {
+    use bevy::ecs::schedule::common_conditions::resource_exists;
+    #[cfg(feature = "debug")]
+    debug::register_target::<Q, ()>(app);
+    app.add_systems(
+        opacity_schedule(app),
+        apply_opacity_query::<Q, ()>
+            .run_if(resource_exists::<R>.and(resource_changed::<OpacityMap>))
+            .in_set(OpacitySet::Apply),
+    );
+    app.add_systems(
+        Last,
+        apply_late_opacity_query::<Q, ()>
+            .run_if(resource_exists::<R>.and(resource_changed::<LateOpacity>))
+            .in_set(LateApply),
+    );
+}
+
+/// Write opacity again when a component changing how it is applied, like [`UiOpacity`],
+/// is inserted, modified or removed while opacity stays the same.
+#[cfg(any(feature = "2d", feature = "ui"))]
+pub(crate) fn reapply_on_change<T: Component>(app: &mut App) {
+    fn reapply<T: Component>(
+        mut map: ResMut<OpacityMap>,
+        changed: Query<(), Changed<T>>,
+        mut removed: RemovedComponents<T>,
+    ) {
+        let removed = removed.read().count() > 0;
+        if removed || !changed.is_empty() {
+            map.set_changed();
+        }
+    }
+    app.add_systems(
+        opacity_schedule(app),
+        reapply::<T>.in_set(OpacitySet::Modify),
+    );
+}
+
+fn apply_opacity_query<Q: OpacityQuery, F: QueryFilter>(
+    map: Res<OpacityMap>,
+    skipped: Res<SkippedUpdates>,
+    cx: StaticSystemParam<Q::Cx>,
+    mut query: Query<(Entity, Q), (F, Without<OpacityIgnore>)>,
+) {
+    let mut cx = cx.into_inner();
+    for (entity, mut component) in &mut query {
+        if skipped.contains(&entity) {
+            continue;
+        }
+        if let Some(opacity) = map.0.get(&entity) {
+            Q::apply_opacity(&mut component, &mut cx, *opacity);
+        }
+    }
+}
+
+/// Add support for writing opacity to a [`QueryData`] without context, in parallel.
+#[derive(Debug)]
+pub(crate) struct ParOpacityQueryPlugin<C: OpacityQuery<Cx = ()>>(PhantomData<C>);
+
+impl<C: OpacityQuery<Cx = ()> + 'static> Plugin for ParOpacityQueryPlugin<C> {
+    fn build(&self, app: &mut App) {
+        #[cfg(feature = "debug")]
+        debug::register_target::<C, ()>(app);
+        app.add_systems(
+            opacity_schedule(app),
+            par_apply_opacity_query::<C>
+                .run_if(resource_changed::<OpacityMap>)
+                .in_set(OpacitySet::Apply),
+        );
+        app.add_systems(
+            Last,
+            apply_late_opacity_query::<C, ()>
+                .run_if(resource_changed::<LateOpacity>)
+                .in_set(LateApply),
+        );
+    }
+}
+
+fn par_apply_opacity_query<Q: OpacityQuery<Cx = ()>>(
+    map: Res<OpacityMap>,
+    skipped: Res<SkippedUpdates>,
+    mut query: Query<(Entity, Q), Without<OpacityIgnore>>,
+) {
+    query.par_iter_mut().for_each(|(entity, mut component)| {
+        if skipped.contains(&entity) {
+            return;
+        }
+        if let Some(opacity) = map.0.get(&entity) {
+            Q::apply_opacity(&mut component, &mut (), *opacity);
+        }
+    });
+}
+
+/// Plugin for [`bevy_mod_opacity`](crate) that adds support for basic bevy types.
+///
+/// Fades are driven by [`Time<Virtual>`] by default.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::OpacityPlugin;
+/// App::new().add_plugins(
+///     OpacityPlugin::with_clock::<Real>()
+///         .with_auto_visibility(true)
+///         .without_builtin(),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct OpacityPlugin {
+    clock: fn(&mut App),
+    config: OpacityConfig,
+    builtin: bool,
+    schedule: InternedScheduleLabel,
+}
+
+impl OpacityPlugin {
+    /// Creates the plugin with fades driven by [`Time<Virtual>`].
+    pub fn new() -> Self {
+        Self::with_clock::<Virtual>()
+    }
+
+    /// Creates the plugin with fades driven by [`Time<C>`],
+    /// for example [`Time<Real>`] to keep fading while virtual time is paused.
+    pub fn with_clock<C: Default + Send + Sync + 'static>() -> Self {
+        OpacityPlugin {
+            clock: add_interpolation::<C>,
+            config: OpacityConfig::default(),
+            builtin: true,
+            schedule: PostUpdate.intern(),
+        }
+    }
+
+    /// Run opacity systems in a schedule other than [`PostUpdate`], like `FixedPostUpdate`.
+    ///
+    /// Outside of [`PostUpdate`] the systems are not ordered against transform propagation or visibility.
+    /// Types must be registered after this plugin is added.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = schedule.intern();
+        self
+    }
+
+    /// Set the initial [`OpacityConfig`].
+    pub const fn with_config(mut self, config: OpacityConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set whether [`Opacity::fade_out`] despawns entities on completion.
+    pub const fn with_despawn_on_fade_out(mut self, despawn: bool) -> Self {
+        self.config.despawn_on_fade_out = despawn;
+        self
+    }
+
+    /// Set whether every entity toggles visibility as if it had [`OpacityVisibility`].
+    pub const fn with_auto_visibility(mut self, auto_visibility: bool) -> Self {
+        self.config.auto_visibility = auto_visibility;
+        self
+    }
+
+    /// Set whether hidden entities and their children are skipped during propagation.
+    #[cfg(feature = "render")]
+    pub const fn with_skip_hidden(mut self, skip_hidden: bool) -> Self {
+        self.config.skip_hidden = skip_hidden;
+        self
+    }
+
+    /// Do not register support for bevy's built-in types like [`Sprite`](bevy::sprite::Sprite),
+    /// only user registered types are affected by opacity.
+    pub const fn without_builtin(mut self) -> Self {
+        self.builtin = false;
+        self
+    }
+}
+
+/// The schedule opacity systems run in.
+#[derive(Debug, Clone, Copy, Resource)]
+struct OpacitySchedule(InternedScheduleLabel);
+
+/// Returns the schedule opacity systems run in, [`PostUpdate`] by default.
+pub(crate) fn opacity_schedule(app: &App) -> InternedScheduleLabel {
+    app.world()
+        .get_resource::<OpacitySchedule>()
+        .map(|schedule| schedule.0)
+        .unwrap_or(PostUpdate.intern())
+}
+
+/// Runtime configuration of [`OpacityPlugin`].
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityConfig {
+    /// If true, [`Opacity::fade_out`] despawns entities on completion.
+    ///
+    /// Defaults to `true`.
+    pub despawn_on_fade_out: bool,
+    /// If true, every entity with computed opacity `0.0` is hidden
+    /// as if it had [`OpacityVisibility`].
+    ///
+    /// Defaults to `false`.
+    pub auto_visibility: bool,
+    /// Minimum change in computed opacity for [`OpacityChanged`] to be sent.
+    ///
+    /// Defaults to `0.0`, every change is sent.
+    pub change_epsilon: f32,
+    /// Entities with computed opacity less than or equal to this value are considered invisible
+    /// by [`BecameInvisible`] and [`BecameVisible`].
+    ///
+    /// Defaults to `0.0`.
+    pub visibility_threshold: f32,
+    /// Minimum change in computed opacity for registered types to be written to,
+    /// a small value like `0.001` avoids updating every sprite and material
+    /// each frame during long and slow fades.
+    ///
+    /// Fully opaque and fully transparent values are always written.
+    ///
+    /// Defaults to `0.0`, every change is written.
+    pub apply_epsilon: f32,
+    /// Maximum number of entities registered types are written to per frame,
+    /// remaining writes are postponed to later frames, for example for large crowds under a slow fade.
+    ///
+    /// Entities whose nearest [`Opacity`] is fading are written first,
+    /// then entities with the largest change. Entities are always written the first time they get an opacity.
+    ///
+    /// Defaults to `None`, every change is written.
+    pub apply_budget: Option<usize>,
+    /// If true, entities with [`Visibility::Hidden`](bevy::prelude::Visibility::Hidden)
+    /// and their children are skipped during propagation,
+    /// their computed opacity and registered types are not updated until they are shown.
+    ///
+    /// Entities hidden by [`OpacityVisibility`] are not skipped.
+    ///
+    /// Defaults to `false`.
+    #[cfg(feature = "render")]
+    pub skip_hidden: bool,
+}
+
+impl Default for OpacityConfig {
+    fn default() -> Self {
+        OpacityConfig {
+            despawn_on_fade_out: true,
+            auto_visibility: false,
+            change_epsilon: 0.0,
+            visibility_threshold: 0.0,
+            apply_epsilon: 0.0,
+            apply_budget: None,
+            #[cfg(feature = "render")]
+            skip_hidden: false,
+        }
+    }
+}
+
+impl Default for OpacityPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn add_interpolation<C: Default + Send + Sync + 'static>(app: &mut App) {
+    app.add_systems(
+        opacity_schedule(app),
+        (
+            interpolate::<C, (Without<FadeUnscaled>, Without<FadePaused>)>
+                .run_if(any_with_component::<Opacity>),
+            interpolate::<Real, (With<FadeUnscaled>, Without<FadePaused>)>
+                .run_if(any_with_component::<FadeUnscaled>),
+            group::interpolate_groups::<C>,
+        )
+            .in_set(Interpolation),
+    );
+    #[cfg(feature = "curve")]
+    curve::opacity_plugin_curve_clock::<C>(app);
+    sequence::opacity_plugin_sequence_clock::<C>(app);
+    spawn::opacity_plugin_spawn_clock::<C>(app);
+    stagger::opacity_plugin_stagger_clock::<C>(app);
+    transition::opacity_plugin_transition_clock::<C>(app);
+}
+
+/// Extensions for [`App`].
+pub trait OpacityExtension {
+    fn register_opacity<Q: OpacityQuery + 'static>(&mut self) -> &mut Self;
+    /// Register an [`OpacityQuery`] that is only written to entities matching `F`,
+    /// like `With<Hud>` or `Without<NoFade>`.
+    ///
+    /// Combine with [`OpacityPlugin::without_builtin`] to keep built-in types
+    /// like [`Sprite`](bevy::sprite::Sprite) away from some entities:
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::{OpacityExtension, OpacityPlugin};
+    /// #[derive(Component)]
+    /// struct CustomPipeline;
+    ///
+    /// App::new()
+    ///     .add_plugins(OpacityPlugin::new().without_builtin())
+    ///     .register_opacity_filtered::<&mut Sprite, Without<CustomPipeline>>();
+    /// ```
+    fn register_opacity_filtered<Q: OpacityQuery + 'static, F: QueryFilter + 'static>(
+        &mut self,
+    ) -> &mut Self;
+    fn register_opacity_component<C: Component>(&mut self) -> &mut Self
+    where
+        &'static mut C: OpacityQuery;
+    /// Register an [`OpacityQuery`] without context, written to entities in parallel.
+    ///
+    /// Prefer this over [`register_opacity`](OpacityExtension::register_opacity)
+    /// for types on many entities, like [`Sprite`](bevy::sprite::Sprite).
+    fn register_opacity_parallel<Q: OpacityQuery<Cx = ()> + 'static>(&mut self) -> &mut Self;
+    /// Register a [`Component`] holding a handle to an [`OpacityAsset`],
+    /// the asset is written to directly.
+    fn register_opacity_asset<C: AsAssetId<Asset = A>, A: OpacityAsset>(&mut self) -> &mut Self;
+    /// Register a [`Component`] holding a handle to an asset that does not implement [`OpacityAsset`],
+    /// opacity is written as alpha to the field at a reflect path like `"base.color"`.
+    ///
+    /// The field must be an `f32` or one of bevy's color types.
+    ///
+    /// # Panics
+    ///
+    /// If `path` is not a valid reflect path.
+    fn register_opacity_asset_field<C: AsAssetId>(&mut self, path: &str) -> &mut Self
+    where
+        C::Asset: Reflect;
+    #[cfg(feature = "2d")]
+    fn register_opacity_material2d<M: bevy::sprite::Material2d + OpacityAsset>(
+        &mut self,
+    ) -> &mut Self;
+    #[cfg(feature = "3d")]
+    fn register_opacity_material3d<M: bevy::pbr::Material + OpacityAsset>(&mut self) -> &mut Self;
+    #[cfg(feature = "ui")]
+    fn register_opacity_ui_material<M: bevy::ui::UiMaterial + OpacityAsset>(&mut self)
+        -> &mut Self;
+    /// Propagate opacity over a [`Relationship`](bevy::ecs::relationship::Relationship),
+    /// entities holding `R` and their descendants follow the opacity of the related entity,
+    /// like [`OpacityTarget`].
+    fn register_opacity_relationship<R: bevy::ecs::relationship::Relationship>(
+        &mut self,
+    ) -> &mut Self;
+    /// Enable [`FadeOutOnExit`] for a state.
+    #[cfg(feature = "state")]
+    fn register_fade_out_on_exit<S: bevy::state::state::States>(&mut self) -> &mut Self;
+}
+
+impl OpacityExtension for App {
+    fn register_opacity<Q: OpacityQuery + 'static>(&mut self) -> &mut Self {
+        self.add_plugins(OpacityQueryPlugin::<Q>(PhantomData));
+        self
+    }
+
+    fn register_opacity_filtered<Q: OpacityQuery + 'static, F: QueryFilter + 'static>(
+        &mut self,
+    ) -> &mut Self {
+        self.add_plugins(OpacityQueryPlugin::<Q, F>(PhantomData));
+        self
+    }
+
+    fn register_opacity_component<C: Component>(&mut self) -> &mut Self
+    where
+        &'static mut C: OpacityQuery,
+    {
+        self.add_plugins(OpacityQueryPlugin::<&mut C>(PhantomData));
+        self
+    }
+
+    fn register_opacity_parallel<Q: OpacityQuery<Cx = ()> + 'static>(&mut self) -> &mut Self {
+        self.add_plugins(ParOpacityQueryPlugin::<Q>(PhantomData));
+        self
+    }
+
+    fn register_opacity_asset<C: AsAssetId<Asset = A>, A: OpacityAsset>(&mut self) -> &mut Self {
+        self.add_plugins(OpacityQueryPlugin::<AssetHandleQuery<C>>(PhantomData));
+        self
+    }
+
+    fn register_opacity_asset_field<C: AsAssetId>(&mut self, path: &str) -> &mut Self
+    where
+        C::Asset: Reflect,
+    {
+        reflect::register_asset_field::<C>(self, path);
+        self
+    }
+
+    fn register_opacity_relationship<R: bevy::ecs::relationship::Relationship>(
+        &mut self,
+    ) -> &mut Self {
+        relationship::register_relationship::<R>(self);
+        self
+    }
+
+    #[cfg(feature = "2d")]
+    fn register_opacity_material2d<M: bevy::sprite::Material2d + OpacityAsset>(
+        &mut self,
+    ) -> &mut Self {
+        self.add_plugins(OpacityQueryPlugin::<&bevy::sprite::MeshMaterial2d<M>>(
+            PhantomData,
+        ));
+        self
+    }
+
+    #[cfg(feature = "3d")]
+    fn register_opacity_material3d<M: bevy::pbr::Material + OpacityAsset>(&mut self) -> &mut Self {
+        self.add_plugins(OpacityQueryPlugin::<&bevy::pbr::MeshMaterial3d<M>>(
+            PhantomData,
+        ));
+        self
+    }
+
+    #[cfg(feature = "ui")]
+    fn register_opacity_ui_material<M: bevy::ui::UiMaterial + OpacityAsset>(
+        &mut self,
+    ) -> &mut Self {
+        self.add_plugins(OpacityQueryPlugin::<&bevy::ui::MaterialNode<M>>(
+            PhantomData,
+        ));
+        self
+    }
+
+    #[cfg(feature = "state")]
+    fn register_fade_out_on_exit<S: bevy::state::state::States>(&mut self) -> &mut Self {
+        state::register_fade_out_on_exit::<S>(self);
+        self
     }
 }
 
 impl Plugin for OpacityPlugin {
     fn build(&self, app: &mut App) {
-        use bevy::render::view::VisibilitySystems::*;
         use OpacitySet::*;
         app.init_resource::<OpacityMap>();
-        app.configure_sets(
-            PostUpdate,
-            (Fading, PostFade, Calculate, Apply)
+        app.init_resource::<ProxiedEntities>();
+        app.init_resource::<SkippedUpdates>();
+        app.init_resource::<LateOpacity>();
+        app.insert_resource(self.config);
+        app.init_resource::<GlobalOpacity>();
+        app.init_resource::<OpacityTimeScale>();
+        app.init_resource::<ReducedMotion>();
+        app.init_resource::<DefaultFadeDurations>();
+        #[cfg(feature = "reflect")]
+        app.register_type::<Opacity>()
+            .register_type::<GlobalOpacity>()
+            .register_type::<IgnoreGlobalOpacity>()
+            .register_type::<OpacityIgnore>()
+            .register_type::<OpacityTarget>()
+            .register_type::<OpacityInherit>()
+            .register_type::<OpacityBlend>()
+            .register_type::<OpacityWeight>()
+            .register_type::<OpacityLimit>()
+            .register_type::<OpacityRoot>()
+            .register_type::<FadeUnscaled>()
+            .register_type::<FadePaused>()
+            .register_type::<FadeInOnAdd>()
+            .register_type::<FadeOutAfter>()
+            .register_type::<OpacityTimeScale>()
+            .register_type::<ReducedMotion>()
+            .register_type::<DefaultFadeDurations>()
+            .register_type::<OpacityConfig>();
+        #[cfg(all(feature = "render", feature = "reflect"))]
+        app.register_type::<GlobalOpacityLayers>();
+        app.insert_resource(OpacitySchedule(self.schedule));
+        if self.schedule == PostUpdate.intern() {
+            app.configure_sets(
+                PostUpdate,
+                (Fading, PostFade, Calculate, Modify, Apply)
+                    .chain()
+                    .after(propagate_parent_transforms)
+                    .after(sync_simple_transforms),
+            );
+            #[cfg(feature = "render")]
+            {
+                use bevy::render::view::VisibilitySystems::*;
+                app.configure_sets(
+                    PostUpdate,
+                    (Fading, PostFade, Calculate, Modify, Apply)
+                        .before(VisibilityPropagate)
+                        .before(CheckVisibility)
+                        .before(UpdateFrusta),
+                );
+            }
+        } else {
+            app.configure_sets(
+                self.schedule,
+                (Fading, PostFade, Calculate, Modify, Apply).chain(),
+            );
+        }
+        app.configure_sets(self.schedule, Interpolation.in_set(Fading));
+        (self.clock)(app);
+        app.add_systems(self.schedule, ApplyDeferred.in_set(PostFade));
+        app.add_systems(Last, calculate_late_opacity.before(LateApply));
+        app.add_systems(
+            opacity_schedule(app),
+            calculate_opacity.run_if(opacity_changed).in_set(Calculate),
+        );
+        app.configure_sets(self.schedule, SkipUpdates.after(Modify).before(Apply));
+        app.add_systems(
+            opacity_schedule(app),
+            (
+                skip_small_updates.run_if(resource_changed::<OpacityMap>.or(has_pending_updates)),
+                clear_updated,
+            )
                 .chain()
-                .after(propagate_parent_transforms)
-                .after(sync_simple_transforms)
-                .before(CheckVisibility)
-                .before(UpdateFrusta),
+                .in_set(SkipUpdates),
         );
-        app.add_systems(PostUpdate, interpolate.in_set(Fading));
-        app.add_systems(PostUpdate, ApplyDeferred.in_set(PostFade));
-        app.add_systems(PostUpdate, calculate_opacity.in_set(Calculate));
-        #[cfg(any(feature = "2d", feature = "ui"))]
-        app.register_opacity_component::<bevy::text::TextColor>();
-        #[cfg(feature = "2d")]
-        sprite::opacity_plugin_2d(app);
-        #[cfg(feature = "3d")]
-        pbr::opacity_plugin_3d(app);
+        #[cfg(any(feature = "2d", feature = "3d", feature = "ui"))]
+        mode::opacity_plugin_mode(app);
+        if self.builtin {
+            #[cfg(any(feature = "2d", feature = "ui"))]
+            text::opacity_plugin_text(app);
+            #[cfg(feature = "2d")]
+            sprite::opacity_plugin_2d(app);
+            #[cfg(feature = "3d")]
+            pbr::opacity_plugin_3d(app);
+            #[cfg(feature = "ui")]
+            ui::opacity_plugin_ui(app);
+            #[cfg(any(feature = "2d", feature = "3d"))]
+            vertex::opacity_plugin_vertex(app);
+            #[cfg(feature = "gizmos")]
+            gizmos::opacity_plugin_gizmos(app);
+            #[cfg(all(feature = "wireframe", any(feature = "2d", feature = "3d")))]
+            wireframe::opacity_plugin_wireframe(app);
+            #[cfg(feature = "hanabi")]
+            hanabi::opacity_plugin_hanabi(app);
+            #[cfg(feature = "ecs_tilemap")]
+            ecs_tilemap::opacity_plugin_ecs_tilemap(app);
+            #[cfg(feature = "egui")]
+            egui::opacity_plugin_egui(app);
+            #[cfg(feature = "lyon")]
+            lyon::opacity_plugin_lyon(app);
+        }
+        #[cfg(feature = "ui")]
+        screen_fade::opacity_plugin_screen_fade(app);
         #[cfg(feature = "ui")]
-        ui::opacity_plugin_ui(app);
+        interaction::opacity_plugin_interaction(app);
+        #[cfg(feature = "ui")]
+        focus::opacity_plugin_focus(app);
+        #[cfg(feature = "picking")]
+        picking::opacity_plugin_picking(app);
+        #[cfg(feature = "avian2d")]
+        collider::opacity_plugin_colliders::<
+            avian2d::prelude::Collider,
+            avian2d::prelude::ColliderDisabled,
+        >(app);
+        #[cfg(feature = "avian3d")]
+        collider::opacity_plugin_colliders::<
+            avian3d::prelude::Collider,
+            avian3d::prelude::ColliderDisabled,
+        >(app);
+        #[cfg(feature = "rapier2d")]
+        collider::opacity_plugin_colliders::<
+            bevy_rapier2d::prelude::Collider,
+            bevy_rapier2d::prelude::ColliderDisabled,
+        >(app);
+        #[cfg(feature = "rapier3d")]
+        collider::opacity_plugin_colliders::<
+            bevy_rapier3d::prelude::Collider,
+            bevy_rapier3d::prelude::ColliderDisabled,
+        >(app);
+        #[cfg(feature = "accessibility")]
+        accessibility::opacity_plugin_accessibility(app);
+        #[cfg(feature = "3d")]
+        shadow::opacity_plugin_shadow(app);
+        #[cfg(feature = "scene")]
+        scene::opacity_plugin_scene(app);
+        #[cfg(feature = "audio")]
+        audio::opacity_plugin_audio(app);
+        #[cfg(feature = "diagnostics")]
+        diagnostics::opacity_plugin_diagnostics(app);
+        #[cfg(feature = "dev_tools")]
+        dev_tools::opacity_plugin_dev_tools(app);
+        #[cfg(feature = "debug")]
+        debug::opacity_plugin_debug(app);
+        #[cfg(feature = "inspector")]
+        inspector::opacity_plugin_inspector(app);
+        #[cfg(feature = "render")]
+        visibility::opacity_plugin_visibility(app);
+        #[cfg(feature = "render")]
+        camera::opacity_plugin_camera(app);
+        #[cfg(feature = "render")]
+        clear_color::opacity_plugin_clear_color(app);
+        #[cfg(feature = "render")]
+        extract::opacity_plugin_extract(app);
+        #[cfg(feature = "render")]
+        buffer::opacity_plugin_buffer(app);
+        #[cfg(feature = "post_process")]
+        post_process::opacity_plugin_post_process(app);
+        #[cfg(feature = "render")]
+        crossfade::opacity_plugin_crossfade(app);
+        #[cfg(feature = "render")]
+        show::opacity_plugin_show(app);
+        group::opacity_plugin_group(app);
+        event::opacity_plugin_event(app);
+        future::opacity_plugin_future(app);
+        callback::opacity_plugin_callback(app);
+        snapshot::opacity_plugin_snapshot(app);
+        sequence::opacity_plugin_sequence(app);
+        #[cfg(feature = "tweening")]
+        tweening::opacity_plugin_tweening(app);
+        easing::opacity_plugin_easing(app);
+        #[cfg(feature = "render")]
+        distance::opacity_plugin_distance(app);
+        #[cfg(feature = "render")]
+        lod::opacity_plugin_lod(app);
+        #[cfg(feature = "occlusion")]
+        occlusion::opacity_plugin_occlusion(app);
+    }
+
+    #[cfg(any(feature = "3d", feature = "post_process", feature = "curve"))]
+    fn finish(&self, app: &mut App) {
+        #[cfg(feature = "curve")]
+        curve::opacity_plugin_curve(app);
+        #[cfg(feature = "3d")]
+        opacity_ext::opacity_plugin_ext(app);
+        #[cfg(feature = "post_process")]
+        post_process::opacity_plugin_post_process_finish(app);
     }
 }
@@ -0,0 +1,96 @@
+use bevy::{
+    app::App,
+    ecs::schedule::IntoScheduleConfigs,
+    prelude::{Changed, Commands, Component, DetectChangesMut, Entity, Query, Visibility},
+};
+
+use crate::{Crossfade, Opacity, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Crossfades between a high and a low level of detail entity,
+/// the inactive entity is set to [`Visibility::Hidden`] when the transition completes.
+///
+/// Call [`LodCrossfade::set_high`] from a level of detail system to switch.
+/// Both entities blend while overlapping, so meshes should use an alpha blended material.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct LodCrossfade {
+    /// The high detail entity.
+    pub high: Entity,
+    /// The low detail entity.
+    pub low: Entity,
+    /// Time in seconds of the transition.
+    pub time: f32,
+    high_active: bool,
+    shown: Option<bool>,
+}
+
+impl LodCrossfade {
+    /// Creates a crossfade that starts at the high detail entity.
+    pub const fn new(high: Entity, low: Entity, time: f32) -> Self {
+        LodCrossfade {
+            high,
+            low,
+            time,
+            high_active: true,
+            shown: None,
+        }
+    }
+
+    /// Start at the low detail entity.
+    pub const fn with_low(mut self) -> Self {
+        self.high_active = false;
+        self
+    }
+
+    /// Returns true if the high detail entity is active.
+    pub const fn is_high(&self) -> bool {
+        self.high_active
+    }
+
+    /// Switch to the high or low detail entity.
+    pub fn set_high(&mut self, high: bool) {
+        self.high_active = high;
+    }
+
+    fn active(&self) -> (Entity, Entity) {
+        if self.high_active {
+            (self.high, self.low)
+        } else {
+            (self.low, self.high)
+        }
+    }
+}
+
+fn lod_crossfade(
+    mut commands: Commands,
+    mut query: Query<&mut LodCrossfade, Changed<LodCrossfade>>,
+) {
+    for mut lod in &mut query {
+        if lod.shown == Some(lod.high_active) {
+            continue;
+        }
+        let (active, inactive) = lod.active();
+        if lod.shown.is_none() {
+            commands.entity(active).try_insert(Opacity::OPAQUE);
+            commands
+                .entity(inactive)
+                .try_insert((Opacity::INVISIBLE, Visibility::Hidden));
+        } else {
+            commands.queue(Crossfade::new(inactive, active, lod.time).hide());
+        }
+        lod.bypass_change_detection().shown = Some(lod.high_active);
+    }
+}
+
+pub fn opacity_plugin_lod(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<LodCrossfade>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        lod_crossfade
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
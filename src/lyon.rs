@@ -0,0 +1,74 @@
+use bevy::{
+    app::App,
+    color::Alpha,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::QueryData,
+        system::{Commands, SystemParam},
+    },
+};
+use bevy_prototype_lyon::entity::Shape;
+
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+use crate::{OpacityExtension, OpacityQuery};
+
+/// The unfaded alphas of the fill and stroke of a [`Shape`].
+///
+/// Inserted automatically the first time opacity is applied,
+/// modify this to change the alpha of the shape.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseShapeAlpha {
+    pub fill: f32,
+    pub stroke: f32,
+}
+
+/// Fades the fill and stroke colors of a [`Shape`].
+///
+/// Shapes share a single material, so the colors are faded instead,
+/// which tessellates the shape again whenever its opacity changes.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct ShapeQuery {
+    pub entity: Entity,
+    pub shape: &'static mut Shape,
+    pub base: Option<&'static BaseShapeAlpha>,
+}
+
+impl OpacityQuery for ShapeQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => *base,
+            None => {
+                let shape = this.shape.as_ref();
+                let base = BaseShapeAlpha {
+                    fill: shape.fill.map_or(1.0, |fill| fill.color.alpha()),
+                    stroke: shape.stroke.map_or(1.0, |stroke| stroke.color.alpha()),
+                };
+                cx.entity(this.entity).try_insert(base);
+                base
+            }
+        };
+        if let Some(fill) = &mut this.shape.fill {
+            fill.color.set_alpha(base.fill * opacity);
+        }
+        if let Some(stroke) = &mut this.shape.stroke {
+            stroke.color.set_alpha(base.stroke * opacity);
+        }
+    }
+}
+
+pub fn opacity_plugin_lyon(app: &mut App) {
+    app.register_opacity::<ShapeQuery>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<BaseShapeAlpha>();
+}
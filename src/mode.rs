@@ -0,0 +1,373 @@
+use bevy::{
+    app::App,
+    color::{Alpha, Color, ColorToComponents, LinearRgba},
+    ecs::schedule::IntoScheduleConfigs,
+    prelude::{Component, Entity, Query, Res, Resource},
+};
+#[cfg(any(feature = "2d", feature = "3d"))]
+use bevy::{
+    asset::{AsAssetId, Asset, AssetId, Assets},
+    ecs::schedule::common_conditions::resource_changed,
+    platform::collections::{HashMap, HashSet},
+    prelude::ResMut,
+};
+#[cfg(any(feature = "2d", feature = "ui"))]
+use bevy::{
+    ecs::{change_detection::DetectChanges, component::Mutable, entity::EntityHashSet},
+    prelude::{Commands, Ref, RemovedComponents},
+};
+
+#[cfg(any(feature = "2d", feature = "3d"))]
+use crate::{OpacityMap, OpacitySet};
+
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::{ReflectComponent, ReflectResource},
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Determines how opacity is written to [`Sprite`](bevy::sprite::Sprite), [`ImageNode`](bevy::ui::widget::ImageNode),
+/// [`ColorMaterial`](bevy::sprite::ColorMaterial) and [`StandardMaterial`](bevy::pbr::StandardMaterial).
+///
+/// Insert as a [`Resource`] to change the default, or as a [`Component`] to override it per entity.
+/// Other targets always write alpha, lights always scale their intensity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component, Resource)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(Reflect),
+    reflect(Component, Resource, Default)
+)]
+pub enum OpacityMode {
+    /// Write opacity to alpha.
+    #[default]
+    Alpha,
+    /// Multiply color toward black and leave alpha untouched,
+    /// for targets that cannot blend or must keep their depth order.
+    Dim,
+    /// Write opacity to alpha and render [`StandardMaterial`](bevy::pbr::StandardMaterial)s with
+    /// [`AlphaMode::AlphaToCoverage`](bevy::render::alpha::AlphaMode::AlphaToCoverage),
+    /// which dithers with MSAA and keeps depth order.
+    ///
+    /// Behaves as [`OpacityMode::Alpha`] on other targets.
+    Dither,
+    /// Multiply color toward black and write opacity to alpha.
+    Hybrid,
+    /// Raise the cutoff of [`StandardMaterial`](bevy::pbr::StandardMaterial)s with
+    /// [`AlphaMode::Mask`](bevy::render::alpha::AlphaMode::Mask) instead of writing alpha,
+    /// so alpha tested textures like foliage dissolve, their authored cutoff is kept in
+    /// [`BaseAlphaCutoffs`](crate::BaseAlphaCutoffs).
+    ///
+    /// Behaves as [`OpacityMode::Alpha`] on other targets and materials.
+    Cutoff,
+}
+
+impl OpacityMode {
+    /// Returns the mode of an entity, falling back to the default mode.
+    pub(crate) fn resolve(mode: Option<&OpacityMode>, default: &OpacityMode) -> OpacityMode {
+        *mode.unwrap_or(default)
+    }
+
+    fn dims(self) -> bool {
+        matches!(self, OpacityMode::Dim | OpacityMode::Hybrid)
+    }
+}
+
+/// The undimmed color of an entity in [`OpacityMode::Dim`] or with an [`OpacityTint`].
+///
+/// Inserted automatically on sprites and images as soon as they get an [`OpacityMode`] that dims or an [`OpacityTint`],
+/// the color of materials is cached per material in [`BaseMaterialColors`] instead.
+/// Modify this instead of the color to change the color of a dimmed entity.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseColor(pub Color);
+
+/// Blends the color of this entity toward a tint as its opacity decreases,
+/// reaching the tint at opacity `0.0`, for example to fade to a white flash or a team color.
+///
+/// Applies to the same targets as [`OpacityMode`], alpha is still written according to the mode.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct OpacityTint(pub Color);
+
+/// Blend the color channels of `color` toward `tint` by `1.0 - opacity`, keeping alpha.
+fn tinted(color: Color, tint: Color, opacity: f32) -> Color {
+    let linear = color.to_linear();
+    let rgb = tint.to_linear().to_vec3().lerp(linear.to_vec3(), opacity);
+    LinearRgba::from_vec3(rgb).with_alpha(linear.alpha).into()
+}
+
+/// Multiply the color channels of `color` by `opacity`, keeping alpha.
+fn dimmed(color: Color, opacity: f32) -> Color {
+    let linear = color.to_linear();
+    let rgb = linear.to_vec3() * opacity;
+    LinearRgba::from_vec3(rgb).with_alpha(linear.alpha).into()
+}
+
+/// Write opacity to the color of a material according to an [`OpacityMode`],
+/// dimming and tinting from the undimmed color of the material cached in [`BaseMaterialColors`].
+///
+/// `alpha` is the authored alpha of the material.
+#[cfg(any(feature = "2d", feature = "3d"))]
+pub(crate) fn apply_material_mode<A: Asset>(
+    mode: OpacityMode,
+    id: AssetId<A>,
+    color: &mut Color,
+    alpha: f32,
+    colors: &mut BaseMaterialColors<A>,
+    tint: Option<&OpacityTint>,
+    opacity: f32,
+) {
+    if !mode.dims() && tint.is_none() {
+        match colors.0.remove(&id) {
+            Some(base) => *color = base.with_alpha(opacity),
+            None => color.set_alpha(opacity),
+        }
+        return;
+    }
+    let base = *colors.0.entry(id).or_insert(color.with_alpha(alpha));
+    apply_base_mode(mode, color, base, tint, opacity);
+}
+
+/// Write opacity to a color from its undimmed color according to an [`OpacityMode`].
+fn apply_base_mode(
+    mode: OpacityMode,
+    color: &mut Color,
+    base: Color,
+    tint: Option<&OpacityTint>,
+    opacity: f32,
+) {
+    *color = match tint {
+        Some(tint) => tinted(base, tint.0, opacity),
+        None => base,
+    };
+    if mode.dims() {
+        *color = dimmed(*color, opacity);
+    }
+    if mode != OpacityMode::Dim {
+        color.set_alpha(opacity);
+    }
+}
+
+/// The [`OpacityMode`] of an entity with a [`BaseColor`] inserted by [`cache_base_colors`],
+/// so the color can be written without reading the [`OpacityMode`] resource.
+#[cfg(any(feature = "2d", feature = "ui"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub(crate) struct ResolvedMode(OpacityMode);
+
+/// A component with a single color written to according to an [`OpacityMode`].
+#[cfg(any(feature = "2d", feature = "ui"))]
+pub(crate) trait ModeColor: Component<Mutability = Mutable> {
+    fn color_mut(&mut self) -> &mut Color;
+}
+
+/// Write opacity to the color of a [`ModeColor`], dimmed and tinted if [`cache_base_colors`] cached its color.
+#[cfg(any(feature = "2d", feature = "ui"))]
+pub(crate) fn apply_cached_mode(
+    color: &mut Color,
+    base: Option<&BaseColor>,
+    mode: Option<&ResolvedMode>,
+    tint: Option<&OpacityTint>,
+    opacity: f32,
+) {
+    match (base, mode) {
+        (Some(base), Some(mode)) => apply_base_mode(mode.0, color, base.0, tint, opacity),
+        _ => color.set_alpha(opacity),
+    }
+}
+
+#[cfg(any(feature = "2d", feature = "ui"))]
+type ModeColorQuery<T> = (
+    Entity,
+    &'static mut T,
+    Option<Ref<'static, OpacityMode>>,
+    Option<Ref<'static, OpacityTint>>,
+    Option<&'static BaseColor>,
+    Option<&'static ResolvedMode>,
+);
+
+/// Insert [`BaseColor`] on entities that are dimmed or tinted, and restore their color when they no longer are,
+/// so opacity can be written to `T` without context.
+#[cfg(any(feature = "2d", feature = "ui"))]
+fn cache_base_colors<T: ModeColor>(
+    mut commands: Commands,
+    default: Res<OpacityMode>,
+    mut query: Query<ModeColorQuery<T>>,
+    mut removed_modes: RemovedComponents<OpacityMode>,
+    mut removed_tints: RemovedComponents<OpacityTint>,
+) {
+    let removed: EntityHashSet = removed_modes.read().chain(removed_tints.read()).collect();
+    for (entity, mut target, mode, tint, base, resolved) in &mut query {
+        let changed = default.is_changed()
+            || target.is_added()
+            || mode.as_ref().is_some_and(DetectChanges::is_changed)
+            || tint.as_ref().is_some_and(DetectChanges::is_added)
+            || removed.contains(&entity);
+        if !changed {
+            continue;
+        }
+        let mode = OpacityMode::resolve(mode.as_deref(), &default);
+        match base {
+            _ if !mode.dims() && tint.is_none() => {
+                if let Some(base) = base {
+                    let color = target.color_mut();
+                    *color = base.0.with_alpha(color.alpha());
+                    commands
+                        .entity(entity)
+                        .try_remove::<(BaseColor, ResolvedMode)>();
+                }
+            }
+            None => {
+                let base = BaseColor(*target.color_mut());
+                commands
+                    .entity(entity)
+                    .try_insert((base, ResolvedMode(mode)));
+            }
+            Some(_) if resolved != Some(&ResolvedMode(mode)) => {
+                commands.entity(entity).try_insert(ResolvedMode(mode));
+            }
+            Some(_) => (),
+        }
+    }
+}
+
+/// Cache the undimmed color of `T` in [`BaseColor`] before opacity is written to it.
+#[cfg(any(feature = "2d", feature = "ui"))]
+pub(crate) fn opacity_plugin_base_color<T: ModeColor>(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        cache_base_colors::<T>.in_set(crate::OpacitySet::Modify),
+    );
+}
+
+/// The authored alpha of each material of type `A`, cached the first time opacity is written to it,
+/// so partially transparent materials like glass fade from their own alpha instead of `1.0`.
+///
+/// Used for [`StandardMaterial`](bevy::pbr::StandardMaterial) and [`ColorMaterial`](bevy::sprite::ColorMaterial).
+/// The alpha is restored when no faded entity uses the material anymore,
+/// modify this instead of the material to change the alpha of a faded material.
+#[cfg(any(feature = "2d", feature = "3d"))]
+#[derive(Debug, Resource)]
+pub struct BaseMaterialAlphas<A: Asset>(HashMap<AssetId<A>, f32>);
+
+#[cfg(any(feature = "2d", feature = "3d"))]
+impl<A: Asset> Default for BaseMaterialAlphas<A> {
+    fn default() -> Self {
+        BaseMaterialAlphas(HashMap::default())
+    }
+}
+
+#[cfg(any(feature = "2d", feature = "3d"))]
+impl<A: Asset> BaseMaterialAlphas<A> {
+    /// Returns the authored alpha of a material, `None` if it was never faded.
+    pub fn get(&self, id: impl Into<AssetId<A>>) -> Option<f32> {
+        self.0.get(&id.into()).copied()
+    }
+
+    /// Set the authored alpha of a material.
+    pub fn set(&mut self, id: impl Into<AssetId<A>>, alpha: f32) {
+        self.0.insert(id.into(), alpha);
+    }
+
+    /// Returns the authored alpha of a material, caching `alpha` if not yet cached.
+    pub(crate) fn cache(&mut self, id: impl Into<AssetId<A>>, alpha: f32) -> f32 {
+        *self.0.entry(id.into()).or_insert(alpha)
+    }
+}
+
+/// The undimmed color of each material of type `A` in an [`OpacityMode`] that dims or with an [`OpacityTint`],
+/// cached the first time it is dimmed or tinted, so materials shared by entities are dimmed from their authored color.
+///
+/// The color is restored when the material is no longer dimmed or tinted, or no faded entity uses it anymore,
+/// modify this instead of the material to change the color of a dimmed material.
+#[cfg(any(feature = "2d", feature = "3d"))]
+#[derive(Debug, Resource)]
+pub struct BaseMaterialColors<A: Asset>(HashMap<AssetId<A>, Color>);
+
+#[cfg(any(feature = "2d", feature = "3d"))]
+impl<A: Asset> Default for BaseMaterialColors<A> {
+    fn default() -> Self {
+        BaseMaterialColors(HashMap::default())
+    }
+}
+
+#[cfg(any(feature = "2d", feature = "3d"))]
+impl<A: Asset> BaseMaterialColors<A> {
+    /// Returns the undimmed color of a material, `None` if it is not dimmed or tinted.
+    pub fn get(&self, id: impl Into<AssetId<A>>) -> Option<Color> {
+        self.0.get(&id.into()).copied()
+    }
+
+    /// Set the undimmed color of a material.
+    pub fn set(&mut self, id: impl Into<AssetId<A>>, color: Color) {
+        self.0.insert(id.into(), color);
+    }
+}
+
+/// A material whose color alpha is cached in [`BaseMaterialAlphas`].
+#[cfg(any(feature = "2d", feature = "3d"))]
+pub(crate) trait MaterialColor: Asset {
+    fn color_mut(&mut self) -> &mut Color;
+}
+
+/// Restore the authored color and alpha of materials no longer used by a faded entity.
+#[cfg(any(feature = "2d", feature = "3d"))]
+fn restore_base_alphas<C: AsAssetId>(
+    map: Res<OpacityMap>,
+    mut bases: ResMut<BaseMaterialAlphas<C::Asset>>,
+    mut colors: ResMut<BaseMaterialColors<C::Asset>>,
+    mut assets: ResMut<Assets<C::Asset>>,
+    query: Query<(Entity, &C)>,
+) where
+    C::Asset: MaterialColor,
+{
+    if bases.0.is_empty() && colors.0.is_empty() {
+        return;
+    }
+    let used: HashSet<_> = query
+        .iter()
+        .filter(|(entity, _)| map.get(*entity).is_some())
+        .map(|(_, material)| material.as_asset_id())
+        .collect();
+    colors.0.retain(|id, color| {
+        if used.contains(id) {
+            return true;
+        }
+        if let Some(material) = assets.get_mut(*id) {
+            *material.color_mut() = *color;
+        }
+        false
+    });
+    bases.0.retain(|id, alpha| {
+        if used.contains(id) {
+            return true;
+        }
+        if let Some(material) = assets.get_mut(*id) {
+            material.color_mut().set_alpha(*alpha);
+        }
+        false
+    });
+}
+
+/// Cache and restore the authored color and alpha of materials held by `C`.
+#[cfg(any(feature = "2d", feature = "3d"))]
+pub(crate) fn opacity_plugin_base_alpha<C: AsAssetId>(app: &mut App)
+where
+    C::Asset: MaterialColor,
+{
+    app.init_resource::<BaseMaterialAlphas<C::Asset>>();
+    app.init_resource::<BaseMaterialColors<C::Asset>>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        restore_base_alphas::<C>
+            .run_if(resource_changed::<OpacityMap>)
+            .in_set(OpacitySet::Apply),
+    );
+}
+
+pub fn opacity_plugin_mode(app: &mut App) {
+    app.init_resource::<OpacityMode>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityMode>()
+        .register_type::<BaseColor>()
+        .register_type::<OpacityTint>();
+}
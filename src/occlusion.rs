@@ -0,0 +1,112 @@
+use bevy::{
+    app::App,
+    asset::Assets,
+    ecs::{
+        entity::EntityHashSet,
+        schedule::{
+            common_conditions::{any_with_component, resource_exists},
+            Condition, IntoScheduleConfigs,
+        },
+    },
+    math::{Dir3, Ray3d},
+    picking::mesh_picking::ray_cast::{MeshRayCast, MeshRayCastSettings, RayCastVisibility},
+    prelude::{Camera, ChildOf, Component, Entity, GlobalTransform, Local, Mesh, Query, With},
+};
+
+use crate::{distance::active_camera, Opacity, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Fades this entity out while it blocks the view between the active [`Camera`]
+/// and an [`OcclusionTarget`], and fades it back in when clear.
+///
+/// Meshes of this entity and its descendants are ray cast against.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+#[require(Opacity)]
+pub struct OccluderFade {
+    /// Opacity while occluding.
+    pub opacity: f32,
+    /// Time in seconds to fade between states.
+    pub time: f32,
+}
+
+impl Default for OccluderFade {
+    fn default() -> Self {
+        OccluderFade {
+            opacity: 0.2,
+            time: 0.25,
+        }
+    }
+}
+
+/// Marker [`Component`] for entities that should not be hidden behind [`OccluderFade`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct OcclusionTarget;
+
+fn occlusion_fade(
+    mut ray_cast: MeshRayCast,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    targets: Query<(Entity, &GlobalTransform), With<OcclusionTarget>>,
+    parents: Query<&ChildOf>,
+    mut occluders: Query<(Entity, &OccluderFade, &mut Opacity)>,
+    mut occluded: Local<EntityHashSet>,
+) {
+    occluded.clear();
+    if let Some(camera) = active_camera(&cameras).map(GlobalTransform::translation) {
+        for (target, transform) in &targets {
+            let offset = transform.translation() - camera;
+            let Ok(direction) = Dir3::new(offset) else {
+                continue;
+            };
+            let distance = offset.length();
+            let filter = |entity| {
+                !parents
+                    .iter_ancestors(entity)
+                    .chain([entity])
+                    .any(|entity| entity == target)
+            };
+            let settings = MeshRayCastSettings::default()
+                .with_visibility(RayCastVisibility::Any)
+                .with_filter(&filter)
+                .never_early_exit();
+            for (entity, hit) in ray_cast.cast_ray(Ray3d::new(camera, direction), &settings) {
+                if hit.distance >= distance {
+                    break;
+                }
+                let occluder = [*entity]
+                    .into_iter()
+                    .chain(parents.iter_ancestors(*entity))
+                    .find(|entity| occluders.contains(*entity));
+                occluded.extend(occluder);
+            }
+        }
+    }
+    for (entity, fade, mut opacity) in &mut occluders {
+        let target = if occluded.contains(&entity) {
+            fade.opacity
+        } else {
+            1.0
+        };
+        if opacity.get_target() != target {
+            opacity.interpolate_to(target, fade.time);
+        }
+    }
+}
+
+pub fn opacity_plugin_occlusion(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<OccluderFade>()
+        .register_type::<OcclusionTarget>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        occlusion_fade
+            .run_if(any_with_component::<OccluderFade>.and(resource_exists::<Assets<Mesh>>))
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
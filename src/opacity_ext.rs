@@ -0,0 +1,76 @@
+use bevy::{
+    app::App,
+    asset::{embedded_asset, io::embedded::EmbeddedAssetRegistry, Asset, Assets, Handle},
+    pbr::{ExtendedMaterial, Material, MaterialExtension},
+    reflect::TypePath,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+use crate::OpacityMaterialExtension;
+
+/// A [`MaterialExtension`] with its own opacity uniform, multiplied into the alpha of the base material,
+/// for materials whose fields cannot be written to.
+///
+/// The shipped fragment shader reads the base through `pbr_input_from_standard_material`,
+/// so the base must use the bindings of a [`StandardMaterial`](bevy::pbr::StandardMaterial)
+/// and an [`AlphaMode`](bevy::render::alpha::AlphaMode) that blends.
+///
+/// Add a [`MaterialPlugin`](bevy::pbr::MaterialPlugin) for the extended material and register it with
+/// [`OpacityExtension::register_opacity_material3d`](crate::OpacityExtension::register_opacity_material3d).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::pbr::ExtendedMaterial;
+/// # use bevy_mod_opacity::{OpacityExt, OpacityExtension};
+/// fn plugin(app: &mut App) {
+///     app.add_plugins(MaterialPlugin::<ExtendedMaterial<StandardMaterial, OpacityExt>>::default())
+///         .register_opacity_material3d::<ExtendedMaterial<StandardMaterial, OpacityExt>>();
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Asset, TypePath, AsBindGroup)]
+pub struct OpacityExt {
+    #[uniform(100)]
+    pub opacity: f32,
+}
+
+impl Default for OpacityExt {
+    fn default() -> Self {
+        OpacityExt { opacity: 1.0 }
+    }
+}
+
+impl OpacityExt {
+    /// Wrap the material behind `base` into a new [`ExtendedMaterial`] with opacity `1.0`,
+    /// returns `None` if `base` is not loaded.
+    pub fn wrap<M: Material>(
+        base: &Handle<M>,
+        materials: &Assets<M>,
+        extended: &mut Assets<ExtendedMaterial<M, OpacityExt>>,
+    ) -> Option<Handle<ExtendedMaterial<M, OpacityExt>>> {
+        let base = materials.get(base)?.clone();
+        Some(extended.add(ExtendedMaterial {
+            base,
+            extension: OpacityExt::default(),
+        }))
+    }
+}
+
+impl MaterialExtension for OpacityExt {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://bevy_mod_opacity/opacity_ext.wgsl".into()
+    }
+}
+
+impl<M: Material> OpacityMaterialExtension<M> for OpacityExt {
+    fn apply_opacity(_: &mut M, ext: &mut Self, opacity: f32) {
+        ext.opacity = opacity;
+    }
+}
+
+/// Called in [`Plugin::finish`](bevy::app::Plugin::finish) so the shader is embedded
+/// regardless of plugin order, apps without an asset server are skipped.
+pub fn opacity_plugin_ext(app: &mut App) {
+    if app.world().contains_resource::<EmbeddedAssetRegistry>() {
+        embedded_asset!(app, "opacity_ext.wgsl");
+    }
+}
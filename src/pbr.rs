@@ -1,13 +1,27 @@
 use bevy::{
-    ecs::system::SystemParam,
+    core_pipeline::Skybox,
+    ecs::{
+        component::Mutable,
+        query::QueryData,
+        schedule::common_conditions::{resource_changed, resource_exists},
+        system::SystemParam,
+    },
     pbr::{
-        decal::ForwardDecalMaterialExt, wireframe::WireframeMaterial, ExtendedMaterial, Material,
-        MaterialExtension, MeshMaterial3d, StandardMaterial,
+        decal::ForwardDecalMaterialExt, wireframe::WireframeMaterial, DistanceFog,
+        ExtendedMaterial, FogVolume, Material, MaterialExtension, MeshMaterial3d, StandardMaterial,
     },
+    platform::collections::{HashMap, HashSet},
     prelude::*,
 };
 
-use crate::{OpacityAsset, OpacityExtension, OpacityQuery};
+use crate::{
+    mode::{
+        apply_material_mode, opacity_plugin_base_alpha, BaseMaterialAlphas, BaseMaterialColors,
+        MaterialColor, OpacityTint,
+    },
+    GlobalOpacity, OpacityAsset, OpacityAssets, OpacityExtension, OpacityMap, OpacityMode,
+    OpacityQuery, OpacitySet,
+};
 
 /// A [`MaterialExtension`] with an opacity value.
 pub trait OpacityMaterialExtension<A> {
@@ -35,6 +49,12 @@ impl OpacityAsset for StandardMaterial {
     }
 }
 
+impl MaterialColor for StandardMaterial {
+    fn color_mut(&mut self) -> &mut Color {
+        &mut self.base_color
+    }
+}
+
 impl OpacityAsset for WireframeMaterial {
     fn apply_opacity(&mut self, opacity: f32) {
         self.color.set_alpha(opacity)
@@ -45,19 +65,596 @@ impl<T> OpacityQuery for &MeshMaterial3d<T>
 where
     T: OpacityAsset + Material,
 {
-    type Cx = ResMut<'static, Assets<T>>;
+    type Cx = OpacityAssets<'static, 'static, T>;
 
     fn apply_opacity(
         this: &mut Self::Item<'_>,
         cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
         opacity: f32,
     ) {
-        if let Some(mat) = cx.get_mut(this.id()) {
+        if let Some(mat) = cx.get_mut(this.id(), opacity, ()) {
             mat.apply_opacity(opacity);
         }
     }
 }
 
+/// Controls which properties of a [`StandardMaterial`] other than alpha are scaled by opacity.
+///
+/// Insert as a [`Resource`] to change the default, or as a [`Component`] to override it per entity.
+/// By default everything is scaled, so faded objects do not glow or reflect at full strength.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Resource)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(Reflect),
+    reflect(Component, Resource, Default)
+)]
+pub struct StandardMaterialOpacityPolicy {
+    /// Scale [`StandardMaterial::emissive`].
+    pub emissive: bool,
+    /// Scale [`StandardMaterial::reflectance`].
+    pub specular: bool,
+    /// Scale [`StandardMaterial::diffuse_transmission`].
+    pub diffuse_transmission: bool,
+}
+
+impl StandardMaterialOpacityPolicy {
+    /// Only write alpha, this was the behavior before policies were added.
+    pub const ALPHA_ONLY: Self = StandardMaterialOpacityPolicy {
+        emissive: false,
+        specular: false,
+        diffuse_transmission: false,
+    };
+}
+
+impl Default for StandardMaterialOpacityPolicy {
+    fn default() -> Self {
+        StandardMaterialOpacityPolicy {
+            emissive: true,
+            specular: true,
+            diffuse_transmission: true,
+        }
+    }
+}
+
+/// The unscaled properties of a [`StandardMaterial`] affected by [`StandardMaterialOpacityPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct BaseStandardMaterial {
+    pub emissive: LinearRgba,
+    pub reflectance: f32,
+    pub diffuse_transmission: f32,
+}
+
+impl From<&StandardMaterial> for BaseStandardMaterial {
+    fn from(material: &StandardMaterial) -> Self {
+        BaseStandardMaterial {
+            emissive: material.emissive,
+            reflectance: material.reflectance,
+            diffuse_transmission: material.diffuse_transmission,
+        }
+    }
+}
+
+impl BaseStandardMaterial {
+    fn write(&self, material: &mut StandardMaterial) {
+        material.emissive = self.emissive;
+        material.reflectance = self.reflectance;
+        material.diffuse_transmission = self.diffuse_transmission;
+    }
+}
+
+/// The unscaled properties of each [`StandardMaterial`] scaled by [`StandardMaterialOpacityPolicy`],
+/// cached the first time opacity is written to it, so materials shared by entities are scaled from their authored values.
+///
+/// The properties are restored when no faded entity uses the material anymore,
+/// modify this instead of the material to change these properties of a faded material.
+#[derive(Debug, Default, Resource)]
+pub struct BaseStandardMaterials(HashMap<AssetId<StandardMaterial>, BaseStandardMaterial>);
+
+impl BaseStandardMaterials {
+    /// Returns the unscaled properties of a material, `None` if it was never faded.
+    pub fn get(&self, id: impl Into<AssetId<StandardMaterial>>) -> Option<BaseStandardMaterial> {
+        self.0.get(&id.into()).copied()
+    }
+
+    /// Set the unscaled properties of a material.
+    pub fn set(&mut self, id: impl Into<AssetId<StandardMaterial>>, base: BaseStandardMaterial) {
+        self.0.insert(id.into(), base);
+    }
+
+    /// Returns the unscaled properties of a material, caching those of `material` if not yet cached.
+    fn cache(
+        &mut self,
+        id: AssetId<StandardMaterial>,
+        material: &StandardMaterial,
+    ) -> BaseStandardMaterial {
+        *self.0.entry(id).or_insert_with(|| material.into())
+    }
+}
+
+/// The authored cutoff of each [`StandardMaterial`] with [`AlphaMode::Mask`] faded in [`OpacityMode::Cutoff`].
+///
+/// The cutoff is restored when no faded entity uses the material anymore,
+/// modify this instead of the material to change the cutoff of a faded material.
+#[derive(Debug, Default, Resource)]
+pub struct BaseAlphaCutoffs(HashMap<AssetId<StandardMaterial>, f32>);
+
+impl BaseAlphaCutoffs {
+    /// Returns the authored cutoff of a material, `None` if it was never faded.
+    pub fn get(&self, id: impl Into<AssetId<StandardMaterial>>) -> Option<f32> {
+        self.0.get(&id.into()).copied()
+    }
+
+    /// Set the authored cutoff of a material.
+    pub fn set(&mut self, id: impl Into<AssetId<StandardMaterial>>, cutoff: f32) {
+        self.0.insert(id.into(), cutoff);
+    }
+
+    /// Returns the authored cutoff of a material, caching the cutoff of `mode` if not yet cached.
+    fn cache(&mut self, id: AssetId<StandardMaterial>, mode: AlphaMode) -> f32 {
+        let AlphaMode::Mask(cutoff) = mode else {
+            return 0.5;
+        };
+        *self.0.entry(id).or_insert(cutoff)
+    }
+}
+
+/// The authored [`AlphaMode`] of each [`StandardMaterial`] rendered with
+/// [`AlphaMode::AlphaToCoverage`] in [`OpacityMode::Dither`].
+///
+/// The alpha mode is restored when the material is no longer dithered or no faded entity uses it anymore,
+/// modify this instead of the material to change the alpha mode of a dithered material.
+#[derive(Debug, Default, Resource)]
+pub struct BaseAlphaModes(HashMap<AssetId<StandardMaterial>, AlphaMode>);
+
+impl BaseAlphaModes {
+    /// Returns the authored alpha mode of a material, `None` if it is not dithered.
+    pub fn get(&self, id: impl Into<AssetId<StandardMaterial>>) -> Option<AlphaMode> {
+        self.0.get(&id.into()).copied()
+    }
+
+    /// Set the authored alpha mode of a material.
+    pub fn set(&mut self, id: impl Into<AssetId<StandardMaterial>>, mode: AlphaMode) {
+        self.0.insert(id.into(), mode);
+    }
+}
+
+/// Raise a cutoff toward just above `1.0` as opacity decreases, discarding every fragment at `0.0`.
+fn dissolve(cutoff: f32, opacity: f32) -> f32 {
+    let opacity = opacity.clamp(0.0, 1.0);
+    cutoff + (1.0 + f32::EPSILON - cutoff) * (1.0 - opacity)
+}
+
+/// Restore the authored cutoff, alpha mode and unscaled properties of materials no longer used by a faded entity.
+fn restore_standard_materials(
+    map: Res<OpacityMap>,
+    mut cutoffs: ResMut<BaseAlphaCutoffs>,
+    mut alpha_modes: ResMut<BaseAlphaModes>,
+    mut bases: ResMut<BaseStandardMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query: Query<(Entity, &MeshMaterial3d<StandardMaterial>)>,
+) {
+    if cutoffs.0.is_empty() && alpha_modes.0.is_empty() && bases.0.is_empty() {
+        return;
+    }
+    let used: HashSet<_> = query
+        .iter()
+        .filter(|(entity, _)| map.get(*entity).is_some())
+        .map(|(_, material)| material.id())
+        .collect();
+    cutoffs.0.retain(|id, cutoff| {
+        if used.contains(id) {
+            return true;
+        }
+        if let Some(material) = materials.get_mut(*id) {
+            material.alpha_mode = AlphaMode::Mask(*cutoff);
+        }
+        false
+    });
+    alpha_modes.0.retain(|id, alpha_mode| {
+        if used.contains(id) {
+            return true;
+        }
+        if let Some(material) = materials.get_mut(*id) {
+            material.alpha_mode = *alpha_mode;
+        }
+        false
+    });
+    bases.0.retain(|id, base| {
+        if used.contains(id) {
+            return true;
+        }
+        if let Some(material) = materials.get_mut(*id) {
+            base.write(material);
+        }
+        false
+    });
+}
+
+#[derive(Debug, QueryData)]
+pub struct StandardMaterialQuery {
+    pub material: &'static MeshMaterial3d<StandardMaterial>,
+    pub policy: Option<&'static StandardMaterialOpacityPolicy>,
+    pub mode: Option<&'static OpacityMode>,
+    pub tint: Option<&'static OpacityTint>,
+}
+
+impl OpacityQuery for StandardMaterialQuery {
+    type Cx = (
+        OpacityAssets<
+            'static,
+            'static,
+            StandardMaterial,
+            (
+                OpacityMode,
+                StandardMaterialOpacityPolicy,
+                Option<OpacityTint>,
+            ),
+        >,
+        (
+            ResMut<'static, BaseMaterialAlphas<StandardMaterial>>,
+            ResMut<'static, BaseAlphaCutoffs>,
+            ResMut<'static, BaseStandardMaterials>,
+        ),
+        (
+            ResMut<'static, BaseMaterialColors<StandardMaterial>>,
+            ResMut<'static, BaseAlphaModes>,
+        ),
+        Res<'static, StandardMaterialOpacityPolicy>,
+        Res<'static, OpacityMode>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (materials, (alphas, cutoffs, bases), (colors, alpha_modes), policy, mode): &mut <Self::Cx as SystemParam>::Item<
+            '_,
+            '_,
+        >,
+        opacity: f32,
+    ) {
+        let mode = OpacityMode::resolve(this.mode, mode);
+        let policy = this.policy.unwrap_or(policy);
+        let Some(material) = materials.get_mut(
+            this.material.id(),
+            opacity,
+            (mode, *policy, this.tint.copied()),
+        ) else {
+            return;
+        };
+        let id = this.material.id();
+        if mode == OpacityMode::Dither {
+            alpha_modes.0.entry(id).or_insert(material.alpha_mode);
+            material.alpha_mode = AlphaMode::AlphaToCoverage;
+        } else if let Some(alpha_mode) = alpha_modes.0.remove(&id) {
+            material.alpha_mode = alpha_mode;
+        }
+        let alpha = alphas.cache(id, material.base_color.alpha());
+        apply_material_mode(
+            mode,
+            id,
+            &mut material.base_color,
+            alpha,
+            colors,
+            this.tint,
+            opacity,
+        );
+        match material.alpha_mode {
+            AlphaMode::Mask(_) if mode == OpacityMode::Cutoff => {
+                let base = cutoffs.cache(id, material.alpha_mode);
+                material.alpha_mode = AlphaMode::Mask(dissolve(base, opacity));
+                material.base_color.set_alpha(alpha);
+            }
+            _ if mode != OpacityMode::Dim => material.base_color.set_alpha(alpha * opacity),
+            _ => (),
+        }
+        if *policy == StandardMaterialOpacityPolicy::ALPHA_ONLY && bases.get(id).is_none() {
+            return;
+        }
+        let base = bases.cache(id, material);
+        let scale = |scaled: bool| if scaled { opacity } else { 1.0 };
+        material.emissive = base.emissive * scale(policy.emissive);
+        material.reflectance = base.reflectance * scale(policy.specular);
+        material.diffuse_transmission =
+            base.diffuse_transmission * scale(policy.diffuse_transmission);
+    }
+}
+
+/// The unscaled intensity of a [`PointLight`], [`SpotLight`], [`DirectionalLight`]
+/// or [`EnvironmentMapLight`].
+///
+/// Inserted automatically with the light's intensity the first time opacity is applied,
+/// modify this instead of the light's intensity to change brightness.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseLightIntensity(pub f32);
+
+/// A light whose intensity is scaled by opacity.
+pub trait OpacityLight: Component<Mutability = Mutable> {
+    fn intensity_mut(&mut self) -> &mut f32;
+}
+
+impl OpacityLight for PointLight {
+    fn intensity_mut(&mut self) -> &mut f32 {
+        &mut self.intensity
+    }
+}
+
+impl OpacityLight for SpotLight {
+    fn intensity_mut(&mut self) -> &mut f32 {
+        &mut self.intensity
+    }
+}
+
+impl OpacityLight for DirectionalLight {
+    fn intensity_mut(&mut self) -> &mut f32 {
+        &mut self.illuminance
+    }
+}
+
+impl OpacityLight for EnvironmentMapLight {
+    fn intensity_mut(&mut self) -> &mut f32 {
+        &mut self.intensity
+    }
+}
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct LightQuery<T: OpacityLight> {
+    pub entity: Entity,
+    pub light: &'static mut T,
+    pub base: Option<&'static BaseLightIntensity>,
+}
+
+impl<T: OpacityLight> OpacityQuery for LightQuery<T> {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = *this.light.intensity_mut();
+                cx.entity(this.entity).try_insert(BaseLightIntensity(base));
+                base
+            }
+        };
+        *this.light.intensity_mut() = base * opacity;
+    }
+}
+
+/// The unscaled brightness of a [`Skybox`].
+///
+/// Inserted automatically with the skybox's brightness the first time opacity is applied,
+/// modify this instead of [`Skybox::brightness`] to change brightness.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseSkyboxBrightness(pub f32);
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct SkyboxQuery {
+    pub entity: Entity,
+    pub skybox: &'static mut Skybox,
+    pub base: Option<&'static BaseSkyboxBrightness>,
+}
+
+impl OpacityQuery for SkyboxQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = this.skybox.brightness;
+                cx.entity(this.entity)
+                    .try_insert(BaseSkyboxBrightness(base));
+                base
+            }
+        };
+        this.skybox.brightness = base * opacity;
+    }
+}
+
+/// The unscaled brightness of an [`AmbientLight`].
+///
+/// Inserted automatically with the light's brightness the first time opacity is applied,
+/// as a [`Component`] for per-camera ambient lights, or as a [`Resource`] when using [`AmbientFade`].
+/// Modify this instead of [`AmbientLight::brightness`] to change brightness.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Resource))]
+pub struct BaseAmbientBrightness(pub f32);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct AmbientLightQuery {
+    pub entity: Entity,
+    pub light: &'static mut AmbientLight,
+    pub base: Option<&'static BaseAmbientBrightness>,
+}
+
+impl OpacityQuery for AmbientLightQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = this.light.brightness;
+                cx.entity(this.entity)
+                    .try_insert(BaseAmbientBrightness(base));
+                base
+            }
+        };
+        this.light.brightness = base * opacity;
+    }
+}
+
+/// [`Resource`] that scales the brightness of the [`AmbientLight`] resource by an opacity,
+/// so scene-wide fades also fade ambient lighting.
+///
+/// Per-camera [`AmbientLight`] components follow the opacity of their entity instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub enum AmbientFade {
+    /// Follow [`GlobalOpacity`].
+    #[default]
+    Global,
+    /// Follow the computed opacity of an entity,
+    /// the last value is kept if the entity has no opacity, for example after being despawned.
+    Entity(Entity),
+}
+
+fn fade_ambient_light(
+    mut commands: Commands,
+    fade: Res<AmbientFade>,
+    map: Res<OpacityMap>,
+    global: Res<GlobalOpacity>,
+    light: Option<ResMut<AmbientLight>>,
+    base: Option<Res<BaseAmbientBrightness>>,
+) {
+    let Some(mut light) = light else {
+        return;
+    };
+    let opacity = match *fade {
+        AmbientFade::Global => global.0,
+        AmbientFade::Entity(entity) => match map.get(entity) {
+            Some(opacity) => opacity,
+            None => return,
+        },
+    };
+    let base = match base {
+        Some(base) => base.0,
+        None => {
+            commands.insert_resource(BaseAmbientBrightness(light.brightness));
+            light.brightness
+        }
+    };
+    light.brightness = base * opacity;
+}
+
+/// The unfaded alpha of a [`DistanceFog`]'s color.
+///
+/// Inserted automatically with the fog's alpha the first time opacity is applied,
+/// modify this instead of [`DistanceFog::color`] to change the alpha of the fog.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseFogAlpha(pub f32);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct DistanceFogQuery {
+    pub entity: Entity,
+    pub fog: &'static mut DistanceFog,
+    pub base: Option<&'static BaseFogAlpha>,
+}
+
+impl OpacityQuery for DistanceFogQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = this.fog.color.alpha();
+                cx.entity(this.entity).try_insert(BaseFogAlpha(base));
+                base
+            }
+        };
+        this.fog.color.set_alpha(base * opacity);
+    }
+}
+
+/// The unscaled density of a [`FogVolume`].
+///
+/// Inserted automatically with the fog's density the first time opacity is applied,
+/// modify this instead of [`FogVolume::density_factor`] to change density.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseFogDensity(pub f32);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct FogVolumeQuery {
+    pub entity: Entity,
+    pub fog: &'static mut FogVolume,
+    pub base: Option<&'static BaseFogDensity>,
+}
+
+impl OpacityQuery for FogVolumeQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = this.fog.density_factor;
+                cx.entity(this.entity).try_insert(BaseFogDensity(base));
+                base
+            }
+        };
+        this.fog.density_factor = base * opacity;
+    }
+}
+
 pub fn opacity_plugin_3d(app: &mut App) {
-    app.register_opacity_material3d::<bevy::pbr::StandardMaterial>();
+    app.init_resource::<StandardMaterialOpacityPolicy>();
+    app.register_opacity::<StandardMaterialQuery>();
+    opacity_plugin_base_alpha::<MeshMaterial3d<StandardMaterial>>(app);
+    app.init_resource::<BaseAlphaCutoffs>();
+    app.init_resource::<BaseAlphaModes>();
+    app.init_resource::<BaseStandardMaterials>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        restore_standard_materials
+            .run_if(resource_changed::<OpacityMap>)
+            .in_set(OpacitySet::Apply),
+    );
+    app.register_opacity::<LightQuery<PointLight>>();
+    app.register_opacity::<LightQuery<SpotLight>>();
+    app.register_opacity::<LightQuery<DirectionalLight>>();
+    app.register_opacity::<LightQuery<EnvironmentMapLight>>();
+    app.register_opacity::<SkyboxQuery>();
+    app.register_opacity::<AmbientLightQuery>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        fade_ambient_light
+            .run_if(
+                resource_exists::<AmbientFade>.and(
+                    resource_changed::<AmbientFade>
+                        .or(resource_changed::<OpacityMap>)
+                        .or(resource_changed::<GlobalOpacity>),
+                ),
+            )
+            .in_set(OpacitySet::Apply),
+    );
+    app.register_opacity::<DistanceFogQuery>();
+    app.register_opacity::<FogVolumeQuery>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<StandardMaterialOpacityPolicy>()
+        .register_type::<BaseStandardMaterial>()
+        .register_type::<BaseLightIntensity>()
+        .register_type::<BaseSkyboxBrightness>()
+        .register_type::<BaseAmbientBrightness>()
+        .register_type::<AmbientFade>()
+        .register_type::<BaseFogAlpha>()
+        .register_type::<BaseFogDensity>();
 }
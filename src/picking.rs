@@ -0,0 +1,69 @@
+use bevy::{
+    app::App,
+    ecs::schedule::{common_conditions::resource_changed, Condition, IntoScheduleConfigs},
+    picking::Pickable,
+    prelude::{Commands, Component, Entity, Query, Res, Resource, Without},
+};
+
+use crate::{OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectResource,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Entities with computed opacity less than or equal to this value cannot be picked.
+///
+/// Defaults to `0.0`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityPickingThreshold(pub f32);
+
+/// Stores the original [`Pickable`] of an entity while it is too transparent to be picked.
+#[derive(Debug, Component)]
+struct PickingDisabled(Option<Pickable>);
+
+fn update_picking(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    threshold: Res<OpacityPickingThreshold>,
+    pickables: Query<Option<&Pickable>, Without<PickingDisabled>>,
+    disabled: Query<(Entity, &PickingDisabled)>,
+) {
+    for (entity, PickingDisabled(previous)) in &disabled {
+        if map
+            .get(entity)
+            .is_some_and(|opacity| opacity <= threshold.0)
+        {
+            continue;
+        }
+        let mut entity = commands.entity(entity);
+        match previous {
+            Some(pickable) => entity.try_insert(pickable.clone()),
+            None => entity.try_remove::<Pickable>(),
+        };
+        entity.try_remove::<PickingDisabled>();
+    }
+    for (entity, opacity) in map.iter() {
+        if opacity > threshold.0 {
+            continue;
+        }
+        if let Ok(previous) = pickables.get(entity) {
+            commands
+                .entity(entity)
+                .try_insert((Pickable::IGNORE, PickingDisabled(previous.cloned())));
+        }
+    }
+}
+
+pub fn opacity_plugin_picking(app: &mut App) {
+    app.init_resource::<OpacityPickingThreshold>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityPickingThreshold>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        update_picking
+            .run_if(resource_changed::<OpacityMap>.or(resource_changed::<OpacityPickingThreshold>))
+            .in_set(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,302 @@
+use bevy::{
+    app::App,
+    asset::{embedded_asset, io::embedded::EmbeddedAssetRegistry, DirectAssetAccessExt, Handle},
+    color::{Color, ColorToComponents, LinearRgba},
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::{query::QueryItem, schedule::IntoScheduleConfigs, world::FromWorld},
+    prelude::{Commands, Component, Entity, Mut, Query, Res, ResMut, Resource, With, World},
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, CachedRenderPipelineId,
+            ColorTargetState, ColorWrites, FragmentState, MultisampleState, Operations,
+            PipelineCache, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
+            RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor, Shader,
+            ShaderStages, SpecializedRenderPipeline, SpecializedRenderPipelines, TextureFormat,
+            TextureSampleType,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::ViewTarget,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use crate::{OpacityExtension, OpacityQuery};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Fades the output of a camera to a color in a post-processing pass,
+/// driven by the computed opacity of the camera entity.
+///
+/// Unlike [`ScreenFade`](crate::ScreenFade) the fade runs after tonemapping,
+/// so it is not affected by bloom or HDR, and UI is drawn on top of it unfaded.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, PostProcessFade};
+/// # fn f(mut commands: Commands) {
+/// commands.spawn((
+///     Camera3d::default(),
+///     PostProcessFade::new(Color::BLACK),
+///     Opacity::new_fade_in(1.0),
+/// ));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct PostProcessFade {
+    /// The color the output fades to at opacity `0.0`.
+    pub color: Color,
+    opacity: f32,
+}
+
+impl PostProcessFade {
+    /// Fades the output of a camera to `color`.
+    pub const fn new(color: Color) -> Self {
+        PostProcessFade {
+            color,
+            opacity: 1.0,
+        }
+    }
+
+    /// Returns the opacity last written to this fade.
+    pub const fn opacity(&self) -> f32 {
+        self.opacity
+    }
+}
+
+/// Fades to black.
+impl Default for PostProcessFade {
+    fn default() -> Self {
+        PostProcessFade::new(Color::BLACK)
+    }
+}
+
+impl OpacityQuery for &'static mut PostProcessFade {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Mut<PostProcessFade>, _: &mut (), opacity: f32) {
+        this.opacity = opacity;
+    }
+}
+
+// `ShaderType` generates trait checks of the fields that are never called.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::{math::Vec4, prelude::Component, render::render_resource::ShaderType};
+
+    /// [`PostProcessFade`](super::PostProcessFade) in the render world,
+    /// only extracted while the fade is visible.
+    #[derive(Debug, Clone, Copy, Component, ShaderType)]
+    pub struct PostProcessFadeUniform {
+        pub color: Vec4,
+        pub opacity: f32,
+    }
+}
+
+use uniform::PostProcessFadeUniform;
+
+impl ExtractComponent for PostProcessFade {
+    type QueryData = &'static PostProcessFade;
+    type QueryFilter = ();
+    type Out = PostProcessFadeUniform;
+
+    fn extract_component(fade: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        (fade.opacity < 1.0).then(|| PostProcessFadeUniform {
+            color: LinearRgba::from(fade.color).to_vec4(),
+            opacity: fade.opacity.max(0.0),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, RenderLabel)]
+struct PostProcessFadeLabel;
+
+#[derive(Resource)]
+struct PostProcessFadePipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+impl FromWorld for PostProcessFadePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "post_process_fade_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<PostProcessFadeUniform>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.load_asset("embedded://bevy_mod_opacity/post_process.wgsl");
+        PostProcessFadePipeline {
+            layout,
+            sampler,
+            shader,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for PostProcessFadePipeline {
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: TextureFormat) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("post_process_fade_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: Vec::new(),
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+/// The pipeline of a view, specialized for the format of its main texture.
+#[derive(Component)]
+struct ViewPostProcessFadePipeline(CachedRenderPipelineId);
+
+fn prepare_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<PostProcessFadePipeline>>,
+    pipeline: Res<PostProcessFadePipeline>,
+    views: Query<(Entity, &ViewTarget), With<PostProcessFadeUniform>>,
+) {
+    for (entity, target) in &views {
+        let id = pipelines.specialize(&pipeline_cache, &pipeline, target.main_texture_format());
+        commands
+            .entity(entity)
+            .insert(ViewPostProcessFadePipeline(id));
+    }
+}
+
+#[derive(Default)]
+struct PostProcessFadeNode;
+
+impl ViewNode for PostProcessFadeNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static ViewPostProcessFadePipeline,
+        &'static PostProcessFadeUniform,
+        &'static DynamicUniformIndex<PostProcessFadeUniform>,
+    );
+
+    fn run(
+        &self,
+        _: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (target, pipeline, _, index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let fade_pipeline = world.resource::<PostProcessFadePipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(pipeline.0)
+        else {
+            return Ok(());
+        };
+        let uniforms = world.resource::<ComponentUniforms<PostProcessFadeUniform>>();
+        let Some(uniform) = uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+        // Source and destination swap on every write, so the bind group is created each run.
+        let post_process = target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "post_process_fade_bind_group",
+            &fade_pipeline.layout,
+            &BindGroupEntries::sequential((post_process.source, &fade_pipeline.sampler, uniform)),
+        );
+        let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("post_process_fade_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_render_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[index.index()]);
+        pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+pub fn opacity_plugin_post_process(app: &mut App) {
+    app.register_opacity::<&'static mut PostProcessFade>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<PostProcessFade>();
+    app.add_plugins((
+        ExtractComponentPlugin::<PostProcessFade>::default(),
+        UniformComponentPlugin::<PostProcessFadeUniform>::default(),
+    ));
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .init_resource::<SpecializedRenderPipelines<PostProcessFadePipeline>>()
+        .add_systems(Render, prepare_pipelines.in_set(RenderSet::Prepare))
+        .add_render_graph_node::<ViewNodeRunner<PostProcessFadeNode>>(Core3d, PostProcessFadeLabel)
+        .add_render_graph_edges(
+            Core3d,
+            (
+                Node3d::Tonemapping,
+                PostProcessFadeLabel,
+                Node3d::EndMainPassPostProcessing,
+            ),
+        )
+        .add_render_graph_node::<ViewNodeRunner<PostProcessFadeNode>>(Core2d, PostProcessFadeLabel)
+        .add_render_graph_edges(
+            Core2d,
+            (
+                Node2d::Tonemapping,
+                PostProcessFadeLabel,
+                Node2d::EndMainPassPostProcessing,
+            ),
+        );
+}
+
+pub fn opacity_plugin_post_process_finish(app: &mut App) {
+    if app.world().contains_resource::<EmbeddedAssetRegistry>() {
+        embedded_asset!(app, "post_process.wgsl");
+    }
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app.init_resource::<PostProcessFadePipeline>();
+}
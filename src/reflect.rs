@@ -0,0 +1,67 @@
+use bevy::{
+    app::App,
+    asset::AsAssetId,
+    color::{Alpha, Color, LinearRgba, Srgba},
+    ecs::schedule::{common_conditions::resource_changed, IntoScheduleConfigs},
+    log::warn_once,
+    prelude::{Entity, Query, Res, Without},
+    reflect::{GetPath, ParsedPath, PartialReflect, Reflect},
+};
+
+use crate::{OpacityAssets, OpacityIgnore, OpacityMap, OpacitySet};
+
+/// Set `alpha` on a reflected `f32` or bevy color, returns false if not supported.
+fn set_reflect_alpha(field: &mut dyn PartialReflect, alpha: f32) -> bool {
+    if let Some(value) = field.try_downcast_mut::<f32>() {
+        *value = alpha;
+    } else if let Some(color) = field.try_downcast_mut::<Color>() {
+        color.set_alpha(alpha);
+    } else if let Some(color) = field.try_downcast_mut::<LinearRgba>() {
+        color.set_alpha(alpha);
+    } else if let Some(color) = field.try_downcast_mut::<Srgba>() {
+        color.set_alpha(alpha);
+    } else {
+        return false;
+    }
+    true
+}
+
+pub fn register_asset_field<C>(app: &mut App, path: &str)
+where
+    C: AsAssetId,
+    C::Asset: Reflect,
+{
+    let parsed =
+        ParsedPath::parse(path).unwrap_or_else(|e| panic!("Invalid reflect path \"{path}\": {e}"));
+    let path = path.to_owned();
+    #[cfg(feature = "debug")]
+    crate::debug::register_target::<&C, ()>(app);
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (move |map: Res<OpacityMap>,
+               query: Query<(Entity, &C), Without<OpacityIgnore>>,
+               mut assets: OpacityAssets<C::Asset>| {
+            for (entity, component) in &query {
+                let Some(opacity) = map.get(entity) else {
+                    continue;
+                };
+                let Some(asset) = assets.get_mut(component.as_asset_id(), opacity, ()) else {
+                    continue;
+                };
+                let field = match asset.reflect_path_mut(&parsed) {
+                    Ok(field) => field,
+                    Err(e) => {
+                        warn_once!("Cannot apply opacity to \"{path}\": {e}");
+                        return;
+                    }
+                };
+                if !set_reflect_alpha(field, opacity) {
+                    warn_once!("Cannot apply opacity to \"{path}\": not a color or f32.");
+                    return;
+                }
+            }
+        })
+        .run_if(resource_changed::<OpacityMap>)
+        .in_set(OpacitySet::Apply),
+    );
+}
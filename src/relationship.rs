@@ -0,0 +1,52 @@
+use bevy::{
+    app::App,
+    ecs::{
+        query::Changed,
+        relationship::Relationship,
+        schedule::{common_conditions::resource_changed, Condition, IntoScheduleConfigs},
+    },
+    prelude::{Children, Entity, Query, Res, ResMut},
+};
+
+use crate::{
+    calculate_opacity, propagate_subtree, skip_small_updates, OpacityGroups, OpacityMap,
+    OpacityNode, OpacitySet, ProxiedEntities,
+};
+
+fn relationship_changed<R: Relationship>(changed: Query<(), Changed<R>>) -> bool {
+    !changed.is_empty()
+}
+
+fn propagate_relationship<R: Relationship>(
+    mut map: ResMut<OpacityMap>,
+    mut proxied: ResMut<ProxiedEntities>,
+    groups: Res<OpacityGroups>,
+    sources: Query<(Entity, &R)>,
+    nodes: Query<OpacityNode>,
+    children: Query<&Children>,
+) {
+    for (entity, relationship) in &sources {
+        if let (Some(opacity), Ok(node)) = (map.get(relationship.get()), nodes.get(entity)) {
+            propagate_subtree(
+                &mut map,
+                &mut proxied,
+                node,
+                opacity,
+                &nodes,
+                &children,
+                &groups,
+            );
+        }
+    }
+}
+
+pub(crate) fn register_relationship<R: Relationship>(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        propagate_relationship::<R>
+            .run_if(resource_changed::<OpacityMap>.or(relationship_changed::<R>))
+            .after(calculate_opacity)
+            .before(skip_small_updates)
+            .in_set(OpacitySet::Calculate),
+    );
+}
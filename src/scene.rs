@@ -0,0 +1,83 @@
+use bevy::{
+    app::App,
+    prelude::{ChildOf, DetectChangesMut, Query, Res, ResMut, Trigger},
+    scene::{SceneInstanceReady, SceneSpawner},
+};
+
+use crate::{LateOpacity, Opacity};
+#[cfg(feature = "3d")]
+use bevy::{
+    asset::{Assets, Handle},
+    pbr::{MeshMaterial3d, StandardMaterial},
+    platform::collections::{hash_map::Entry, HashMap},
+    prelude::With,
+};
+
+/// Marks [`Opacity`] of a spawned scene and the ancestors of its root as changed,
+/// so computed values are applied before the scene is first rendered.
+///
+/// Scenes ready after opacity was applied this frame are written in [`Last`](bevy::app::Last).
+fn on_scene_ready(
+    trigger: Trigger<SceneInstanceReady>,
+    spawner: Res<SceneSpawner>,
+    parents: Query<&ChildOf>,
+    mut late: ResMut<LateOpacity>,
+    mut query: Query<&mut Opacity>,
+) {
+    let root = trigger.target();
+    let ancestors = parents.iter_ancestors(root);
+    let entities = spawner.iter_instance_entities(trigger.instance_id);
+    late.bypass_change_detection().queue_descendants(root);
+    for entity in [root].into_iter().chain(ancestors).chain(entities) {
+        if let Ok(mut opacity) = query.get_mut(entity) {
+            opacity.set_changed();
+        }
+    }
+}
+
+/// Gives each spawned scene with [`Opacity`] its own copies of shared
+/// [`StandardMaterial`]s, so fading one glTF instance does not fade the others.
+///
+/// Materials shared within the same instance remain shared.
+#[cfg(feature = "3d")]
+fn unique_scene_materials(
+    trigger: Trigger<SceneInstanceReady>,
+    spawner: Res<SceneSpawner>,
+    parents: Query<&ChildOf>,
+    opacity: Query<(), With<Opacity>>,
+    mut query: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let root = trigger.target();
+    let entities = spawner.iter_instance_entities(trigger.instance_id);
+    let faded = [root]
+        .into_iter()
+        .chain(parents.iter_ancestors(root))
+        .chain(spawner.iter_instance_entities(trigger.instance_id))
+        .any(|entity| opacity.contains(entity));
+    if !faded {
+        return;
+    }
+    let mut copies = HashMap::<_, Handle<StandardMaterial>>::new();
+    for entity in entities {
+        let Ok(mut material) = query.get_mut(entity) else {
+            continue;
+        };
+        let handle = match copies.entry(material.id()) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => {
+                let Some(copy) = materials.get(&material.0).cloned() else {
+                    continue;
+                };
+                entry.insert(materials.add(copy)).clone()
+            }
+        };
+        material.0 = handle;
+    }
+}
+
+pub fn opacity_plugin_scene(app: &mut App) {
+    app.add_observer(on_scene_ready);
+    #[cfg(feature = "3d")]
+    app.add_observer(unique_scene_materials);
+}
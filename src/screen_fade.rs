@@ -0,0 +1,223 @@
+use bevy::{
+    app::App,
+    color::Color,
+    ecs::{
+        entity::ContainsEntity,
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+        system::Command,
+    },
+    prelude::{Commands, Component, Entity, Event, EventWriter, Query, With, World},
+    render::camera::{Camera, NormalizedRenderTarget},
+    ui::{BackgroundColor, GlobalZIndex, Node, PositionType, UiTargetCamera, Val},
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{Opacity, OpacitySet, UiOpacity};
+
+/// A [`Command`] that fades a fullscreen overlay over every window,
+/// or over a single camera with [`ScreenFade::for_camera`].
+///
+/// [`ScreenFadeFinished`] is sent for each window or camera when the fade completes.
+/// Fades of individual cameras are independent from each other and from fades of the whole window,
+/// for split-screen and multiple viewports.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::ScreenFade;
+/// fn game_over(mut commands: Commands) {
+///     commands.queue(ScreenFade::out(Color::BLACK, 0.5));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenFade {
+    color: Option<Color>,
+    time: f32,
+    camera: Option<Entity>,
+}
+
+impl ScreenFade {
+    /// Fade the screen to a color.
+    pub const fn out(color: Color, time: f32) -> Self {
+        ScreenFade {
+            color: Some(color),
+            time,
+            camera: None,
+        }
+    }
+
+    /// Fade the overlay out and remove it.
+    pub const fn reveal(time: f32) -> Self {
+        ScreenFade {
+            color: None,
+            time,
+            camera: None,
+        }
+    }
+
+    /// Only fade the viewport of a camera.
+    pub const fn for_camera(mut self, camera: Entity) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+}
+
+/// Sent when a [`ScreenFade`] completes on a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct ScreenFadeFinished {
+    /// The window faded, `None` if no window exists.
+    pub window: Option<Entity>,
+    /// The camera faded with [`ScreenFade::for_camera`], `None` if the whole window was faded.
+    pub camera: Option<Entity>,
+    /// True if the screen is now covered, false if revealed.
+    pub covered: bool,
+}
+
+/// The fullscreen overlay spawned by [`ScreenFade`].
+#[derive(Debug, Component)]
+struct ScreenFadeOverlay {
+    window: Option<Entity>,
+    camera: Option<Entity>,
+    fading: bool,
+}
+
+fn primary_window(world: &mut World) -> Option<Entity> {
+    world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .iter(world)
+        .next()
+}
+
+/// Returns the window a camera renders to.
+fn camera_window(world: &mut World, camera: Entity) -> Option<Entity> {
+    let primary = primary_window(world);
+    match world.get::<Camera>(camera)?.target.normalize(primary)? {
+        NormalizedRenderTarget::Window(window) => Some(window.entity()),
+        _ => None,
+    }
+}
+
+/// Returns each window paired with the camera with the highest order rendering to it.
+fn window_cameras(world: &mut World) -> Vec<(Option<Entity>, Option<Entity>)> {
+    let primary = primary_window(world);
+    let windows: Vec<_> = world
+        .query_filtered::<Entity, With<Window>>()
+        .iter(world)
+        .collect();
+    if windows.is_empty() {
+        return vec![(None, None)];
+    }
+    let mut cameras = world.query::<(Entity, &Camera)>();
+    windows
+        .into_iter()
+        .map(|window| {
+            let camera = cameras
+                .iter(world)
+                .filter(|(_, camera)| {
+                    camera.target.normalize(primary).is_some_and(|target| {
+                        matches!(target, NormalizedRenderTarget::Window(x) if x.entity() == window)
+                    })
+                })
+                .max_by_key(|(_, camera)| camera.order)
+                .map(|(entity, _)| entity);
+            (Some(window), camera)
+        })
+        .collect()
+}
+
+impl Command for ScreenFade {
+    fn apply(self, world: &mut World) {
+        let targets = match self.camera {
+            Some(camera) => vec![(camera_window(world, camera), Some(camera))],
+            None => window_cameras(world),
+        };
+        for (window, camera) in targets {
+            let overlay = world
+                .query::<(Entity, &ScreenFadeOverlay)>()
+                .iter(world)
+                .find(|(_, overlay)| match self.camera {
+                    Some(_) => overlay.camera == camera,
+                    None => overlay.camera.is_none() && overlay.window == window,
+                })
+                .map(|(entity, _)| entity);
+            match (overlay, self.color) {
+                (Some(entity), color) => {
+                    let mut entity = world.entity_mut(entity);
+                    if let Some(color) = color {
+                        entity.insert(BackgroundColor(color));
+                    }
+                    if let Some(mut overlay) = entity.get_mut::<ScreenFadeOverlay>() {
+                        overlay.fading = true;
+                    }
+                    if let Some(mut opacity) = entity.get_mut::<Opacity>() {
+                        let target = if color.is_some() { 1.0 } else { 0.0 };
+                        opacity.interpolate_to(target, self.time);
+                    }
+                }
+                (None, Some(color)) => {
+                    let mut opacity = Opacity::INVISIBLE;
+                    opacity.interpolate_to(1.0, self.time);
+                    let mut entity = world.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..Default::default()
+                        },
+                        BackgroundColor(color),
+                        UiOpacity::BACKGROUND,
+                        GlobalZIndex(i32::MAX),
+                        opacity,
+                        ScreenFadeOverlay {
+                            window,
+                            camera: self.camera,
+                            fading: true,
+                        },
+                    ));
+                    if let Some(camera) = camera {
+                        entity.insert(UiTargetCamera(camera));
+                    }
+                }
+                (None, None) => {
+                    world.send_event(ScreenFadeFinished {
+                        window,
+                        camera: self.camera,
+                        covered: false,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn finish_screen_fade(
+    mut commands: Commands,
+    mut query: Query<(Entity, &Opacity, &mut ScreenFadeOverlay)>,
+    mut events: EventWriter<ScreenFadeFinished>,
+) {
+    for (entity, opacity, mut overlay) in &mut query {
+        if !overlay.fading || opacity.current != opacity.target {
+            continue;
+        }
+        overlay.fading = false;
+        let covered = opacity.target > 0.0;
+        events.write(ScreenFadeFinished {
+            window: overlay.window,
+            camera: overlay.camera,
+            covered,
+        });
+        if !covered {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+pub fn opacity_plugin_screen_fade(app: &mut App) {
+    app.add_event::<ScreenFadeFinished>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        finish_screen_fade
+            .run_if(any_with_component::<ScreenFadeOverlay>)
+            .after(OpacitySet::Fading)
+            .before(OpacitySet::PostFade),
+    );
+}
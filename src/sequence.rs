@@ -0,0 +1,330 @@
+use bevy::{
+    app::App,
+    ecs::{
+        query::QueryFilter,
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+    },
+    prelude::{Bundle, Commands, Component, Entity, Event, EventWriter, Query, Res, With, Without},
+    time::{Real, Time},
+};
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug},
+    time::Duration,
+};
+
+use crate::{FadePaused, FadeUnscaled, Opacity, OpacitySet, OpacityTimeScale, ReducedMotion};
+
+/// A step of an [`OpacitySequence`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpacityStep {
+    /// Interpolate to an opacity and wait for the fade to complete.
+    FadeTo(f32, Duration),
+    /// Wait without changing opacity.
+    Hold(Duration),
+    /// Interpolate to `0.0` and wait for the fade to complete, the entity is not despawned.
+    FadeOut(Duration),
+    /// Send an [`OpacitySequenceEvent`] with this name.
+    Emit(Cow<'static, str>),
+    /// Trigger an event targeting the entity, see [`OpacitySequence::trigger`].
+    Trigger(StepTrigger),
+    /// Despawn the entity, ending the sequence.
+    Despawn,
+}
+
+/// Runs [`OpacityStep`]s on this entity's [`Opacity`] in order, removed when all steps complete.
+///
+/// Fade steps complete when the [`Opacity`] reaches its target, so they follow [`FadePaused`],
+/// [`OpacityTimeScale`] and [`ReducedMotion`] like any fade. Holds run on the clock of
+/// [`OpacityPlugin`](crate::OpacityPlugin), or [`Time<Real>`] with [`FadeUnscaled`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, OpacitySequence};
+/// # use std::time::Duration;
+/// fn toast(mut commands: Commands) {
+///     commands.spawn((
+///         Text::new("Saved"),
+///         Opacity::INVISIBLE,
+///         OpacitySequence::new()
+///             .fade_to(1.0, Duration::from_millis(200))
+///             .hold(Duration::from_secs(3))
+///             .fade_out(Duration::from_millis(500))
+///             .despawn(),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Component)]
+#[require(Opacity)]
+pub struct OpacitySequence {
+    pub steps: Vec<OpacityStep>,
+    /// Index of the current step.
+    pub index: usize,
+    /// Time in seconds since the current step started.
+    pub elapsed: f32,
+    started: bool,
+}
+
+impl OpacitySequence {
+    /// Creates an empty sequence.
+    pub const fn new() -> Self {
+        OpacitySequence {
+            steps: Vec::new(),
+            index: 0,
+            elapsed: 0.0,
+            started: false,
+        }
+    }
+
+    /// Adds a step to the sequence.
+    pub fn then(mut self, step: OpacityStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Interpolate to an opacity.
+    pub fn fade_to(self, opacity: f32, duration: Duration) -> Self {
+        self.then(OpacityStep::FadeTo(opacity, duration))
+    }
+
+    /// Wait without changing opacity.
+    pub fn hold(self, duration: Duration) -> Self {
+        self.then(OpacityStep::Hold(duration))
+    }
+
+    /// Interpolate to `0.0`.
+    pub fn fade_out(self, duration: Duration) -> Self {
+        self.then(OpacityStep::FadeOut(duration))
+    }
+
+    /// Send an [`OpacitySequenceEvent`].
+    pub fn emit(self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.then(OpacityStep::Emit(name.into()))
+    }
+
+    /// Trigger a default `E` targeting the entity, observed with [`Trigger<E>`](bevy::prelude::Trigger).
+    pub fn trigger<E: Event + Default>(self) -> Self {
+        self.then(OpacityStep::Trigger(StepTrigger::new::<E>()))
+    }
+
+    /// Despawn the entity.
+    pub fn despawn(self) -> Self {
+        self.then(OpacityStep::Despawn)
+    }
+
+    /// Returns the current step, `None` if the sequence completed.
+    pub fn current(&self) -> Option<&OpacityStep> {
+        self.steps.get(self.index)
+    }
+}
+
+/// Triggers an event on an entity, created by [`OpacitySequence::trigger`].
+#[derive(Clone, Copy)]
+pub struct StepTrigger(fn(&mut Commands, Entity));
+
+impl StepTrigger {
+    /// Trigger a default `E` targeting the entity.
+    pub fn new<E: Event + Default>() -> Self {
+        StepTrigger(|commands, entity| commands.trigger_targets(E::default(), entity))
+    }
+}
+
+impl PartialEq for StepTrigger {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::fn_addr_eq(self.0, other.0)
+    }
+}
+
+impl Debug for StepTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StepTrigger")
+    }
+}
+
+/// Sent by [`OpacityStep::Emit`].
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct OpacitySequenceEvent {
+    pub entity: Entity,
+    pub name: Cow<'static, str>,
+}
+
+/// Start a fade step, completing it at once when reduced motion makes fades instant.
+fn start_fade(
+    opacity: &mut Opacity,
+    target: f32,
+    duration: Duration,
+    reduced_motion: &ReducedMotion,
+) {
+    opacity.interpolate_to(target, duration.as_secs_f32());
+    if reduced_motion.speed(1.0).is_infinite() {
+        opacity.set(target);
+    }
+}
+
+fn run_sequences<C: Default + Send + Sync + 'static, F: QueryFilter>(
+    mut commands: Commands,
+    time: Res<Time<C>>,
+    scale: Res<OpacityTimeScale>,
+    reduced_motion: Res<ReducedMotion>,
+    mut events: EventWriter<OpacitySequenceEvent>,
+    mut query: Query<(Entity, &mut OpacitySequence, &mut Opacity), (F, Without<FadePaused>)>,
+) {
+    let dt = scale.scale_delta(time.delta_secs());
+    for (entity, mut sequence, mut opacity) in &mut query {
+        let sequence = &mut *sequence;
+        sequence.elapsed += dt;
+        while let Some(step) = sequence.steps.get(sequence.index) {
+            if !sequence.started {
+                sequence.started = true;
+                match step {
+                    OpacityStep::FadeTo(target, duration) => {
+                        start_fade(&mut opacity, *target, *duration, &reduced_motion);
+                    }
+                    OpacityStep::FadeOut(duration) => {
+                        start_fade(&mut opacity, 0.0, *duration, &reduced_motion);
+                    }
+                    OpacityStep::Emit(name) => {
+                        events.write(OpacitySequenceEvent {
+                            entity,
+                            name: name.clone(),
+                        });
+                    }
+                    OpacityStep::Trigger(trigger) => (trigger.0)(&mut commands, entity),
+                    OpacityStep::Despawn => {
+                        commands.entity(entity).try_despawn();
+                        break;
+                    }
+                    OpacityStep::Hold(_) => (),
+                }
+            }
+            match step {
+                OpacityStep::FadeTo(..) | OpacityStep::FadeOut(_) if opacity.is_fading() => break,
+                OpacityStep::FadeTo(..) | OpacityStep::FadeOut(_) => sequence.elapsed = 0.0,
+                OpacityStep::Hold(duration) => {
+                    let duration = duration.as_secs_f32();
+                    if sequence.elapsed < duration {
+                        break;
+                    }
+                    sequence.elapsed -= duration;
+                }
+                _ => (),
+            }
+            sequence.index += 1;
+            sequence.started = false;
+        }
+        if sequence.index >= sequence.steps.len() {
+            commands.entity(entity).try_remove::<OpacitySequence>();
+        }
+    }
+}
+
+/// An [`Opacity`] followed by the steps of an [`OpacitySequence`], built with [`OpacityThen`].
+#[derive(Debug, Clone, PartialEq, Bundle)]
+pub struct OpacityChain {
+    opacity: Opacity,
+    sequence: OpacitySequence,
+}
+
+/// Chain follow up steps after the current fade of an [`Opacity`],
+/// for behaviors too simple for a full [`OpacitySequence`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, OpacityThen};
+/// fn damage_number(mut commands: Commands) {
+///     commands.spawn((
+///         Text2d::new("12"),
+///         Opacity::new_fade_in(0.1)
+///             .then_hold(1.0)
+///             .then_fade_out(0.5)
+///             .then_despawn(),
+///     ));
+/// }
+/// ```
+pub trait OpacityThen: Sized {
+    /// Converts into an [`OpacityChain`].
+    fn into_chain(self) -> OpacityChain;
+
+    /// Interpolate to an opacity over `time` seconds.
+    fn then_fade_to(self, opacity: f32, time: f32) -> OpacityChain {
+        let mut chain = self.into_chain();
+        chain.sequence = chain.sequence.fade_to(opacity, seconds(time));
+        chain
+    }
+
+    /// Interpolate to `1.0` over `time` seconds.
+    fn then_fade_in(self, time: f32) -> OpacityChain {
+        self.then_fade_to(1.0, time)
+    }
+
+    /// Interpolate to `0.0` over `time` seconds, the entity is not despawned.
+    fn then_fade_out(self, time: f32) -> OpacityChain {
+        let mut chain = self.into_chain();
+        chain.sequence = chain.sequence.fade_out(seconds(time));
+        chain
+    }
+
+    /// Wait for `time` seconds.
+    fn then_hold(self, time: f32) -> OpacityChain {
+        let mut chain = self.into_chain();
+        chain.sequence = chain.sequence.hold(seconds(time));
+        chain
+    }
+
+    /// Trigger a default `E` targeting the entity.
+    fn then_emit<E: Event + Default>(self) -> OpacityChain {
+        let mut chain = self.into_chain();
+        chain.sequence = chain.sequence.trigger::<E>();
+        chain
+    }
+
+    /// Despawn the entity.
+    fn then_despawn(self) -> OpacityChain {
+        let mut chain = self.into_chain();
+        chain.sequence = chain.sequence.despawn();
+        chain
+    }
+}
+
+/// Starts after the fade in progress completes.
+impl OpacityThen for Opacity {
+    fn into_chain(self) -> OpacityChain {
+        let mut sequence = OpacitySequence::new();
+        if self.is_fading() {
+            sequence = sequence.hold(seconds(self.remaining_time()));
+        }
+        OpacityChain {
+            opacity: self,
+            sequence,
+        }
+    }
+}
+
+impl OpacityThen for OpacityChain {
+    fn into_chain(self) -> OpacityChain {
+        self
+    }
+}
+
+fn seconds(time: f32) -> Duration {
+    Duration::try_from_secs_f32(time).unwrap_or_default()
+}
+
+pub fn opacity_plugin_sequence(app: &mut App) {
+    app.add_event::<OpacitySequenceEvent>();
+}
+
+/// Run [`OpacitySequence`]s with the clock of [`OpacityPlugin`](crate::OpacityPlugin),
+/// or [`Time<Real>`] with [`FadeUnscaled`].
+pub(crate) fn opacity_plugin_sequence_clock<C: Default + Send + Sync + 'static>(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            run_sequences::<C, Without<FadeUnscaled>>,
+            run_sequences::<Real, With<FadeUnscaled>>,
+        )
+            .run_if(any_with_component::<OpacitySequence>)
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
@@ -0,0 +1,76 @@
+use bevy::{
+    app::App,
+    ecs::schedule::{common_conditions::resource_changed, Condition, IntoScheduleConfigs},
+    pbr::NotShadowCaster,
+    prelude::{Commands, Component, Entity, Mesh3d, Query, Res, Resource, With, Without},
+};
+
+use crate::{OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectResource,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Meshes with computed opacity less than or equal to this value
+/// stop casting shadows with [`NotShadowCaster`].
+///
+/// [`NotShadowCaster`] is removed again when opacity rises above the threshold,
+/// meshes that already had it are not affected.
+///
+/// Defaults to `0.0`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::OpacityShadowThreshold;
+/// App::new().insert_resource(OpacityShadowThreshold(0.5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Resource)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Resource, Default))]
+pub struct OpacityShadowThreshold(pub f32);
+
+/// Marks a mesh whose [`NotShadowCaster`] was inserted by this crate.
+#[derive(Debug, Component)]
+struct ShadowsDisabled;
+
+fn update_shadows(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    threshold: Res<OpacityShadowThreshold>,
+    meshes: Query<(), (With<Mesh3d>, Without<NotShadowCaster>)>,
+    disabled: Query<Entity, With<ShadowsDisabled>>,
+) {
+    for entity in &disabled {
+        if map
+            .get(entity)
+            .is_some_and(|opacity| opacity <= threshold.0)
+        {
+            continue;
+        }
+        commands
+            .entity(entity)
+            .try_remove::<(NotShadowCaster, ShadowsDisabled)>();
+    }
+    for (entity, opacity) in map.iter() {
+        if opacity > threshold.0 {
+            continue;
+        }
+        if meshes.contains(entity) {
+            commands
+                .entity(entity)
+                .try_insert((NotShadowCaster, ShadowsDisabled));
+        }
+    }
+}
+
+pub fn opacity_plugin_shadow(app: &mut App) {
+    app.init_resource::<OpacityShadowThreshold>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityShadowThreshold>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        update_shadows
+            .run_if(resource_changed::<OpacityMap>.or(resource_changed::<OpacityShadowThreshold>))
+            .in_set(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,126 @@
+use bevy::{
+    app::App,
+    ecs::{
+        query::Changed,
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+    },
+    prelude::{Component, DetectChangesMut, Query, Visibility},
+};
+use std::time::Duration;
+
+use crate::{Opacity, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Fades this entity in when its [`Visibility`] is changed from [`Visibility::Hidden`],
+/// and fades it out before hiding it when set to [`Visibility::Hidden`].
+///
+/// Toggle [`Visibility`] as usual, for example on dialogs and toasts.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::FadeOnVisibility;
+/// # use std::time::Duration;
+/// fn spawn_dialog(mut commands: Commands) {
+///     commands.spawn((
+///         Node::default(),
+///         Visibility::Hidden,
+///         FadeOnVisibility::new(Duration::from_millis(200)),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+#[require(Visibility, Opacity)]
+pub struct FadeOnVisibility {
+    /// Time to fade in and out.
+    pub duration: Duration,
+    /// The last visibility other than [`Visibility::Hidden`], `None` before it is first seen.
+    visible: Option<Visibility>,
+    shown: bool,
+    hiding: bool,
+}
+
+impl FadeOnVisibility {
+    /// Creates a fade on visibility changes lasting `duration`.
+    pub const fn new(duration: Duration) -> Self {
+        FadeOnVisibility {
+            duration,
+            visible: None,
+            shown: false,
+            hiding: false,
+        }
+    }
+
+    /// Returns true if this entity is fading out before being hidden.
+    pub const fn is_hiding(&self) -> bool {
+        self.hiding
+    }
+
+    fn fade(&self, opacity: &mut Opacity, target: f32) {
+        if self.duration.is_zero() {
+            opacity.set(target);
+        } else {
+            opacity.interpolate_to(target, self.duration.as_secs_f32());
+        }
+    }
+}
+
+fn fade_on_visibility(
+    mut query: Query<(&mut Visibility, &mut FadeOnVisibility, &mut Opacity), Changed<Visibility>>,
+) {
+    for (mut visibility, mut fade, mut opacity) in &mut query {
+        if fade.visible.is_none() {
+            fade.shown = *visibility != Visibility::Hidden;
+            fade.visible = Some(Visibility::Inherited);
+        }
+        match *visibility {
+            Visibility::Hidden if fade.shown && !fade.hiding => {
+                // Keep showing the entity until faded out,
+                // the change is still seen by visibility propagation.
+                *visibility.bypass_change_detection() = fade.visible.unwrap_or_default();
+                fade.hiding = true;
+                fade.fade(&mut opacity, 0.0);
+            }
+            Visibility::Hidden => (),
+            shown => {
+                fade.visible = Some(shown);
+                if !fade.shown {
+                    opacity.set(0.0);
+                }
+                if !fade.shown || fade.hiding {
+                    fade.shown = true;
+                    fade.hiding = false;
+                    fade.fade(&mut opacity, 1.0);
+                }
+            }
+        }
+    }
+}
+
+fn finish_hiding(mut query: Query<(&mut Visibility, &mut FadeOnVisibility, &Opacity)>) {
+    for (mut visibility, mut fade, opacity) in &mut query {
+        if fade.hiding && opacity.get() <= 0.0 {
+            fade.hiding = false;
+            fade.shown = false;
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+pub fn opacity_plugin_show(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<FadeOnVisibility>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            fade_on_visibility
+                .in_set(OpacitySet::Fading)
+                .before(crate::Interpolation),
+            finish_hiding
+                .after(OpacitySet::Fading)
+                .before(OpacitySet::PostFade),
+        )
+            .run_if(any_with_component::<FadeOnVisibility>),
+    );
+}
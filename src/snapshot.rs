@@ -0,0 +1,77 @@
+use bevy::{
+    app::App,
+    ecs::entity::EntityHashMap,
+    prelude::{Children, Entity, Resource, World},
+};
+
+use crate::{Opacity, OpacityMap};
+
+/// The [`Opacity`] and computed opacity of every entity in a subtree at some point in time.
+///
+/// Taken and restored with [`OpacityCommandsExt::snapshot_opacity`](crate::OpacityCommandsExt::snapshot_opacity)
+/// and [`OpacityCommandsExt::restore_opacity`](crate::OpacityCommandsExt::restore_opacity),
+/// or directly on a [`World`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpacitySnapshot(Vec<(Entity, Option<Opacity>, Option<f32>)>);
+
+impl OpacitySnapshot {
+    /// Snapshot an entity and its descendants.
+    pub fn take(world: &World, root: Entity) -> Self {
+        let map = world.get_resource::<OpacityMap>();
+        let mut entries = Vec::new();
+        let mut stack = vec![root];
+        while let Some(entity) = stack.pop() {
+            if world.get_entity(entity).is_err() {
+                continue;
+            }
+            entries.push((
+                entity,
+                world.get::<Opacity>(entity).copied(),
+                map.and_then(|map| map.get(entity)),
+            ));
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children);
+            }
+        }
+        OpacitySnapshot(entries)
+    }
+
+    /// Restore the [`Opacity`] of every entity still alive,
+    /// [`Opacity`] is removed from entities that did not have it.
+    pub fn restore(&self, world: &mut World) {
+        for (entity, opacity, _) in &self.0 {
+            let Ok(mut entity) = world.get_entity_mut(*entity) else {
+                continue;
+            };
+            match opacity {
+                Some(opacity) => entity.insert(*opacity),
+                None => entity.remove::<Opacity>(),
+            };
+        }
+    }
+
+    /// Returns the [`Opacity`] of an entity when the snapshot was taken.
+    pub fn get(&self, entity: Entity) -> Option<Opacity> {
+        self.0
+            .iter()
+            .find(|(e, ..)| *e == entity)
+            .and_then(|(_, opacity, _)| *opacity)
+    }
+
+    /// Returns the computed opacity of an entity when the snapshot was taken.
+    pub fn computed(&self, entity: Entity) -> Option<f32> {
+        self.0
+            .iter()
+            .find(|(e, ..)| *e == entity)
+            .and_then(|(.., computed)| *computed)
+    }
+}
+
+/// [`Resource`] of snapshots taken by [`OpacityCommandsExt::snapshot_opacity`](crate::OpacityCommandsExt::snapshot_opacity),
+/// keyed by the root of the snapshot.
+#[derive(Debug, Default, Resource)]
+pub struct OpacitySnapshots(pub EntityHashMap<OpacitySnapshot>);
+
+pub fn opacity_plugin_snapshot(app: &mut App) {
+    app.init_resource::<OpacitySnapshots>();
+}
@@ -0,0 +1,113 @@
+use bevy::{
+    app::App,
+    ecs::{
+        component::HookContext,
+        query::QueryFilter,
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+        world::DeferredWorld,
+    },
+    prelude::{Commands, Component, Entity, Query, Res, With, Without},
+    time::{Real, Time},
+};
+use std::time::Duration;
+
+use crate::{FadePaused, FadeUnscaled, Opacity, OpacitySet, OpacityTimeScale};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Starts this entity at opacity `0.0` when added and fades it in to its [`Opacity`] target.
+///
+/// Add as a required component of a marker to fade in every entity of that category.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::FadeInOnAdd;
+/// # use std::time::Duration;
+/// #[derive(Component)]
+/// #[require(FadeInOnAdd(Duration::from_millis(300)))]
+/// struct Popup;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+#[component(on_add = fade_in_on_add)]
+#[require(Opacity)]
+pub struct FadeInOnAdd(pub Duration);
+
+fn fade_in_on_add(mut world: DeferredWorld, context: HookContext) {
+    let Some(&FadeInOnAdd(duration)) = world.get::<FadeInOnAdd>(context.entity) else {
+        return;
+    };
+    if let Some(mut opacity) = world.get_mut::<Opacity>(context.entity) {
+        let target = opacity.get_target();
+        opacity.current = 0.0;
+        opacity.interpolate_to(target, duration.as_secs_f32());
+    }
+}
+
+/// Waits for `lifetime`, then calls [`Opacity::fade_out`] so this entity
+/// fades out in `fade` and is despawned.
+///
+/// The lifetime runs on the clock of [`OpacityPlugin`](crate::OpacityPlugin),
+/// or [`Time<Real>`] with [`FadeUnscaled`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::FadeOutAfter;
+/// # use std::time::Duration;
+/// fn damage_number(mut commands: Commands) {
+///     commands.spawn((
+///         Text2d::new("12"),
+///         FadeOutAfter::new(Duration::from_secs(1), Duration::from_millis(300)),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+#[require(Opacity)]
+pub struct FadeOutAfter {
+    pub lifetime: Duration,
+    pub fade: Duration,
+    elapsed: f32,
+}
+
+impl FadeOutAfter {
+    /// Fade out in `fade` after `lifetime`.
+    pub const fn new(lifetime: Duration, fade: Duration) -> Self {
+        FadeOutAfter {
+            lifetime,
+            fade,
+            elapsed: 0.0,
+        }
+    }
+}
+
+fn fade_out_after<C: Default + Send + Sync + 'static, F: QueryFilter>(
+    mut commands: Commands,
+    time: Res<Time<C>>,
+    scale: Res<OpacityTimeScale>,
+    mut query: Query<(Entity, &mut FadeOutAfter, &mut Opacity), (F, Without<FadePaused>)>,
+) {
+    let dt = scale.scale_delta(time.delta_secs());
+    for (entity, mut after, mut opacity) in &mut query {
+        after.elapsed += dt;
+        if after.elapsed >= after.lifetime.as_secs_f32() {
+            opacity.fade_out(after.fade.as_secs_f32());
+            commands.entity(entity).try_remove::<FadeOutAfter>();
+        }
+    }
+}
+
+/// Run [`FadeOutAfter`] lifetimes with the clock of [`OpacityPlugin`](crate::OpacityPlugin),
+/// or [`Time<Real>`] with [`FadeUnscaled`].
+pub(crate) fn opacity_plugin_spawn_clock<C: Default + Send + Sync + 'static>(app: &mut App) {
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            fade_out_after::<C, Without<FadeUnscaled>>,
+            fade_out_after::<Real, With<FadeUnscaled>>,
+        )
+            .run_if(any_with_component::<FadeOutAfter>)
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
@@ -1,12 +1,19 @@
 use bevy::{
     app::App,
-    asset::Assets,
-    color::Alpha,
-    ecs::system::{ResMut, SystemParam},
+    color::{Alpha, Color},
+    ecs::{query::QueryData, system::SystemParam},
+    prelude::{Res, ResMut},
     sprite::{ColorMaterial, Material2d, MeshMaterial2d, Sprite, Wireframe2dMaterial},
 };
 
-use crate::{OpacityAsset, OpacityExtension, OpacityQuery};
+use crate::{
+    mode::{
+        apply_cached_mode, apply_material_mode, opacity_plugin_base_alpha,
+        opacity_plugin_base_color, BaseColor, BaseMaterialAlphas, BaseMaterialColors,
+        MaterialColor, ModeColor, OpacityTint, ResolvedMode,
+    },
+    OpacityAsset, OpacityAssets, OpacityExtension, OpacityMode, OpacityQuery,
+};
 
 impl OpacityQuery for &mut Sprite {
     type Cx = ();
@@ -16,12 +23,94 @@ impl OpacityQuery for &mut Sprite {
     }
 }
 
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct SpriteQuery {
+    pub sprite: &'static mut Sprite,
+    pub base: Option<&'static BaseColor>,
+    pub tint: Option<&'static OpacityTint>,
+    mode: Option<&'static ResolvedMode>,
+}
+
+impl OpacityQuery for SpriteQuery {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        apply_cached_mode(
+            &mut this.sprite.color,
+            this.base,
+            this.mode,
+            this.tint,
+            opacity,
+        );
+    }
+}
+
+impl ModeColor for Sprite {
+    fn color_mut(&mut self) -> &mut Color {
+        &mut self.color
+    }
+}
+
+#[derive(Debug, QueryData)]
+pub struct ColorMaterialQuery {
+    pub material: &'static MeshMaterial2d<ColorMaterial>,
+    pub mode: Option<&'static OpacityMode>,
+    pub tint: Option<&'static OpacityTint>,
+    #[cfg(feature = "lyon")]
+    pub shape: bevy::ecs::query::Has<bevy_prototype_lyon::entity::Shape>,
+}
+
+impl OpacityQuery for ColorMaterialQuery {
+    type Cx = (
+        OpacityAssets<'static, 'static, ColorMaterial, (OpacityMode, Option<OpacityTint>)>,
+        ResMut<'static, BaseMaterialAlphas<ColorMaterial>>,
+        ResMut<'static, BaseMaterialColors<ColorMaterial>>,
+        Res<'static, OpacityMode>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (materials, alphas, colors, mode): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        // Shapes share one material and fade through their own colors instead.
+        #[cfg(feature = "lyon")]
+        if this.shape {
+            return;
+        }
+        let mode = OpacityMode::resolve(this.mode, mode);
+        let id = this.material.id();
+        if let Some(material) = materials.get_mut(id, opacity, (mode, this.tint.copied())) {
+            let alpha = alphas.cache(id, material.color.alpha());
+            apply_material_mode(
+                mode,
+                id,
+                &mut material.color,
+                alpha,
+                colors,
+                this.tint,
+                opacity,
+            );
+            if mode != OpacityMode::Dim {
+                material.color.set_alpha(alpha * opacity);
+            }
+        }
+    }
+}
+
 impl OpacityAsset for ColorMaterial {
     fn apply_opacity(&mut self, opacity: f32) {
         self.color.set_alpha(opacity)
     }
 }
 
+impl MaterialColor for ColorMaterial {
+    fn color_mut(&mut self) -> &mut Color {
+        &mut self.color
+    }
+}
+
 impl OpacityAsset for Wireframe2dMaterial {
     fn apply_opacity(&mut self, opacity: f32) {
         self.color.set_alpha(opacity)
@@ -32,20 +121,22 @@ impl<T> OpacityQuery for &MeshMaterial2d<T>
 where
     T: OpacityAsset + Material2d,
 {
-    type Cx = ResMut<'static, Assets<T>>;
+    type Cx = OpacityAssets<'static, 'static, T>;
 
     fn apply_opacity(
         this: &mut Self::Item<'_>,
         cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
         opacity: f32,
     ) {
-        if let Some(mat) = cx.get_mut(this.id()) {
+        if let Some(mat) = cx.get_mut(this.id(), opacity, ()) {
             mat.apply_opacity(opacity);
         }
     }
 }
 
 pub fn opacity_plugin_2d(app: &mut App) {
-    app.register_opacity_component::<Sprite>();
-    app.register_opacity_material2d::<ColorMaterial>();
+    app.register_opacity_parallel::<SpriteQuery>();
+    opacity_plugin_base_color::<Sprite>(app);
+    app.register_opacity::<ColorMaterialQuery>();
+    opacity_plugin_base_alpha::<MeshMaterial2d<ColorMaterial>>(app);
 }
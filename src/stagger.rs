@@ -0,0 +1,147 @@
+use bevy::{
+    app::App,
+    ecs::{
+        query::QueryFilter,
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+    },
+    prelude::{Children, Commands, Component, Entity, Query, Res, With, Without},
+    time::{Real, Time},
+};
+use std::time::Duration;
+
+use crate::{FadePaused, FadeUnscaled, Opacity, OpacitySet, OpacityTimeScale, ReducedMotion};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Fades the children of this entity one after another in sibling order,
+/// each child starts its fade `per_child_delay` after the previous one.
+///
+/// Children without [`Opacity`] receive one. When fading in, children are held at `0.0`
+/// until their fade starts. Removed when every child has started fading.
+///
+/// Delays run on the clock of [`OpacityPlugin`](crate::OpacityPlugin), or [`Time<Real>`] with [`FadeUnscaled`],
+/// and are shortened like a fade of the same length by [`ReducedMotion`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::StaggeredFade;
+/// # use std::time::Duration;
+/// fn open_menu(mut commands: Commands) {
+///     commands.spawn((
+///         Node::default(),
+///         StaggeredFade::fade_in(Duration::from_millis(50), Duration::from_millis(200)),
+///         children![Text::new("Play"), Text::new("Options"), Text::new("Quit")],
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct StaggeredFade {
+    pub per_child_delay: Duration,
+    /// Duration of the fade of each child.
+    pub duration: Duration,
+    /// Fade children out to `0.0` instead of in, children are not despawned.
+    pub hide: bool,
+    elapsed: f32,
+    /// Number of children whose fade started.
+    started: usize,
+}
+
+impl StaggeredFade {
+    /// Fade children in to `1.0` one after another.
+    pub const fn fade_in(per_child_delay: Duration, duration: Duration) -> Self {
+        StaggeredFade {
+            per_child_delay,
+            duration,
+            hide: false,
+            elapsed: 0.0,
+            started: 0,
+        }
+    }
+
+    /// Fade children out to `0.0` one after another.
+    pub const fn fade_out(per_child_delay: Duration, duration: Duration) -> Self {
+        StaggeredFade {
+            hide: true,
+            ..StaggeredFade::fade_in(per_child_delay, duration)
+        }
+    }
+}
+
+type Staggered = (
+    Entity,
+    &'static mut StaggeredFade,
+    Option<&'static Children>,
+);
+
+fn staggered_fade<C: Default + Send + Sync + 'static, F: QueryFilter>(
+    mut commands: Commands,
+    time: Res<Time<C>>,
+    scale: Res<OpacityTimeScale>,
+    reduced_motion: Res<ReducedMotion>,
+    mut query: Query<Staggered, (F, Without<FadePaused>)>,
+    mut opacities: Query<Option<&mut Opacity>>,
+) {
+    let dt = scale.scale_delta(time.delta_secs());
+    for (entity, mut stagger, children) in &mut query {
+        let children = children.map(|children| &children[..]).unwrap_or_default();
+        let target = if stagger.hide { 0.0 } else { 1.0 };
+        let duration = stagger.duration.as_secs_f32();
+        let delay = stagger.per_child_delay.as_secs_f32();
+        // Shorten delays as reduced motion shortens a fade of the same length.
+        let speedup = (reduced_motion.speed(1.0 / delay) * delay).max(1.0);
+        if speedup.is_infinite() {
+            stagger.elapsed = f32::INFINITY;
+        } else {
+            stagger.elapsed += dt * speedup;
+        }
+        for (index, child) in children.iter().enumerate().skip(stagger.started) {
+            let Ok(opacity) = opacities.get_mut(*child) else {
+                // A child that cannot fade does not hold back the others.
+                if stagger.started == index {
+                    stagger.started += 1;
+                }
+                continue;
+            };
+            let due = stagger.elapsed >= delay * index as f32;
+            match opacity {
+                Some(mut opacity) if due => opacity.interpolate_to(target, duration),
+                Some(mut opacity) => {
+                    if !stagger.hide && opacity.get() != 0.0 {
+                        opacity.set(0.0);
+                    }
+                }
+                None => {
+                    let mut opacity = Opacity::new(if stagger.hide { 1.0 } else { 0.0 });
+                    if due {
+                        opacity.interpolate_to(target, duration);
+                    }
+                    commands.entity(*child).try_insert(opacity);
+                }
+            }
+            if due && stagger.started == index {
+                stagger.started += 1;
+            }
+        }
+        if stagger.started >= children.len() {
+            commands.entity(entity).try_remove::<StaggeredFade>();
+        }
+    }
+}
+
+/// Run [`StaggeredFade`]s with the clock of [`OpacityPlugin`](crate::OpacityPlugin),
+/// or [`Time<Real>`] with [`FadeUnscaled`].
+pub(crate) fn opacity_plugin_stagger_clock<C: Default + Send + Sync + 'static>(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<StaggeredFade>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            staggered_fade::<C, Without<FadeUnscaled>>,
+            staggered_fade::<Real, With<FadeUnscaled>>,
+        )
+            .run_if(any_with_component::<StaggeredFade>)
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
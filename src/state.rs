@@ -0,0 +1,52 @@
+use bevy::{
+    app::App,
+    ecs::schedule::IntoScheduleConfigs,
+    prelude::{Commands, Component, Entity, EventReader, Query},
+    state::state::{StateTransition, StateTransitionEvent, StateTransitionSteps, States},
+};
+use std::time::Duration;
+
+use crate::Opacity;
+
+/// Fades out and then despawns this entity when the state `S` is exited.
+///
+/// An alternative to `StateScoped` that does not remove the entity instantly,
+/// register the state with [`OpacityExtension::register_fade_out_on_exit`](crate::OpacityExtension::register_fade_out_on_exit).
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct FadeOutOnExit<S: States>(pub S, pub Duration);
+
+fn fade_out_on_exit<S: States>(
+    mut commands: Commands,
+    mut transitions: EventReader<StateTransitionEvent<S>>,
+    mut query: Query<(Entity, &FadeOutOnExit<S>, Option<&mut Opacity>)>,
+) {
+    let Some(transition) = transitions.read().last() else {
+        return;
+    };
+    if transition.entered == transition.exited {
+        return;
+    }
+    let Some(exited) = &transition.exited else {
+        return;
+    };
+    for (entity, FadeOutOnExit(state, duration), opacity) in &mut query {
+        if state != exited {
+            continue;
+        }
+        match opacity {
+            Some(mut opacity) => opacity.fade_out(duration.as_secs_f32()),
+            None => {
+                let mut opacity = Opacity::OPAQUE;
+                opacity.fade_out(duration.as_secs_f32());
+                commands.entity(entity).try_insert(opacity);
+            }
+        }
+    }
+}
+
+pub fn register_fade_out_on_exit<S: States>(app: &mut App) {
+    app.add_systems(
+        StateTransition,
+        fade_out_on_exit::<S>.in_set(StateTransitionSteps::ExitSchedules),
+    );
+}
@@ -0,0 +1,45 @@
+use bevy::{
+    app::App,
+    time::{Real, Time, Virtual},
+};
+use std::time::Duration;
+
+/// Extensions for [`App`] that advance opacity by an exact delta, for testing fade driven logic.
+pub trait OpacityTestExt {
+    /// Advance [`Time<Virtual>`] and [`Time<Real>`] by `delta` and run the opacity schedule once,
+    /// fades, propagation and writes to targets are complete when this returns.
+    ///
+    /// Unlike [`App::update`], other schedules do not run and the first step is not zero.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::{Opacity, OpacityPlugin, OpacityTestExt};
+    /// # use std::time::Duration;
+    /// let mut app = App::new();
+    /// app.add_plugins((MinimalPlugins, AssetPlugin::default(), OpacityPlugin::default()));
+    /// # app.init_asset::<StandardMaterial>().init_asset::<ColorMaterial>().init_asset::<Mesh>();
+    /// let entity = app.world_mut().spawn(Opacity::new_fade_in(1.0)).id();
+    /// app.step_opacity(Duration::from_millis(500));
+    /// assert_eq!(app.world().get::<Opacity>(entity).unwrap().get(), 0.5);
+    /// ```
+    fn step_opacity(&mut self, delta: Duration) -> &mut Self;
+}
+
+impl OpacityTestExt for App {
+    fn step_opacity(&mut self, delta: Duration) -> &mut Self {
+        let world = self.world_mut();
+        if let Some(mut time) = world.get_resource_mut::<Time<Real>>() {
+            time.advance_by(delta);
+        }
+        let generic = world.get_resource_mut::<Time<Virtual>>().map(|mut time| {
+            time.advance_by(delta);
+            time.as_generic()
+        });
+        if let Some(generic) = generic {
+            world.insert_resource(generic);
+        }
+        let schedule = crate::opacity_schedule(self);
+        self.world_mut().run_schedule(schedule);
+        self
+    }
+}
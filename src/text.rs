@@ -0,0 +1,62 @@
+use bevy::{app::App, color::Alpha, ecs::query::QueryData, prelude::Component, text::TextColor};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+use std::marker::PhantomData;
+
+use crate::{OpacityQuery, ParOpacityQueryPlugin};
+
+impl OpacityQuery for &mut TextColor {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        this.set_alpha(opacity);
+    }
+}
+
+/// Determine how opacity is applied to the [`TextColor`] of this entity,
+/// usually a `TextSpan` inside rich text.
+///
+/// Items without this component follow opacity exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub enum TextOpacity {
+    /// Alpha is set to the computed opacity multiplied by this weight,
+    /// e.g. `0.5` for a dimmed hint that still responds to fades.
+    Weight(f32),
+    /// Alpha is not controlled by opacity.
+    Independent,
+}
+
+impl Default for TextOpacity {
+    fn default() -> Self {
+        TextOpacity::Weight(1.0)
+    }
+}
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct TextColorQuery {
+    pub text_opacity: Option<&'static TextOpacity>,
+    pub color: &'static mut TextColor,
+}
+
+impl OpacityQuery for TextColorQuery {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        match this.text_opacity.copied().unwrap_or_default() {
+            TextOpacity::Weight(weight) => this.color.set_alpha(opacity * weight),
+            TextOpacity::Independent => (),
+        }
+    }
+}
+
+pub fn opacity_plugin_text(app: &mut App) {
+    app.add_plugins(ParOpacityQueryPlugin::<TextColorQuery>(PhantomData));
+    crate::reapply_on_change::<TextOpacity>(app);
+    #[cfg(feature = "reflect")]
+    app.register_type::<TextOpacity>();
+}
@@ -0,0 +1,136 @@
+use bevy::{
+    app::App,
+    ecs::{
+        query::QueryFilter,
+        schedule::{common_conditions::any_with_component, IntoScheduleConfigs},
+    },
+    math::curve::{Curve, EaseFunction},
+    prelude::{Component, Query, Res, With, Without},
+    time::{Real, Time},
+};
+use std::time::Duration;
+
+use crate::{FadePaused, FadeUnscaled, Opacity, OpacitySet, OpacityTimeScale, ReducedMotion};
+#[cfg(feature = "reflect")]
+use bevy::{prelude::ReflectComponent, reflect::Reflect};
+
+/// Animates every jump of this entity's [`Opacity`], like a call to [`Opacity::set`],
+/// over `duration` with `easing`, similar to a CSS transition.
+///
+/// Fades started with [`Opacity::fade_in`] and similar functions are not affected,
+/// combine with [`FadeOnVisibility`](crate::FadeOnVisibility) to also transition [`Visibility`](bevy::prelude::Visibility) toggles.
+///
+/// Runs on the clock of [`OpacityPlugin`](crate::OpacityPlugin), or [`Time<Real>`] with [`FadeUnscaled`],
+/// and is shortened like a fade of the same length by [`ReducedMotion`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, UiTransition};
+/// # use std::time::Duration;
+/// fn spawn_tooltip(mut commands: Commands) {
+///     commands.spawn((
+///         Node::default(),
+///         Opacity::INVISIBLE,
+///         UiTransition::new(Duration::from_millis(150), EaseFunction::CubicOut),
+///     ));
+/// }
+///
+/// fn hover(mut tooltip: Single<&mut Opacity>) {
+///     tooltip.set(1.0);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+#[require(Opacity)]
+pub struct UiTransition {
+    pub duration: Duration,
+    pub easing: EaseFunction,
+    /// The opacity last written by or seen by the transition, `None` before it is first seen.
+    shown: Option<f32>,
+    from: f32,
+    elapsed: f32,
+}
+
+impl UiTransition {
+    /// Creates a transition lasting `duration`.
+    pub const fn new(duration: Duration, easing: EaseFunction) -> Self {
+        UiTransition {
+            duration,
+            easing,
+            shown: None,
+            from: 0.0,
+            elapsed: f32::INFINITY,
+        }
+    }
+
+    /// Returns true if a transition is in progress.
+    pub fn is_transitioning(&self) -> bool {
+        self.elapsed < self.duration.as_secs_f32()
+    }
+}
+
+/// Transitions linearly in 200 milliseconds.
+impl Default for UiTransition {
+    fn default() -> Self {
+        UiTransition::new(Duration::from_millis(200), EaseFunction::Linear)
+    }
+}
+
+fn run_transitions<C: Default + Send + Sync + 'static, F: QueryFilter>(
+    time: Res<Time<C>>,
+    scale: Res<OpacityTimeScale>,
+    reduced_motion: Res<ReducedMotion>,
+    mut query: Query<(&mut UiTransition, &mut Opacity), (F, Without<FadePaused>)>,
+) {
+    let dt = scale.scale_delta(time.delta_secs());
+    for (mut transition, mut opacity) in &mut query {
+        let Some(shown) = transition.shown else {
+            transition.shown = Some(opacity.current);
+            continue;
+        };
+        if opacity.is_fading() {
+            transition.shown = Some(opacity.current);
+            transition.elapsed = f32::INFINITY;
+            continue;
+        }
+        if opacity.current != shown {
+            // Start from the displayed value, even if a transition was in progress.
+            transition.from = shown;
+            transition.elapsed = 0.0;
+        } else if !transition.is_transitioning() {
+            continue;
+        }
+        let duration = transition.duration.as_secs_f32();
+        let speedup = (reduced_motion.speed(1.0 / duration) * duration).max(1.0);
+        if speedup.is_infinite() {
+            transition.elapsed = transition.elapsed.max(duration);
+        } else {
+            transition.elapsed += dt * speedup;
+        }
+        let t = if duration > 0.0 {
+            (transition.elapsed / duration).min(1.0)
+        } else {
+            1.0
+        };
+        let eased = transition.easing.sample_clamped(t);
+        opacity.current = transition.from + (opacity.target - transition.from) * eased;
+        transition.shown = Some(opacity.current);
+    }
+}
+
+/// Run [`UiTransition`]s with the clock of [`OpacityPlugin`](crate::OpacityPlugin),
+/// or [`Time<Real>`] with [`FadeUnscaled`].
+pub(crate) fn opacity_plugin_transition_clock<C: Default + Send + Sync + 'static>(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<UiTransition>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        (
+            run_transitions::<C, Without<FadeUnscaled>>,
+            run_transitions::<Real, With<FadeUnscaled>>,
+        )
+            .run_if(any_with_component::<UiTransition>)
+            .in_set(OpacitySet::Fading)
+            .before(crate::Interpolation),
+    );
+}
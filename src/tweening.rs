@@ -0,0 +1,53 @@
+use bevy::{
+    app::{App, Update},
+    ecs::schedule::IntoScheduleConfigs,
+    math::FloatExt,
+};
+use bevy_tweening::{component_animator_system, AnimationSystem, Lens, Targetable, TweenCompleted};
+
+use crate::Opacity;
+
+/// A [`Lens`] tweening the value of [`Opacity`].
+///
+/// Tween `Opacity` instead of the alpha of sprites or materials,
+/// so propagation and writing alpha stay in this crate.
+/// Each step cancels the fade of the `Opacity`, so the tween does not despawn the entity,
+/// fade out with [`Opacity::fade_out`] to despawn it.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_tweening::{Animator, Tween};
+/// # use bevy_mod_opacity::{Opacity, OpacityLens};
+/// # use std::time::Duration;
+/// fn spawn(mut commands: Commands) {
+///     commands.spawn((
+///         Sprite::default(),
+///         Opacity::INVISIBLE,
+///         Animator::new(Tween::new(
+///             EaseFunction::QuadraticOut,
+///             Duration::from_secs(1),
+///             OpacityLens { start: 0.0, end: 1.0 },
+///         )),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpacityLens {
+    /// Start opacity.
+    pub start: f32,
+    /// End opacity.
+    pub end: f32,
+}
+
+impl Lens<Opacity> for OpacityLens {
+    fn lerp(&mut self, target: &mut dyn Targetable<Opacity>, ratio: f32) {
+        target.target_mut().set(self.start.lerp(self.end, ratio));
+    }
+}
+
+pub fn opacity_plugin_tweening(app: &mut App) {
+    app.add_event::<TweenCompleted>().add_systems(
+        Update,
+        component_animator_system::<Opacity>.in_set(AnimationSystem::AnimationUpdate),
+    );
+}
@@ -1,66 +1,313 @@
-use crate::{OpacityExtension, OpacityQuery};
-use bevy::ui::{BackgroundColor, BorderColor};
+use crate::{
+    mode::{
+        apply_cached_mode, opacity_plugin_base_color, BaseColor, ModeColor, OpacityTint,
+        ResolvedMode,
+    },
+    OpacityAsset, OpacityAssets, OpacityExtension, OpacityQuery, ParOpacityQueryPlugin,
+};
+use bevy::ui::{
+    BackgroundColor, BorderColor, BoxShadow, MaterialNode, Outline, TextShadow, UiMaterial,
+};
 use bevy::{
     app::App,
-    color::Alpha,
-    ecs::query::QueryData,
-    prelude::{Component, ImageNode},
+    color::{Alpha, Color},
+    ecs::{query::QueryData, system::SystemParam},
+    prelude::{Commands, Component, Entity, ImageNode},
+};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
 };
+use std::marker::PhantomData;
+use std::ops::{BitOr, BitOrAssign};
+
+/// Determine which colors of a UI node are controlled by opacity,
+/// and the weight opacity is multiplied by for each of them.
+///
+/// A target set to `None` is not affected by opacity, `Some(1.0)` follows opacity exactly,
+/// `Some(0.5)` fades at 50% of the computed opacity.
+///
+/// Items without this component behave as [`UiOpacity::default`],
+/// meaning only [`ImageNode`] and [`BoxShadow`] are controlled by opacity.
+///
+/// Constants naming targets, like [`UiOpacity::BORDER`], control only those targets.
+/// Flags can be combined with `|`, the left-hand side's weight takes priority.
+///
+/// ```
+/// # use bevy_mod_opacity::UiOpacity;
+/// let ui_opacity = UiOpacity::BORDER | UiOpacity::NONE.with_background(0.5);
+/// // Also keep the default image and box shadow.
+/// let ui_opacity = UiOpacity::BORDER | UiOpacity::DEFAULT;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct UiOpacity {
+    /// Weight of [`BackgroundColor`].
+    pub background: Option<f32>,
+    /// Weight of [`BorderColor`].
+    pub border: Option<f32>,
+    /// Weight of [`Outline`].
+    pub outline: Option<f32>,
+    /// Weight of [`BoxShadow`].
+    pub box_shadow: Option<f32>,
+    /// Weight of [`ImageNode`].
+    pub image: Option<f32>,
+}
+
+impl UiOpacity {
+    /// No colors are controlled by opacity.
+    pub const NONE: UiOpacity = UiOpacity {
+        background: None,
+        border: None,
+        outline: None,
+        box_shadow: None,
+        image: None,
+    };
+    /// Opacity controls [`ImageNode`] and [`BoxShadow`] color, same as [`UiOpacity::default`].
+    pub const DEFAULT: UiOpacity = UiOpacity::IMAGE.with_box_shadow(1.0);
+    /// Opacity controls background color only.
+    pub const BACKGROUND: UiOpacity = UiOpacity::NONE.with_background(1.0);
+    /// Opacity controls border color only.
+    pub const BORDER: UiOpacity = UiOpacity::NONE.with_border(1.0);
+    /// Opacity controls border and background color only.
+    pub const BOTH: UiOpacity = UiOpacity::BORDER.with_background(1.0);
+    /// Opacity controls outline color only.
+    pub const OUTLINE: UiOpacity = UiOpacity::NONE.with_outline(1.0);
+    /// Opacity controls box shadow color only.
+    pub const BOX_SHADOW: UiOpacity = UiOpacity::NONE.with_box_shadow(1.0);
+    /// Opacity controls image color only.
+    pub const IMAGE: UiOpacity = UiOpacity::NONE.with_image(1.0);
+    /// Opacity controls all colors.
+    pub const ALL: UiOpacity = UiOpacity {
+        background: Some(1.0),
+        border: Some(1.0),
+        outline: Some(1.0),
+        box_shadow: Some(1.0),
+        image: Some(1.0),
+    };
+
+    /// Set the weight of [`BackgroundColor`].
+    pub const fn with_background(mut self, weight: f32) -> Self {
+        self.background = Some(weight);
+        self
+    }
+
+    /// Set the weight of [`BorderColor`].
+    pub const fn with_border(mut self, weight: f32) -> Self {
+        self.border = Some(weight);
+        self
+    }
+
+    /// Set the weight of [`Outline`].
+    pub const fn with_outline(mut self, weight: f32) -> Self {
+        self.outline = Some(weight);
+        self
+    }
+
+    /// Set the weight of [`BoxShadow`].
+    pub const fn with_box_shadow(mut self, weight: f32) -> Self {
+        self.box_shadow = Some(weight);
+        self
+    }
+
+    /// Set the weight of [`ImageNode`].
+    pub const fn with_image(mut self, weight: f32) -> Self {
+        self.image = Some(weight);
+        self
+    }
+}
+
+/// Controls [`ImageNode`] and [`BoxShadow`].
+impl Default for UiOpacity {
+    fn default() -> Self {
+        UiOpacity::DEFAULT
+    }
+}
+
+impl BitOr for UiOpacity {
+    type Output = UiOpacity;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        UiOpacity {
+            background: self.background.or(rhs.background),
+            border: self.border.or(rhs.border),
+            outline: self.outline.or(rhs.outline),
+            box_shadow: self.box_shadow.or(rhs.box_shadow),
+            image: self.image.or(rhs.image),
+        }
+    }
+}
+
+impl BitOrAssign for UiOpacity {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs
+    }
+}
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct UiImageQuery {
+    pub ui_color: Option<&'static UiOpacity>,
+    pub image: &'static mut ImageNode,
+    pub base: Option<&'static BaseColor>,
+    pub tint: Option<&'static OpacityTint>,
+    mode: Option<&'static ResolvedMode>,
+}
 
-impl OpacityQuery for &mut ImageNode {
+impl OpacityQuery for UiImageQuery {
     type Cx = ();
 
     fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
-        this.color.set_alpha(opacity);
+        if let Some(weight) = this.ui_color.copied().unwrap_or_default().image {
+            apply_cached_mode(
+                &mut this.image.color,
+                this.base,
+                this.mode,
+                this.tint,
+                opacity * weight,
+            );
+        }
     }
 }
 
-/// Determine whether [`BorderColor`] and [`BackgroundColor`] are controlled by
-/// opacity or should stay transparent.
-/// 
-/// Items without this component are ignored.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
-pub enum UiOpacity {
-    /// Both should stay transparent
-    #[default]
-    None,
-    /// Opacity controls border color.
-    Border,
-    /// Opacity controls background color.
-    Background,
-    /// Opacity controls border and background color.
-    Both,
+impl ModeColor for ImageNode {
+    fn color_mut(&mut self) -> &mut Color {
+        &mut self.color
+    }
 }
 
 #[derive(Debug, QueryData)]
 #[query_data(mutable)]
 pub struct UiColorQuery {
     pub ui_color: &'static UiOpacity,
-    pub background: &'static mut BackgroundColor,
-    pub border: &'static mut BorderColor,
+    pub background: Option<&'static mut BackgroundColor>,
+    pub border: Option<&'static mut BorderColor>,
+    pub outline: Option<&'static mut Outline>,
 }
 
 impl OpacityQuery for UiColorQuery {
     type Cx = ();
 
     fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
-        match this.ui_color {
-            UiOpacity::None => (),
-            UiOpacity::Border => {
-                this.border.0.set_alpha(opacity);
-            }
-            UiOpacity::Background => {
-                this.background.0.set_alpha(opacity);
+        if let (Some(background), Some(weight)) = (&mut this.background, this.ui_color.background) {
+            background.0.set_alpha(opacity * weight);
+        }
+        if let (Some(border), Some(weight)) = (&mut this.border, this.ui_color.border) {
+            border.0.set_alpha(opacity * weight);
+        }
+        if let (Some(outline), Some(weight)) = (&mut this.outline, this.ui_color.outline) {
+            outline.color.set_alpha(opacity * weight);
+        }
+    }
+}
+
+/// The unfaded alpha of each shadow in a [`BoxShadow`].
+///
+/// Inserted automatically the first time opacity is applied,
+/// and refreshed if the number of shadows changes.
+#[derive(Debug, Clone, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseBoxShadowAlpha(pub Vec<f32>);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct BoxShadowQuery {
+    pub entity: Entity,
+    pub ui_color: Option<&'static UiOpacity>,
+    pub shadow: &'static mut BoxShadow,
+    pub base: Option<&'static BaseBoxShadowAlpha>,
+}
+
+impl OpacityQuery for BoxShadowQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let Some(weight) = this.ui_color.copied().unwrap_or_default().box_shadow else {
+            return;
+        };
+        let opacity = opacity * weight;
+        let base = match this.base {
+            Some(base) if base.0.len() == this.shadow.0.len() => base.0.clone(),
+            _ => {
+                let base: Vec<_> = this.shadow.0.iter().map(|x| x.color.alpha()).collect();
+                cx.entity(this.entity)
+                    .try_insert(BaseBoxShadowAlpha(base.clone()));
+                base
             }
-            UiOpacity::Both => {
-                this.border.0.set_alpha(opacity);
-                this.background.0.set_alpha(opacity);
+        };
+        for (style, alpha) in this.shadow.0.iter_mut().zip(base) {
+            style.color.set_alpha(alpha * opacity);
+        }
+    }
+}
+
+/// The unfaded alpha of a [`TextShadow`].
+///
+/// Inserted automatically the first time opacity is applied,
+/// modify this to change the alpha of the shadow.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseTextShadowAlpha(pub f32);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct TextShadowQuery {
+    pub entity: Entity,
+    pub shadow: &'static mut TextShadow,
+    pub base: Option<&'static BaseTextShadowAlpha>,
+}
+
+impl OpacityQuery for TextShadowQuery {
+    type Cx = Commands<'static, 'static>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let base = match this.base {
+            Some(base) => base.0,
+            None => {
+                let base = this.shadow.color.alpha();
+                cx.entity(this.entity).try_insert(BaseTextShadowAlpha(base));
+                base
             }
+        };
+        this.shadow.color.set_alpha(base * opacity);
+    }
+}
+
+impl<T> OpacityQuery for &MaterialNode<T>
+where
+    T: OpacityAsset + UiMaterial,
+{
+    type Cx = OpacityAssets<'static, 'static, T>;
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        if let Some(mat) = cx.get_mut(this.id(), opacity, ()) {
+            mat.apply_opacity(opacity);
         }
     }
 }
 
 pub fn opacity_plugin_ui(app: &mut App) {
-    app.register_opacity_component::<ImageNode>();
-    app.register_opacity::<UiColorQuery>();
+    app.add_plugins(ParOpacityQueryPlugin::<UiImageQuery>(PhantomData));
+    opacity_plugin_base_color::<ImageNode>(app);
+    app.add_plugins(ParOpacityQueryPlugin::<UiColorQuery>(PhantomData));
+    app.register_opacity::<BoxShadowQuery>();
+    app.register_opacity::<TextShadowQuery>();
+    crate::reapply_on_change::<UiOpacity>(app);
+    #[cfg(feature = "reflect")]
+    app.register_type::<UiOpacity>()
+        .register_type::<BaseBoxShadowAlpha>()
+        .register_type::<BaseTextShadowAlpha>();
 }
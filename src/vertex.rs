@@ -0,0 +1,83 @@
+use bevy::{
+    app::App,
+    asset::AsAssetId,
+    ecs::{query::QueryData, system::SystemParam},
+    prelude::{Commands, Component, Entity, Mesh},
+    render::mesh::VertexAttributeValues,
+};
+
+use crate::{OpacityAssets, OpacityExtension, OpacityQuery};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Opt-in [`Component`] that writes opacity to the alpha of [`Mesh::ATTRIBUTE_COLOR`]
+/// of this entity's [`Mesh3d`](bevy::prelude::Mesh3d) or [`Mesh2d`](bevy::prelude::Mesh2d).
+///
+/// Since the mesh asset is written to directly, the mesh should not be shared with other entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+pub struct VertexColorOpacity;
+
+/// The unfaded alpha of each vertex color of a mesh.
+///
+/// Inserted automatically the first time opacity is applied,
+/// and refreshed if the number of vertices changes.
+#[derive(Debug, Clone, PartialEq, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct BaseVertexAlpha(pub Vec<f32>);
+
+#[derive(Debug, QueryData)]
+pub struct VertexColorQuery<C: AsAssetId<Asset = Mesh>> {
+    pub entity: Entity,
+    pub mesh: &'static C,
+    pub marker: &'static VertexColorOpacity,
+    pub base: Option<&'static BaseVertexAlpha>,
+}
+
+impl<C: AsAssetId<Asset = Mesh>> OpacityQuery for VertexColorQuery<C> {
+    type Cx = (
+        OpacityAssets<'static, 'static, Mesh>,
+        Commands<'static, 'static>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (meshes, commands): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let Some(mesh) = meshes.get_mut(this.mesh.as_asset_id(), opacity, ()) else {
+            return;
+        };
+        let Some(VertexAttributeValues::Float32x4(colors)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR)
+        else {
+            return;
+        };
+        let base = match this.base {
+            Some(base) if base.0.len() == colors.len() => base.0.clone(),
+            _ => {
+                let base: Vec<_> = colors.iter().map(|color| color[3]).collect();
+                commands
+                    .entity(this.entity)
+                    .try_insert(BaseVertexAlpha(base.clone()));
+                base
+            }
+        };
+        for (color, alpha) in colors.iter_mut().zip(base) {
+            color[3] = alpha * opacity;
+        }
+    }
+}
+
+pub fn opacity_plugin_vertex(app: &mut App) {
+    #[cfg(feature = "2d")]
+    app.register_opacity::<VertexColorQuery<bevy::prelude::Mesh2d>>();
+    #[cfg(feature = "3d")]
+    app.register_opacity::<VertexColorQuery<bevy::prelude::Mesh3d>>();
+    #[cfg(feature = "reflect")]
+    app.register_type::<VertexColorOpacity>()
+        .register_type::<BaseVertexAlpha>();
+}
@@ -0,0 +1,134 @@
+use bevy::{
+    app::App,
+    ecs::{
+        schedule::{common_conditions::resource_changed, IntoScheduleConfigs},
+        system::SystemParam,
+    },
+    prelude::{
+        Changed, Commands, Component, DetectChanges, DetectChangesMut, Entity, Query, Res,
+        Visibility, Without,
+    },
+};
+
+use crate::{OpacityConfig, OpacityIgnore, OpacityMap, OpacitySet};
+#[cfg(feature = "reflect")]
+use bevy::{
+    prelude::ReflectComponent,
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Opt-in [`Component`] that sets [`Visibility::Hidden`] on this entity
+/// when its computed opacity reaches `0.0`,
+/// and restores the previous [`Visibility`] when opacity rises above `0.0`.
+///
+/// A [`Visibility`] set while hidden is restored instead, and is kept as is
+/// if set in the same frame opacity rises above `0.0`.
+///
+/// Fully transparent entities are otherwise still extracted and drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Default))]
+#[require(Visibility)]
+pub struct OpacityVisibility {
+    previous: Option<Visibility>,
+}
+
+impl OpacityVisibility {
+    /// Returns true if this entity is currently hidden by opacity.
+    pub const fn is_hidden(&self) -> bool {
+        self.previous.is_some()
+    }
+}
+
+/// Entities excluded from propagation by [`OpacityConfig::skip_hidden`].
+#[derive(SystemParam)]
+pub(crate) struct HiddenEntities<'w, 's> {
+    config: Res<'w, OpacityConfig>,
+    query: Query<'w, 's, (&'static Visibility, Option<&'static OpacityVisibility>)>,
+    changed: Query<'w, 's, Entity, Changed<Visibility>>,
+}
+
+impl HiddenEntities<'_, '_> {
+    /// Returns true if the config changed, which may include [`OpacityConfig::skip_hidden`].
+    pub(crate) fn is_changed(&self) -> bool {
+        self.config.is_changed()
+    }
+
+    /// Returns true if this entity and its children are skipped during propagation.
+    ///
+    /// Entities hidden by [`OpacityVisibility`] are not skipped, or they would never be shown again.
+    pub(crate) fn contains(&self, entity: Entity) -> bool {
+        self.config.skip_hidden
+            && self.query.get(entity).is_ok_and(|(visibility, state)| {
+                *visibility == Visibility::Hidden
+                    && !state.is_some_and(OpacityVisibility::is_hidden)
+            })
+    }
+
+    /// Entities whose [`Visibility`] changed, if hidden entities are skipped.
+    pub(crate) fn changed(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.changed.iter().filter(|_| self.config.skip_hidden)
+    }
+}
+
+fn toggle_visibility(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    config: Res<OpacityConfig>,
+    mut query: Query<(Entity, &mut Visibility, &mut OpacityVisibility)>,
+    mut others: Query<&mut Visibility, (Without<OpacityVisibility>, Without<OpacityIgnore>)>,
+) {
+    for (entity, mut visibility, mut state) in &mut query {
+        let invisible = map.get(entity).is_some_and(|opacity| opacity <= 0.0);
+        match state.previous {
+            None if invisible => {
+                state.previous = Some(*visibility);
+                *visibility = Visibility::Hidden;
+            }
+            // Writes of this system are not seen as changes the next time it runs,
+            // so this visibility was set elsewhere while hidden.
+            Some(_) if visibility.is_changed() => {
+                if invisible {
+                    // Restore the new visibility when shown,
+                    // the change is still seen by visibility propagation.
+                    state.previous = Some(*visibility);
+                    *visibility.bypass_change_detection() = Visibility::Hidden;
+                } else {
+                    state.previous = None;
+                }
+            }
+            Some(previous) if !invisible => {
+                // Only replace the `Hidden` written by this system.
+                if *visibility == Visibility::Hidden {
+                    *visibility = previous;
+                }
+                state.previous = None;
+            }
+            _ => (),
+        }
+    }
+    if !config.auto_visibility {
+        return;
+    }
+    for (entity, opacity) in map.iter() {
+        if opacity > 0.0 {
+            continue;
+        }
+        if let Ok(mut visibility) = others.get_mut(entity) {
+            commands.entity(entity).try_insert(OpacityVisibility {
+                previous: Some(*visibility),
+            });
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+pub fn opacity_plugin_visibility(app: &mut App) {
+    #[cfg(feature = "reflect")]
+    app.register_type::<OpacityVisibility>();
+    app.add_systems(
+        crate::opacity_schedule(app),
+        toggle_visibility
+            .run_if(resource_changed::<OpacityMap>)
+            .in_set(OpacitySet::Apply),
+    );
+}
@@ -0,0 +1,92 @@
+use bevy::{
+    app::App,
+    asset::{Assets, Handle},
+    ecs::{component::Mutable, query::QueryData, system::SystemParam},
+    prelude::{Commands, Component, Entity},
+};
+
+use crate::{register_opacity_requiring, OpacityAsset, OpacityAssets, OpacityQuery};
+
+/// A component holding the wireframe material of a mesh.
+pub trait WireframeHandle: Component<Mutability = Mutable> {
+    type Material: OpacityAsset + Clone;
+
+    fn handle_mut(&mut self) -> &mut Handle<Self::Material>;
+}
+
+#[cfg(feature = "3d")]
+impl WireframeHandle for bevy::pbr::wireframe::Mesh3dWireframe {
+    type Material = bevy::pbr::wireframe::WireframeMaterial;
+
+    fn handle_mut(&mut self) -> &mut Handle<Self::Material> {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "2d")]
+impl WireframeHandle for bevy::sprite::Mesh2dWireframe {
+    type Material = bevy::sprite::Wireframe2dMaterial;
+
+    fn handle_mut(&mut self) -> &mut Handle<Self::Material> {
+        &mut self.0
+    }
+}
+
+/// The wireframe material owned by this entity.
+///
+/// Wireframes without a color share a global material,
+/// so a copy is made before it is modified by opacity.
+#[derive(Debug, Component)]
+pub struct WireframeOpacity<M: OpacityAsset>(Handle<M>);
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct WireframeQuery<T: WireframeHandle> {
+    pub entity: Entity,
+    pub wireframe: &'static mut T,
+    pub state: Option<&'static WireframeOpacity<T::Material>>,
+}
+
+impl<T: WireframeHandle> OpacityQuery for WireframeQuery<T> {
+    type Cx = (
+        Commands<'static, 'static>,
+        OpacityAssets<'static, 'static, T::Material>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (commands, assets): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let handle = this.wireframe.handle_mut();
+        // Bevy replaces the handle when `WireframeColor` changes.
+        if this.state.is_some_and(|state| &state.0 == handle) {
+            if let Some(material) = assets.get_mut(handle.id(), opacity, ()) {
+                material.apply_opacity(opacity);
+            }
+            return;
+        }
+        let Some(mut material) = assets.get(handle.id()).cloned() else {
+            return;
+        };
+        material.apply_opacity(opacity);
+        *handle = assets.add(material);
+        commands
+            .entity(this.entity)
+            .try_insert(WireframeOpacity(handle.clone()));
+    }
+}
+
+/// Wireframes are only faded if `WireframePlugin` or `Wireframe2dPlugin` is added.
+pub fn opacity_plugin_wireframe(app: &mut App) {
+    #[cfg(feature = "3d")]
+    register_opacity_requiring::<
+        WireframeQuery<bevy::pbr::wireframe::Mesh3dWireframe>,
+        Assets<bevy::pbr::wireframe::WireframeMaterial>,
+    >(app);
+    #[cfg(feature = "2d")]
+    register_opacity_requiring::<
+        WireframeQuery<bevy::sprite::Mesh2dWireframe>,
+        Assets<bevy::sprite::Wireframe2dMaterial>,
+    >(app);
+}
@@ -0,0 +1,61 @@
+//! Logs are captured by a global subscriber, so this runs in its own test binary.
+#![cfg(feature = "debug")]
+use bevy::log::tracing_subscriber;
+use bevy::{asset::AssetPlugin, pbr::StandardMaterial, prelude::*, sprite::ColorMaterial};
+use bevy_mod_opacity::{Opacity, OpacityPlugin};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn warn_unsupported_targets() {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    tracing_subscriber::fmt()
+        .with_max_level(bevy::log::Level::WARN)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .init();
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::default(),
+    ))
+    .init_asset::<StandardMaterial>()
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>();
+    let empty = app.world_mut().spawn(Opacity::new(0.5)).id();
+    let parent = app.world_mut().spawn(Opacity::new(0.5)).id();
+    app.world_mut().spawn((Sprite::default(), ChildOf(parent)));
+    let reported = |entity: Entity| {
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        logs.matches(&format!(
+            "Entity {entity} has `Opacity` but no registered type"
+        ))
+        .count()
+    };
+    // Entities get a grace period before they are reported.
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(reported(empty), 0);
+    for _ in 0..200 {
+        app.update();
+    }
+    assert_eq!(reported(empty), 1);
+    assert_eq!(reported(parent), 0);
+}
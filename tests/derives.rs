@@ -1,13 +1,14 @@
 use bevy::{
     app::App,
-    asset::Asset,
-    color::Srgba,
-    pbr::{ExtendedMaterial, Material, MaterialExtension, StandardMaterial},
+    asset::{AsAssetId, Asset, AssetId, Handle},
+    color::{LinearRgba, Srgba},
+    pbr::{ExtendedMaterial, Material, MaterialExtension, MeshMaterial3d, StandardMaterial},
     prelude::Component,
     reflect::TypePath,
     render::render_resource::AsBindGroup,
+    ui::UiMaterial,
 };
-use bevy_mod_opacity::{Opacity, OpacityExtension, OpacityPlugin};
+use bevy_mod_opacity::{Opacity, OpacityAsset, OpacityExtension, OpacityPlugin};
 
 #[derive(Debug, Component, Opacity)]
 pub struct MyColor {
@@ -27,6 +28,15 @@ pub struct MyColorMaterial {
 
 impl Material for MyColorMaterial {}
 
+#[derive(Debug, Clone, TypePath, Asset, OpacityAsset, AsBindGroup)]
+pub struct MyShaderMaterial {
+    #[uniform(0)]
+    #[opacity]
+    pub color: LinearRgba,
+}
+
+impl Material for MyShaderMaterial {}
+
 #[derive(Debug, Clone, AsBindGroup, TypePath, Asset, Opacity)]
 #[opacity(extends = StandardMaterial)]
 pub struct MyColorMaterialExt {
@@ -45,13 +55,113 @@ pub struct MyColorMaterialExtMask {
 
 impl MaterialExtension for MyColorMaterialExtMask {}
 
+#[derive(Debug, Clone, TypePath, Asset, Opacity, AsBindGroup)]
+#[opacity(asset)]
+pub struct MyUiMaterial {
+    #[opacity]
+    pub color: Srgba,
+}
+
+impl UiMaterial for MyUiMaterial {}
+
+#[derive(Debug, Component)]
+pub struct MyMaterialHandle(pub Handle<MyColorMaterial>);
+
+impl AsAssetId for MyMaterialHandle {
+    type Asset = MyColorMaterial;
+
+    fn as_asset_id(&self) -> AssetId<Self::Asset> {
+        self.0.id()
+    }
+}
+
+#[derive(Debug, Component, Opacity)]
+pub struct MyMaterials {
+    #[opacity]
+    pub a: f32,
+    #[opacity]
+    pub primary: Handle<MyColorMaterial>,
+    #[opacity]
+    pub secondary: MeshMaterial3d<MyColorMaterial>,
+    #[opacity]
+    pub standard: MeshMaterial3d<StandardMaterial>,
+}
+
+#[derive(Debug, Component, Opacity)]
+pub struct MyShaderMaterialHandle {
+    #[opacity]
+    pub material: Handle<MyShaderMaterial>,
+}
+
 #[test]
 fn test() {
     let _app = App::new()
-        .add_plugins(OpacityPlugin)
+        .add_plugins(OpacityPlugin::default())
         .register_opacity_component::<MyColor>()
         .register_opacity_material3d::<MyColorMaterial>()
+        .register_opacity::<&mut MyMaterials>()
+        .register_opacity_component::<MyShaderMaterialHandle>()
+        .register_opacity_parallel::<&mut MyColor>()
+        .register_opacity_material3d::<MyShaderMaterial>()
+        .register_opacity_ui_material::<MyUiMaterial>()
+        .register_opacity_asset::<MyMaterialHandle, MyColorMaterial>()
         .register_opacity_material3d::<ExtendedMaterial<StandardMaterial, MyColorMaterialExt>>()
         .register_opacity_material3d::<ExtendedMaterial<StandardMaterial, MyColorMaterialExtMask>>(
         );
 }
+
+#[test]
+fn handle_fields() {
+    use bevy::{asset::AssetPlugin, prelude::*};
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::default().without_builtin(),
+    ))
+    .init_asset::<MyShaderMaterial>()
+    .register_opacity_component::<MyShaderMaterialHandle>()
+    .register_opacity_component::<MyColor>();
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<MyShaderMaterial>>()
+        .add(MyShaderMaterial {
+            color: LinearRgba::WHITE,
+        });
+    app.world_mut().spawn((
+        Opacity::new(0.5),
+        MyShaderMaterialHandle {
+            material: material.clone(),
+        },
+    ));
+    let other = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            MyColor {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+        ))
+        .id();
+    app.update();
+    let alpha = |app: &App| {
+        let materials = app.world().resource::<Assets<MyShaderMaterial>>();
+        materials.get(&material).unwrap().color.alpha
+    };
+    assert_eq!(alpha(&app), 0.5);
+    // The asset is not written to again while its opacity is unchanged.
+    app.world_mut()
+        .resource_mut::<Assets<MyShaderMaterial>>()
+        .get_mut(&material)
+        .unwrap()
+        .color
+        .alpha = 1.0;
+    app.world_mut().get_mut::<Opacity>(other).unwrap().set(0.25);
+    app.update();
+    assert_eq!(alpha(&app), 1.0);
+    assert_eq!(app.world().get::<MyColor>(other).unwrap().a, 0.25);
+}
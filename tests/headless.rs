@@ -0,0 +1,29 @@
+//! Runs without rendering, like a headless server:
+//! `cargo test --no-default-features --test headless`.
+#![cfg(not(feature = "render"))]
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_mod_opacity::{Opacity, OpacityMap, OpacityPlugin};
+use std::time::Duration;
+
+#[test]
+fn fade_out_and_despawn() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, OpacityPlugin::default()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+    let mut opacity = Opacity::OPAQUE;
+    opacity.fade_out(0.4);
+    let root = app.world_mut().spawn(opacity).id();
+    let child = app.world_mut().spawn(ChildOf(root)).id();
+    app.update();
+    app.update();
+    let computed = app.world().resource::<OpacityMap>().get(child);
+    assert_eq!(computed, Some(0.75));
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app.world().get_entity(root).is_err());
+    assert!(app.world().get_entity(child).is_err());
+}
@@ -0,0 +1,4147 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::render::render_resource::AsBindGroup;
+use bevy::{asset::AssetPlugin, pbr::StandardMaterial, prelude::*, sprite::ColorMaterial};
+use bevy_mod_opacity::{
+    Opacity, OpacityExtension, OpacityMap, OpacityPlugin, OpacityReader, OpacityVisibility,
+    UiOpacity,
+};
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::default(),
+    ))
+    .init_asset::<StandardMaterial>()
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>();
+    app
+}
+
+fn alpha(app: &App, entity: Entity) -> f32 {
+    app.world().get::<Sprite>(entity).unwrap().color.alpha()
+}
+
+#[test]
+fn nested_roots() {
+    let mut app = app();
+    let mut leaves = Vec::new();
+    let mut roots = Vec::new();
+    for _ in 0..64 {
+        let root = app
+            .world_mut()
+            .spawn((Opacity::new(0.5), Sprite::default()))
+            .id();
+        let child = app
+            .world_mut()
+            .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(root)))
+            .id();
+        let leaf = app
+            .world_mut()
+            .spawn((Sprite::default(), ChildOf(child)))
+            .id();
+        roots.push(root);
+        leaves.push(leaf);
+    }
+    let outside = app.world_mut().spawn(Sprite::default()).id();
+    app.update();
+    for root in roots {
+        assert_eq!(alpha(&app, root), 0.5);
+    }
+    for leaf in leaves {
+        assert_eq!(alpha(&app, leaf), 0.25);
+    }
+    assert_eq!(alpha(&app, outside), 1.0);
+}
+
+#[test]
+fn updates_after_idle() {
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(root)))
+        .id();
+    app.update();
+    app.update();
+    assert_eq!(alpha(&app, child), 0.5);
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.25);
+    app.update();
+    assert_eq!(alpha(&app, child), 0.25);
+    app.world_mut().entity_mut(child).remove::<ChildOf>();
+    app.world_mut()
+        .get_mut::<Sprite>(child)
+        .unwrap()
+        .color
+        .set_alpha(1.0);
+    app.update();
+    assert_eq!(alpha(&app, child), 1.0);
+}
+
+#[test]
+fn reader() {
+    let mut app = app();
+    let root = app.world_mut().spawn(Opacity::new(0.5)).id();
+    let child = app.world_mut().spawn(ChildOf(root)).id();
+    let outside = app.world_mut().spawn_empty().id();
+    app.update();
+    let (root, child, outside, len) = app
+        .world_mut()
+        .run_system_once(move |reader: OpacityReader| {
+            (
+                reader.get(root),
+                reader.get(child),
+                reader.get(outside),
+                reader.len(),
+            )
+        })
+        .unwrap();
+    assert_eq!(root, Some(0.5));
+    assert_eq!(child, Some(0.5));
+    assert_eq!(outside, None);
+    assert_eq!(len, 2);
+}
+
+#[test]
+fn auto_visibility() {
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((
+            Opacity::new(1.0),
+            Visibility::Inherited,
+            OpacityVisibility::default(),
+        ))
+        .id();
+    app.update();
+    assert_eq!(
+        app.world().get::<Visibility>(root),
+        Some(&Visibility::Inherited)
+    );
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.0);
+    app.update();
+    assert_eq!(
+        app.world().get::<Visibility>(root),
+        Some(&Visibility::Hidden)
+    );
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.5);
+    app.update();
+    assert_eq!(
+        app.world().get::<Visibility>(root),
+        Some(&Visibility::Inherited)
+    );
+}
+
+#[test]
+fn opacity_visibility_override() {
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.0), OpacityVisibility::default()))
+        .id();
+    let visibility = |app: &App| *app.world().get::<Visibility>(root).unwrap();
+    app.update();
+    assert_eq!(visibility(&app), Visibility::Hidden);
+    // Set while hidden, restored when shown.
+    *app.world_mut().get_mut::<Visibility>(root).unwrap() = Visibility::Visible;
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.0);
+    app.update();
+    assert_eq!(visibility(&app), Visibility::Hidden);
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.5);
+    app.update();
+    assert_eq!(visibility(&app), Visibility::Visible);
+    // Set in the same frame as shown, kept.
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.0);
+    app.update();
+    assert_eq!(visibility(&app), Visibility::Hidden);
+    *app.world_mut().get_mut::<Visibility>(root).unwrap() = Visibility::Hidden;
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(1.0);
+    app.update();
+    assert_eq!(visibility(&app), Visibility::Hidden);
+    assert!(!app
+        .world()
+        .get::<OpacityVisibility>(root)
+        .unwrap()
+        .is_hidden());
+}
+
+#[test]
+fn light_intensity() {
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            PointLight {
+                intensity: 1000.0,
+                ..Default::default()
+            },
+        ))
+        .id();
+    app.update();
+    assert_eq!(
+        app.world().get::<PointLight>(root).unwrap().intensity,
+        500.0
+    );
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.25);
+    app.update();
+    assert_eq!(
+        app.world().get::<PointLight>(root).unwrap().intensity,
+        250.0
+    );
+}
+
+#[test]
+fn distance_fog_alpha() {
+    let mut app = app();
+    let camera = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            DistanceFog {
+                color: Color::srgba(1.0, 1.0, 1.0, 0.5),
+                ..Default::default()
+            },
+        ))
+        .id();
+    let fog_alpha = |app: &App| {
+        app.world()
+            .get::<DistanceFog>(camera)
+            .unwrap()
+            .color
+            .alpha()
+    };
+    app.update();
+    assert_eq!(fog_alpha(&app), 0.25);
+    app.world_mut().get_mut::<Opacity>(camera).unwrap().set(1.0);
+    app.update();
+    assert_eq!(fog_alpha(&app), 0.5);
+}
+
+#[test]
+fn ui_weights() {
+    let mut app = app();
+    let node = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            UiOpacity::BORDER.with_background(0.5) | UiOpacity::DEFAULT,
+            BackgroundColor(Color::WHITE),
+            BorderColor(Color::WHITE),
+            Outline::default(),
+            ImageNode::default(),
+        ))
+        .id();
+    app.update();
+    let world = app.world();
+    assert_eq!(world.get::<BorderColor>(node).unwrap().0.alpha(), 0.5);
+    assert_eq!(world.get::<BackgroundColor>(node).unwrap().0.alpha(), 0.25);
+    assert_eq!(world.get::<ImageNode>(node).unwrap().color.alpha(), 0.5);
+    assert_eq!(
+        world.get::<Outline>(node).unwrap().color,
+        Outline::default().color
+    );
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct ThirdPartyInner {
+    pub color: LinearRgba,
+}
+
+#[derive(Debug, Clone, Asset, Reflect, AsBindGroup)]
+pub struct ThirdPartyMaterial {
+    pub inner: ThirdPartyInner,
+}
+
+impl Material for ThirdPartyMaterial {}
+
+#[test]
+fn ui_opacity_constants() {
+    // Single target constants control only their target.
+    for (constant, target) in [
+        (UiOpacity::BACKGROUND, UiOpacity::NONE.with_background(1.0)),
+        (UiOpacity::BORDER, UiOpacity::NONE.with_border(1.0)),
+        (UiOpacity::OUTLINE, UiOpacity::NONE.with_outline(1.0)),
+        (UiOpacity::BOX_SHADOW, UiOpacity::NONE.with_box_shadow(1.0)),
+        (UiOpacity::IMAGE, UiOpacity::NONE.with_image(1.0)),
+    ] {
+        assert_eq!(constant, target);
+    }
+    assert_eq!(UiOpacity::BOTH, UiOpacity::BORDER | UiOpacity::BACKGROUND);
+    assert_eq!(UiOpacity::DEFAULT, UiOpacity::IMAGE | UiOpacity::BOX_SHADOW);
+}
+
+#[test]
+fn box_shadow_alpha() {
+    use bevy::ui::ShadowStyle;
+
+    let mut app = app();
+    let shadow = |alpha| ShadowStyle {
+        color: Color::BLACK.with_alpha(alpha),
+        ..Default::default()
+    };
+    let node = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), BoxShadow(vec![shadow(0.5)])))
+        .id();
+    let alphas = |app: &App| -> Vec<f32> {
+        app.world()
+            .get::<BoxShadow>(node)
+            .unwrap()
+            .0
+            .iter()
+            .map(|x| x.color.alpha())
+            .collect()
+    };
+    app.update();
+    assert_eq!(alphas(&app), [0.25]);
+    app.world_mut().get_mut::<Opacity>(node).unwrap().set(1.0);
+    app.update();
+    assert_eq!(alphas(&app), [0.5]);
+    // Adding a shadow refreshes the base alphas.
+    app.world_mut()
+        .get_mut::<BoxShadow>(node)
+        .unwrap()
+        .0
+        .push(shadow(1.0));
+    app.world_mut().get_mut::<Opacity>(node).unwrap().set(0.5);
+    app.update();
+    assert_eq!(alphas(&app), [0.25, 0.5]);
+}
+
+#[test]
+fn outline_alpha() {
+    let mut app = app();
+    let node = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            UiOpacity::OUTLINE,
+            Outline {
+                color: Color::WHITE,
+                ..Default::default()
+            },
+        ))
+        .id();
+    let outline_alpha = |app: &App| app.world().get::<Outline>(node).unwrap().color.alpha();
+    app.update();
+    assert_eq!(outline_alpha(&app), 0.5);
+    app.world_mut()
+        .entity_mut(node)
+        .insert(UiOpacity::NONE.with_outline(0.5));
+    app.update();
+    assert_eq!(outline_alpha(&app), 0.25);
+    app.world_mut().get_mut::<Opacity>(node).unwrap().set(1.0);
+    app.update();
+    assert_eq!(outline_alpha(&app), 0.5);
+}
+
+#[test]
+fn text_shadow_alpha() {
+    use bevy::ui::TextShadow;
+
+    let mut app = app();
+    let text = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            Text::new("faded"),
+            TextShadow {
+                color: Color::BLACK.with_alpha(0.8),
+                ..Default::default()
+            },
+        ))
+        .id();
+    let shadow_alpha = |app: &App| app.world().get::<TextShadow>(text).unwrap().color.alpha();
+    app.update();
+    assert_eq!(shadow_alpha(&app), 0.4);
+    app.world_mut().get_mut::<Opacity>(text).unwrap().set(0.25);
+    app.update();
+    assert_eq!(shadow_alpha(&app), 0.2);
+    app.world_mut().get_mut::<Opacity>(text).unwrap().set(1.0);
+    app.update();
+    assert_eq!(shadow_alpha(&app), 0.8);
+}
+
+#[test]
+fn text_span_weights() {
+    use bevy_mod_opacity::TextOpacity;
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Text::new("root"), TextColor::WHITE))
+        .id();
+    let mut span = |text_opacity: Option<TextOpacity>| {
+        let mut span = app.world_mut().spawn((
+            TextSpan::new("span"),
+            TextColor(Color::WHITE.with_alpha(0.7)),
+            ChildOf(root),
+        ));
+        if let Some(text_opacity) = text_opacity {
+            span.insert(text_opacity);
+        }
+        span.id()
+    };
+    let plain = span(None);
+    let hint = span(Some(TextOpacity::Weight(0.5)));
+    let independent = span(Some(TextOpacity::Independent));
+    let text_alpha = |app: &App, entity| app.world().get::<TextColor>(entity).unwrap().alpha();
+    app.update();
+    assert_eq!(text_alpha(&app, root), 0.5);
+    assert_eq!(text_alpha(&app, plain), 0.5);
+    assert_eq!(text_alpha(&app, hint), 0.25);
+    assert_eq!(text_alpha(&app, independent), 0.7);
+    // Changing the weight applies without a change of opacity.
+    app.world_mut()
+        .entity_mut(hint)
+        .insert(TextOpacity::Weight(0.2));
+    app.update();
+    assert_eq!(text_alpha(&app, hint), 0.1);
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(1.0);
+    app.update();
+    assert_eq!(text_alpha(&app, hint), 0.2);
+    assert_eq!(text_alpha(&app, independent), 0.7);
+}
+
+#[derive(Component)]
+struct InstancedMaterial(Handle<ColorMaterial>);
+
+impl bevy::asset::AsAssetId for InstancedMaterial {
+    type Asset = ColorMaterial;
+
+    fn as_asset_id(&self) -> AssetId<ColorMaterial> {
+        self.0.id()
+    }
+}
+
+#[test]
+fn asset_handle_component() {
+    let mut app = app();
+    app.register_opacity_asset::<InstancedMaterial, ColorMaterial>();
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .add(ColorMaterial::from_color(Color::WHITE));
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), InstancedMaterial(material.clone())))
+        .id();
+    let material_alpha = |app: &App| {
+        app.world()
+            .resource::<Assets<ColorMaterial>>()
+            .get(&material)
+            .unwrap()
+            .color
+            .alpha()
+    };
+    app.update();
+    assert_eq!(material_alpha(&app), 0.5);
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set(0.25);
+    app.update();
+    assert_eq!(material_alpha(&app), 0.25);
+}
+
+#[test]
+fn reflect_field() {
+    let mut app = app();
+    app.init_asset::<ThirdPartyMaterial>()
+        .register_opacity_asset_field::<MeshMaterial3d<ThirdPartyMaterial>>("inner.color");
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<ThirdPartyMaterial>>()
+        .add(ThirdPartyMaterial {
+            inner: ThirdPartyInner {
+                color: LinearRgba::WHITE,
+            },
+        });
+    app.world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial3d(handle.clone())));
+    app.update();
+    let assets = app.world().resource::<Assets<ThirdPartyMaterial>>();
+    assert_eq!(assets.get(&handle).unwrap().inner.color.alpha, 0.5);
+}
+
+#[cfg(feature = "scene")]
+#[test]
+fn scene_round_trip() {
+    use bevy::ecs::entity::EntityHashMap;
+    use bevy::scene::{DynamicScene, DynamicSceneBuilder};
+
+    let mut source = app();
+    let mut opacity = Opacity::new(1.0);
+    opacity.interpolate_to(0.0, 10.0);
+    let root = source
+        .world_mut()
+        .spawn((opacity, UiOpacity::BORDER, Sprite::default()))
+        .id();
+    let scene: DynamicScene = DynamicSceneBuilder::from_world(source.world())
+        .allow_component::<Opacity>()
+        .allow_component::<UiOpacity>()
+        .extract_entity(root)
+        .build();
+
+    let mut app = app();
+    let mut entity_map = EntityHashMap::default();
+    scene
+        .write_to_world(app.world_mut(), &mut entity_map)
+        .unwrap();
+    let entity = entity_map[&root];
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&opacity));
+    app.world_mut().entity_mut(entity).insert(Sprite::default());
+    app.update();
+
+    let loaded = app.world().get::<Opacity>(entity).unwrap();
+    assert_eq!(loaded.get_target(), 0.0);
+    assert_eq!(
+        app.world().get::<UiOpacity>(entity),
+        Some(&UiOpacity::BORDER)
+    );
+    assert_eq!(alpha(&app, entity), loaded.get());
+}
+
+#[cfg(all(feature = "scene", feature = "3d"))]
+#[test]
+fn scene_unique_materials() {
+    use bevy::scene::{Scene, ScenePlugin};
+
+    let mut app = app();
+    app.add_plugins(ScenePlugin)
+        .register_type::<MeshMaterial3d<StandardMaterial>>();
+    let shared = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    let mut world = World::new();
+    world.spawn(MeshMaterial3d(shared.clone()));
+    let scene = app
+        .world_mut()
+        .resource_mut::<Assets<Scene>>()
+        .add(Scene::new(world));
+
+    let faded = app
+        .world_mut()
+        .spawn((SceneRoot(scene.clone()), Opacity::new(0.5)))
+        .id();
+    let plain = app.world_mut().spawn(SceneRoot(scene)).id();
+    app.update();
+    app.update();
+
+    let material = |root: Entity| {
+        let child = app.world().get::<Children>(root).unwrap()[0];
+        app.world()
+            .get::<MeshMaterial3d<StandardMaterial>>(child)
+            .unwrap()
+            .0
+            .clone()
+    };
+    assert_ne!(material(faded), shared);
+    assert_eq!(material(plain), shared);
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    assert_eq!(materials.get(&shared).unwrap().base_color.alpha(), 1.0);
+    assert_eq!(
+        materials.get(&material(faded)).unwrap().base_color.alpha(),
+        0.5
+    );
+}
+
+#[cfg(all(feature = "scene", feature = "3d"))]
+#[test]
+fn late_scene() {
+    use bevy::scene::{scene_spawner_system, DynamicScene, Scene, SceneSpawner};
+    use bevy_mod_opacity::OpacitySet;
+
+    let mut app = app();
+    app.init_asset::<Scene>()
+        .init_asset::<DynamicScene>()
+        .init_resource::<SceneSpawner>()
+        .register_type::<MeshMaterial3d<StandardMaterial>>()
+        .add_systems(PostUpdate, scene_spawner_system.after(OpacitySet::Apply));
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    let mut world = World::new();
+    world.spawn(MeshMaterial3d(material));
+    let scene = app
+        .world_mut()
+        .resource_mut::<Assets<Scene>>()
+        .add(Scene::new(world));
+    let parent = app.world_mut().spawn(Opacity::new(0.5)).id();
+    app.world_mut()
+        .resource_mut::<SceneSpawner>()
+        .spawn_as_child(scene, parent);
+    app.update();
+
+    let child = app.world().get::<Children>(parent).unwrap()[0];
+    let material = app
+        .world()
+        .get::<MeshMaterial3d<StandardMaterial>>(child)
+        .unwrap();
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    assert_eq!(materials.get(material).unwrap().base_color.alpha(), 0.5);
+}
+
+#[cfg(feature = "state")]
+#[test]
+fn fade_out_on_exit() {
+    use bevy::state::app::StatesPlugin;
+    use bevy_mod_opacity::FadeOutOnExit;
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, States)]
+    enum Screen {
+        #[default]
+        Menu,
+        Game,
+    }
+
+    let mut app = app();
+    app.add_plugins(StatesPlugin)
+        .init_state::<Screen>()
+        .register_fade_out_on_exit::<Screen>();
+    let menu = app
+        .world_mut()
+        .spawn(FadeOutOnExit(Screen::Menu, Duration::from_secs(1)))
+        .id();
+    app.update();
+    assert!(app.world().get::<Opacity>(menu).is_none());
+
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Game);
+    app.update();
+    let opacity = app.world().get::<Opacity>(menu).unwrap();
+    assert!(opacity.is_despawning());
+    assert_eq!(opacity.get_target(), 0.0);
+}
+
+#[test]
+fn screen_fade() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{ScreenFade, ScreenFadeFinished};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.world_mut()
+        .commands()
+        .queue(ScreenFade::out(Color::BLACK, 0.25));
+    let finished = |app: &mut App| {
+        app.world_mut()
+            .resource_mut::<Events<ScreenFadeFinished>>()
+            .drain()
+            .collect::<Vec<_>>()
+    };
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(finished(&mut app).is_empty());
+    app.update();
+    assert_eq!(
+        finished(&mut app),
+        vec![ScreenFadeFinished {
+            window: None,
+            camera: None,
+            covered: true,
+        }]
+    );
+    let mut overlays = app.world_mut().query::<(&BackgroundColor, &Opacity)>();
+    let (color, _) = overlays.single(app.world()).unwrap();
+    assert_eq!(color.0.alpha(), 1.0);
+
+    app.world_mut().commands().queue(ScreenFade::reveal(0.25));
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(
+        finished(&mut app),
+        vec![ScreenFadeFinished {
+            window: None,
+            camera: None,
+            covered: false,
+        }]
+    );
+    app.update();
+    assert!(overlays.iter(app.world()).next().is_none());
+}
+
+#[test]
+fn camera_screen_fade() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy::ui::UiTargetCamera;
+    use bevy_mod_opacity::{ScreenFade, ScreenFadeFinished};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let left = app.world_mut().spawn(Camera::default()).id();
+    let right = app.world_mut().spawn(Camera::default()).id();
+    app.world_mut()
+        .commands()
+        .queue(ScreenFade::out(Color::BLACK, 0.1).for_camera(left));
+    app.world_mut()
+        .commands()
+        .queue(ScreenFade::out(Color::WHITE, 0.3).for_camera(right));
+    app.update();
+    app.update();
+    let finished = app
+        .world_mut()
+        .resource_mut::<Events<ScreenFadeFinished>>()
+        .drain()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        finished,
+        vec![ScreenFadeFinished {
+            window: None,
+            camera: Some(left),
+            covered: true,
+        }]
+    );
+    let mut opacity = |camera| {
+        app.world_mut()
+            .query::<(&UiTargetCamera, &Opacity)>()
+            .iter(app.world())
+            .find(|(target, _)| target.0 == camera)
+            .map(|(_, opacity)| opacity.get())
+    };
+    assert_eq!(opacity(left), Some(1.0));
+    assert!(opacity(right).unwrap() < 1.0);
+}
+
+#[test]
+fn crossfade() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{Crossfade, CrossfadeFinished};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let menu = app
+        .world_mut()
+        .spawn((Node::default(), Opacity::OPAQUE))
+        .id();
+    let settings = app
+        .world_mut()
+        .spawn((Node::default(), Visibility::Hidden))
+        .id();
+    let game = app.world_mut().spawn(Node::default()).id();
+    app.world_mut()
+        .commands()
+        .queue(Crossfade::new(menu, settings, 0.25).hide());
+    app.update();
+    assert_eq!(
+        app.world().get::<Visibility>(settings),
+        Some(&Visibility::Inherited)
+    );
+    for _ in 0..3 {
+        app.update();
+    }
+    let events: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Events<CrossfadeFinished>>()
+        .drain()
+        .collect();
+    assert_eq!(
+        events,
+        vec![CrossfadeFinished {
+            from: menu,
+            to: settings
+        }]
+    );
+    assert_eq!(
+        app.world().get::<Visibility>(menu),
+        Some(&Visibility::Hidden)
+    );
+
+    app.world_mut()
+        .commands()
+        .queue(Crossfade::new(settings, game, 0.25));
+    for _ in 0..4 {
+        app.update();
+    }
+    assert!(app.world().get_entity(settings).is_err());
+    assert_eq!(app.world().get::<Opacity>(game).unwrap().get(), 1.0);
+}
+
+#[test]
+fn global_opacity() {
+    use bevy_mod_opacity::{GlobalOpacity, IgnoreGlobalOpacity};
+
+    let mut app = app();
+    let world_root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let menu = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default(), IgnoreGlobalOpacity))
+        .id();
+    let menu_item = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(menu)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, world_root), 0.5);
+
+    app.insert_resource(GlobalOpacity(0.5));
+    app.update();
+    assert_eq!(alpha(&app, world_root), 0.25);
+    assert_eq!(alpha(&app, menu), 1.0);
+    assert_eq!(alpha(&app, menu_item), 1.0);
+
+    app.world_mut()
+        .entity_mut(menu)
+        .remove::<IgnoreGlobalOpacity>();
+    app.update();
+    assert_eq!(alpha(&app, menu_item), 0.5);
+}
+
+#[test]
+fn opacity_groups() {
+    use bevy_mod_opacity::{OpacityGroup, OpacityGroups};
+
+    let mut app = app();
+    let health = app
+        .world_mut()
+        .spawn((OpacityGroup::new("hud"), Sprite::default()))
+        .id();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let minimap = app
+        .world_mut()
+        .spawn((OpacityGroup::new("hud"), Sprite::default(), ChildOf(root)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, health), 1.0);
+    assert_eq!(alpha(&app, minimap), 0.5);
+
+    app.world_mut()
+        .resource_mut::<OpacityGroups>()
+        .set("hud", 0.5);
+    app.update();
+    assert_eq!(alpha(&app, health), 0.5);
+    assert_eq!(alpha(&app, minimap), 0.25);
+    assert_eq!(alpha(&app, root), 0.5);
+}
+
+#[test]
+fn opacity_target() {
+    use bevy_mod_opacity::OpacityTarget;
+
+    let mut app = app();
+    let owner = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let health_bar = app
+        .world_mut()
+        .spawn((OpacityTarget(owner), Sprite::default()))
+        .id();
+    let fill = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(health_bar)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, health_bar), 0.5);
+    assert_eq!(alpha(&app, fill), 0.25);
+
+    app.world_mut().entity_mut(owner).insert(Opacity::new(1.0));
+    app.update();
+    assert_eq!(alpha(&app, health_bar), 1.0);
+    assert_eq!(alpha(&app, fill), 0.5);
+}
+
+#[test]
+fn distance_fade() {
+    use bevy_mod_opacity::{DistanceFade, DistanceFadeReference};
+
+    let mut app = app();
+    app.add_plugins(TransformPlugin);
+    app.world_mut()
+        .spawn((DistanceFadeReference, Transform::default()));
+    let near = app
+        .world_mut()
+        .spawn((
+            DistanceFade::new(10.0, 20.0),
+            Transform::from_xyz(5.0, 0.0, 0.0),
+            Sprite::default(),
+        ))
+        .id();
+    let mid = app
+        .world_mut()
+        .spawn((
+            DistanceFade::new(10.0, 20.0),
+            Transform::from_xyz(0.0, 15.0, 0.0),
+            Sprite::default(),
+        ))
+        .id();
+    let far = app
+        .world_mut()
+        .spawn((
+            DistanceFade::new(10.0, 20.0),
+            Transform::from_xyz(0.0, 0.0, 30.0),
+            Sprite::default(),
+        ))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, near), 1.0);
+    assert_eq!(alpha(&app, mid), 0.5);
+    assert_eq!(alpha(&app, far), 0.0);
+}
+
+#[cfg(feature = "occlusion")]
+#[test]
+fn occlusion_fade() {
+    use bevy::render::primitives::Aabb;
+    use bevy_mod_opacity::{OccluderFade, OcclusionTarget};
+
+    let mut app = app();
+    app.add_plugins(TransformPlugin).init_asset::<Mesh>();
+    let mesh = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::default());
+    let aabb = Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5));
+    app.world_mut()
+        .spawn((Camera::default(), Transform::from_xyz(0.0, 0.0, 10.0)));
+    app.world_mut()
+        .spawn((OcclusionTarget, Transform::default()));
+    let fade = OccluderFade::default();
+    let wall = app
+        .world_mut()
+        .spawn((fade, Transform::from_xyz(0.0, 0.0, 5.0)))
+        .with_child((Mesh3d(mesh.clone()), aabb))
+        .id();
+    let pillar = app
+        .world_mut()
+        .spawn((fade, Transform::from_xyz(5.0, 0.0, 5.0)))
+        .with_child((Mesh3d(mesh), aabb))
+        .id();
+    app.update();
+    let opacity = |entity| app.world().get::<Opacity>(entity).unwrap().get_target();
+    assert_eq!(opacity(wall), 0.2);
+    assert_eq!(opacity(pillar), 1.0);
+}
+
+#[test]
+fn lod_crossfade() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::LodCrossfade;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let high = app.world_mut().spawn(Sprite::default()).id();
+    let low = app.world_mut().spawn(Sprite::default()).id();
+    let lod = app
+        .world_mut()
+        .spawn(LodCrossfade::new(high, low, 0.25))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, high), 1.0);
+    assert_eq!(alpha(&app, low), 0.0);
+    assert_eq!(
+        app.world().get::<Visibility>(low),
+        Some(&Visibility::Hidden)
+    );
+
+    app.world_mut()
+        .get_mut::<LodCrossfade>(lod)
+        .unwrap()
+        .set_high(false);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(alpha(&app, high), 0.0);
+    assert_eq!(alpha(&app, low), 1.0);
+    assert_eq!(
+        app.world().get::<Visibility>(high),
+        Some(&Visibility::Hidden)
+    );
+    assert_eq!(
+        app.world().get::<Visibility>(low),
+        Some(&Visibility::Inherited)
+    );
+}
+
+#[test]
+fn real_clock() {
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::with_clock::<Real>(),
+    ))
+    .init_asset::<StandardMaterial>()
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new_fade_in(1.0), Sprite::default()))
+        .id();
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(alpha(&app, entity) > 0.0);
+}
+
+#[test]
+fn fade_unscaled() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::FadeUnscaled;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .set_relative_speed(0.1);
+    let world = app
+        .world_mut()
+        .spawn((Opacity::new_fade_in(1.0), Sprite::default()))
+        .id();
+    let popup = app
+        .world_mut()
+        .spawn((Opacity::new_fade_in(1.0), Sprite::default(), FadeUnscaled))
+        .id();
+    for _ in 0..4 {
+        app.update();
+    }
+    assert!(alpha(&app, popup) > 0.25);
+    assert!(alpha(&app, world) < 0.05);
+}
+
+#[test]
+fn time_scale() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::OpacityTimeScale;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.world_mut().resource_mut::<OpacityTimeScale>().pause();
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new_fade_in(1.0), Sprite::default()))
+        .id();
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(alpha(&app, entity), 0.0);
+
+    let mut scale = app.world_mut().resource_mut::<OpacityTimeScale>();
+    scale.unpause();
+    scale.set_scale(10.0);
+    app.update();
+    assert_eq!(alpha(&app, entity), 1.0);
+}
+
+#[test]
+fn fade_paused() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::FadePaused;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default()))
+        .id();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .fade_out(0.25);
+    app.update();
+    app.update();
+    app.world_mut().entity_mut(entity).insert(FadePaused);
+    let paused = *app.world().get::<Opacity>(entity).unwrap();
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&paused));
+
+    app.world_mut().entity_mut(entity).remove::<FadePaused>();
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn reduced_motion() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::ReducedMotion;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.insert_resource(ReducedMotion::Capped(0.2));
+    let capped = app
+        .world_mut()
+        .spawn((Opacity::new_fade_in(10.0), Sprite::default()))
+        .id();
+    app.update();
+    app.update();
+    assert_eq!(alpha(&app, capped), 0.5);
+
+    app.insert_resource(ReducedMotion::Instant);
+    let instant = app
+        .world_mut()
+        .spawn((Opacity::new_fade_in(10.0), Sprite::default()))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, instant), 1.0);
+}
+
+#[test]
+fn plugin_config() {
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::new()
+            .with_despawn_on_fade_out(false)
+            .with_auto_visibility(true),
+    ))
+    .init_asset::<StandardMaterial>()
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default()))
+        .id();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .fade_out(0.25);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(alpha(&app, entity), 0.0);
+    assert_eq!(
+        app.world().get::<Visibility>(entity),
+        Some(&Visibility::Hidden)
+    );
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::new().without_builtin(),
+    ));
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, entity), 1.0);
+}
+
+#[test]
+fn custom_schedule() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::new().in_schedule(Update),
+    ))
+    .init_asset::<StandardMaterial>()
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>();
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    app.world_mut().run_schedule(Update);
+    assert_eq!(alpha(&app, entity), 0.5);
+}
+
+#[test]
+fn opacity_inherit() {
+    use bevy_mod_opacity::OpacityInherit;
+
+    let mut app = app();
+    let card = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let tooltip = app
+        .world_mut()
+        .spawn((OpacityInherit::Isolated, Sprite::default(), ChildOf(card)))
+        .id();
+    let tooltip_text = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(tooltip)))
+        .id();
+    let skipped = app
+        .world_mut()
+        .spawn((OpacityInherit::Skip, Sprite::default(), ChildOf(card)))
+        .id();
+    let nested = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(skipped)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, card), 0.5);
+    assert_eq!(alpha(&app, tooltip), 1.0);
+    assert_eq!(alpha(&app, tooltip_text), 1.0);
+    assert!(app.world().resource::<OpacityMap>().get(skipped).is_none());
+    assert_eq!(alpha(&app, nested), 0.5);
+}
+
+#[test]
+fn opacity_blend() {
+    use bevy_mod_opacity::OpacityBlend;
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let mut child = |blend, opacity| {
+        app.world_mut()
+            .spawn((
+                blend,
+                Opacity::new(opacity),
+                Sprite::default(),
+                ChildOf(root),
+            ))
+            .id()
+    };
+    let multiply = child(OpacityBlend::Multiply, 0.5);
+    let min = child(OpacityBlend::Min, 0.75);
+    let max = child(OpacityBlend::Max, 0.75);
+    let replace = child(OpacityBlend::Override, 0.75);
+    let leaf = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(replace)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, multiply), 0.25);
+    assert_eq!(alpha(&app, min), 0.5);
+    assert_eq!(alpha(&app, max), 0.75);
+    assert_eq!(alpha(&app, replace), 0.75);
+    assert_eq!(alpha(&app, leaf), 0.75);
+}
+
+#[test]
+fn opacity_weight() {
+    use bevy_mod_opacity::OpacityWeight;
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let glow = app
+        .world_mut()
+        .spawn((OpacityWeight(0.5), Sprite::default(), ChildOf(root)))
+        .id();
+    let leaf = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(glow)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, glow), 0.75);
+    assert_eq!(alpha(&app, leaf), 0.375);
+    app.world_mut().get_mut::<OpacityWeight>(glow).unwrap().0 = 0.0;
+    app.update();
+    assert_eq!(alpha(&app, glow), 1.0);
+}
+
+#[test]
+fn opacity_limit() {
+    use bevy_mod_opacity::{OpacityBlend, OpacityLimit};
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default()))
+        .id();
+    let ghost = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            OpacityLimit::max(0.4),
+            OpacityBlend::Override,
+            Sprite::default(),
+            ChildOf(root),
+        ))
+        .id();
+    let leaf = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(ghost)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, ghost), 0.4);
+    assert_eq!(alpha(&app, leaf), 0.4);
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.5);
+    app.world_mut()
+        .entity_mut(ghost)
+        .insert(OpacityBlend::Multiply);
+    app.update();
+    assert_eq!(alpha(&app, ghost), 0.25);
+
+    let marker = app
+        .world_mut()
+        .spawn((OpacityLimit::min(0.25), Sprite::default(), ChildOf(root)))
+        .id();
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.0);
+    app.update();
+    assert_eq!(alpha(&app, root), 0.0);
+    assert_eq!(alpha(&app, marker), 0.25);
+}
+
+#[test]
+fn opacity_root() {
+    use bevy_mod_opacity::OpacityRoot;
+
+    let mut app = app();
+    let top = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let mut entity = top;
+    for _ in 0..8 {
+        entity = app
+            .world_mut()
+            .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(entity)))
+            .id();
+    }
+    let pinned = app
+        .world_mut()
+        .spawn((OpacityRoot, Sprite::default(), ChildOf(entity)))
+        .id();
+    let pinned_child = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(pinned)))
+        .id();
+    for _ in 0..4 {
+        app.world_mut().get_mut::<Opacity>(top).unwrap().set(0.5);
+        app.update();
+        assert_eq!(alpha(&app, entity), 0.5f32.powi(9));
+        assert_eq!(alpha(&app, pinned), 1.0);
+        assert_eq!(alpha(&app, pinned_child), 0.5);
+    }
+}
+
+#[derive(Component)]
+#[relationship(relationship_target = Labels)]
+struct LabelOf(Entity);
+
+#[derive(Component)]
+#[relationship_target(relationship = LabelOf)]
+struct Labels(Vec<Entity>);
+
+#[test]
+fn opacity_relationship() {
+    let mut app = app();
+    app.register_opacity_relationship::<LabelOf>();
+    let owner = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let label = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default(), LabelOf(owner)))
+        .id();
+    let label_child = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(label)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, label), 0.25);
+    assert_eq!(alpha(&app, label_child), 0.25);
+    app.world_mut().get_mut::<Opacity>(owner).unwrap().set(1.0);
+    app.update();
+    assert_eq!(alpha(&app, label_child), 0.5);
+}
+
+#[test]
+fn standard_material_policy() {
+    use bevy_mod_opacity::StandardMaterialOpacityPolicy;
+
+    let mut app = app();
+    let mut materials = app.world_mut().resource_mut::<Assets<StandardMaterial>>();
+    let glowing = StandardMaterial {
+        emissive: LinearRgba::rgb(2.0, 2.0, 2.0),
+        ..Default::default()
+    };
+    let scaled = materials.add(glowing.clone());
+    let alpha_only = materials.add(glowing);
+    app.world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial3d(scaled.clone())));
+    app.world_mut().spawn((
+        Opacity::new(0.5),
+        MeshMaterial3d(alpha_only.clone()),
+        StandardMaterialOpacityPolicy::ALPHA_ONLY,
+    ));
+    app.update();
+    app.update();
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    let scaled = materials.get(&scaled).unwrap();
+    assert_eq!(scaled.base_color.alpha(), 0.5);
+    assert_eq!(scaled.emissive.red, 1.0);
+    assert_eq!(scaled.reflectance, 0.25);
+    let alpha_only = materials.get(&alpha_only).unwrap();
+    assert_eq!(alpha_only.base_color.alpha(), 0.5);
+    assert_eq!(alpha_only.emissive.red, 2.0);
+    assert_eq!(alpha_only.reflectance, 0.5);
+}
+
+#[test]
+fn shared_standard_material_policy() {
+    use bevy_mod_opacity::BaseStandardMaterials;
+
+    let mut app = app();
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            emissive: LinearRgba::rgb(2.0, 2.0, 2.0),
+            ..Default::default()
+        });
+    let emissive = |app: &App| {
+        let materials = app.world().resource::<Assets<StandardMaterial>>();
+        materials.get(&material).unwrap().emissive.red
+    };
+    let first = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial3d(material.clone())))
+        .id();
+    app.update();
+    assert_eq!(emissive(&app), 1.0);
+    // A second entity sharing the scaled material scales from the authored values.
+    let second = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial3d(material.clone())))
+        .id();
+    app.world_mut().despawn(first);
+    app.update();
+    app.world_mut().get_mut::<Opacity>(second).unwrap().set(1.0);
+    app.update();
+    assert_eq!(emissive(&app), 2.0);
+    app.world_mut()
+        .get_mut::<Opacity>(second)
+        .unwrap()
+        .set(0.25);
+    app.update();
+    assert_eq!(emissive(&app), 0.5);
+    // Restored once no faded entity uses the material.
+    app.world_mut().entity_mut(second).remove::<Opacity>();
+    app.update();
+    assert_eq!(emissive(&app), 2.0);
+    let bases = app.world().resource::<BaseStandardMaterials>();
+    assert_eq!(bases.get(&material), None);
+}
+
+#[test]
+fn dim_mode() {
+    use bevy_mod_opacity::{BaseColor, OpacityMode};
+
+    let mut app = app();
+    app.insert_resource(OpacityMode::Dim);
+    let color = Color::linear_rgb(0.5, 1.0, 0.25);
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            Sprite {
+                color,
+                ..Default::default()
+            },
+        ))
+        .id();
+    app.update();
+    let sprite = || app.world().get::<Sprite>(entity).unwrap().color.to_linear();
+    assert_eq!(sprite(), LinearRgba::new(0.25, 0.5, 0.125, 1.0));
+    app.update();
+    assert_eq!(
+        app.world().get::<BaseColor>(entity),
+        Some(&BaseColor(color))
+    );
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.0);
+    app.update();
+    let sprite = || app.world().get::<Sprite>(entity).unwrap().color.to_linear();
+    assert_eq!(sprite(), LinearRgba::new(0.0, 0.0, 0.0, 1.0));
+
+    app.insert_resource(OpacityMode::Alpha);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.5);
+    app.update();
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color,
+        color.with_alpha(0.5)
+    );
+    assert!(app.world().get::<BaseColor>(entity).is_none());
+}
+
+#[test]
+fn opacity_tint() {
+    use bevy_mod_opacity::{BaseColor, OpacityTint};
+
+    let mut app = app();
+    let black = Color::linear_rgb(0.0, 0.0, 0.0);
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.25),
+            OpacityTint(Color::WHITE),
+            Sprite {
+                color: black,
+                ..Default::default()
+            },
+        ))
+        .id();
+    app.update();
+    let sprite = |app: &App| app.world().get::<Sprite>(entity).unwrap().color.to_linear();
+    assert_eq!(sprite(&app), LinearRgba::new(0.75, 0.75, 0.75, 0.25));
+    app.update();
+    assert_eq!(
+        app.world().get::<BaseColor>(entity),
+        Some(&BaseColor(black))
+    );
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    app.update();
+    assert_eq!(sprite(&app), LinearRgba::new(0.0, 0.0, 0.0, 1.0));
+}
+
+#[test]
+fn per_entity_mode() {
+    use bevy_mod_opacity::OpacityMode;
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let hybrid = app
+        .world_mut()
+        .spawn((OpacityMode::Hybrid, Sprite::default(), ChildOf(root)))
+        .id();
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    app.world_mut().spawn((
+        OpacityMode::Dither,
+        MeshMaterial3d(material.clone()),
+        ChildOf(root),
+    ));
+    app.update();
+    assert_eq!(alpha(&app, root), 0.5);
+    assert_eq!(
+        app.world().get::<Sprite>(hybrid).unwrap().color.to_linear(),
+        LinearRgba::new(0.5, 0.5, 0.5, 0.5)
+    );
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    let material = materials.get(&material).unwrap();
+    assert_eq!(material.alpha_mode, AlphaMode::AlphaToCoverage);
+    assert_eq!(material.base_color.alpha(), 0.5);
+}
+
+#[test]
+fn shared_material_modes() {
+    use bevy_mod_opacity::{BaseAlphaModes, BaseMaterialColors, OpacityMode};
+
+    let mut app = app();
+    app.insert_resource(OpacityMode::Dim);
+    let color = Color::linear_rgb(0.5, 1.0, 0.25);
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .add(ColorMaterial::from_color(color));
+    let material_color = |app: &App| {
+        let materials = app.world().resource::<Assets<ColorMaterial>>();
+        materials.get(&material).unwrap().color
+    };
+    let first = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial2d(material.clone())))
+        .id();
+    app.update();
+    assert_eq!(
+        material_color(&app).to_linear(),
+        LinearRgba::new(0.25, 0.5, 0.125, 1.0)
+    );
+    // A second entity sharing the dimmed material dims from the authored color.
+    let second = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial2d(material.clone())))
+        .id();
+    app.world_mut().despawn(first);
+    app.update();
+    app.world_mut().get_mut::<Opacity>(second).unwrap().set(1.0);
+    app.update();
+    assert_eq!(material_color(&app), color);
+    app.world_mut().get_mut::<Opacity>(second).unwrap().set(0.5);
+    app.update();
+    app.world_mut().despawn(second);
+    app.update();
+    assert_eq!(material_color(&app), color);
+    let colors = app.world().resource::<BaseMaterialColors<ColorMaterial>>();
+    assert_eq!(colors.get(&material), None);
+
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            alpha_mode: AlphaMode::Premultiplied,
+            ..Default::default()
+        });
+    let alpha_mode = |app: &App| {
+        let materials = app.world().resource::<Assets<StandardMaterial>>();
+        materials.get(&material).unwrap().alpha_mode
+    };
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            OpacityMode::Dither,
+            MeshMaterial3d(material.clone()),
+        ))
+        .id();
+    app.update();
+    assert_eq!(alpha_mode(&app), AlphaMode::AlphaToCoverage);
+    app.world_mut()
+        .entity_mut(entity)
+        .insert(OpacityMode::Alpha)
+        .get_mut::<Opacity>()
+        .unwrap()
+        .set(0.25);
+    app.update();
+    assert_eq!(alpha_mode(&app), AlphaMode::Premultiplied);
+    assert_eq!(
+        app.world().resource::<BaseAlphaModes>().get(&material),
+        None
+    );
+    // Restored once no faded entity uses the material.
+    app.world_mut()
+        .entity_mut(entity)
+        .insert(OpacityMode::Dither);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.5);
+    app.update();
+    assert_eq!(alpha_mode(&app), AlphaMode::AlphaToCoverage);
+    app.world_mut().despawn(entity);
+    app.update();
+    assert_eq!(alpha_mode(&app), AlphaMode::Premultiplied);
+}
+
+#[test]
+fn remove_dim_mode() {
+    use bevy_mod_opacity::{BaseColor, OpacityMode};
+
+    let mut app = app();
+    let color = Color::linear_rgb(0.5, 1.0, 0.25);
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            OpacityMode::Dim,
+            ImageNode::default().with_color(color),
+        ))
+        .id();
+    app.update();
+    let image = |app: &App| app.world().get::<ImageNode>(entity).unwrap().color;
+    assert_eq!(
+        image(&app).to_linear(),
+        LinearRgba::new(0.25, 0.5, 0.125, 1.0)
+    );
+    app.world_mut().entity_mut(entity).remove::<OpacityMode>();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set(0.25);
+    app.update();
+    assert_eq!(image(&app), color.with_alpha(0.25));
+    assert!(app.world().get::<BaseColor>(entity).is_none());
+}
+
+#[test]
+fn vertex_color() {
+    use bevy::render::mesh::VertexAttributeValues;
+    use bevy_mod_opacity::{BaseVertexAlpha, VertexColorOpacity};
+
+    let mut app = app();
+    let mesh = Mesh::from(Rectangle::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, vec![[1.0, 1.0, 1.0, 0.5]; 4]);
+    let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().add(mesh);
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Mesh3d(mesh.clone()), VertexColorOpacity))
+        .id();
+    app.update();
+    let alphas = |app: &App| {
+        let meshes = app.world().resource::<Assets<Mesh>>();
+        match meshes.get(&mesh).unwrap().attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => {
+                colors.iter().map(|color| color[3]).collect::<Vec<_>>()
+            }
+            _ => panic!("missing vertex colors"),
+        }
+    };
+    assert_eq!(alphas(&app), vec![0.25; 4]);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    app.update();
+    assert_eq!(alphas(&app), vec![0.5; 4]);
+    assert_eq!(
+        app.world().get::<BaseVertexAlpha>(entity),
+        Some(&BaseVertexAlpha(vec![0.5; 4]))
+    );
+}
+
+#[test]
+fn skybox_brightness() {
+    use bevy::core_pipeline::Skybox;
+
+    let mut app = app();
+    let camera = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            Skybox {
+                brightness: 1000.0,
+                ..Default::default()
+            },
+            EnvironmentMapLight {
+                intensity: 400.0,
+                ..Default::default()
+            },
+        ))
+        .id();
+    app.update();
+    app.world_mut()
+        .get_mut::<Opacity>(camera)
+        .unwrap()
+        .set(0.25);
+    app.update();
+    assert_eq!(app.world().get::<Skybox>(camera).unwrap().brightness, 250.0);
+    assert_eq!(
+        app.world()
+            .get::<EnvironmentMapLight>(camera)
+            .unwrap()
+            .intensity,
+        100.0
+    );
+}
+
+#[test]
+fn ambient_fade() {
+    use bevy_mod_opacity::{AmbientFade, GlobalOpacity};
+
+    let mut app = app();
+    app.insert_resource(AmbientLight {
+        brightness: 100.0,
+        ..Default::default()
+    })
+    .insert_resource(AmbientFade::Global)
+    .insert_resource(GlobalOpacity(0.5));
+    let camera = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.25),
+            AmbientLight {
+                brightness: 100.0,
+                ..Default::default()
+            },
+        ))
+        .id();
+    app.update();
+    assert_eq!(app.world().resource::<AmbientLight>().brightness, 50.0);
+    assert_eq!(
+        app.world().get::<AmbientLight>(camera).unwrap().brightness,
+        12.5
+    );
+    app.insert_resource(AmbientFade::Entity(camera));
+    app.update();
+    assert_eq!(app.world().resource::<AmbientLight>().brightness, 12.5);
+}
+
+#[test]
+fn camera_fade() {
+    use bevy::render::view::ColorGrading;
+    use bevy_mod_opacity::CameraFade;
+
+    let mut app = app();
+    let camera = app.world_mut().spawn((CameraFade, Opacity::new(0.25))).id();
+    app.update();
+    let exposure = |app: &App| {
+        app.world()
+            .get::<ColorGrading>(camera)
+            .unwrap()
+            .global
+            .exposure
+    };
+    assert_eq!(exposure(&app), -2.0);
+    app.world_mut().get_mut::<Opacity>(camera).unwrap().set(1.0);
+    app.update();
+    assert_eq!(exposure(&app), 0.0);
+}
+
+#[cfg(feature = "diagnostics")]
+#[test]
+fn diagnostics() {
+    use bevy::diagnostic::{DiagnosticPath, DiagnosticsStore};
+    use bevy_mod_opacity::OpacityDiagnostics;
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    app.world_mut().spawn((Sprite::default(), ChildOf(root)));
+    app.world_mut().spawn(Opacity::new_fade_in(1.0));
+    app.update();
+    let value = |app: &App, path: DiagnosticPath| {
+        app.world()
+            .resource::<DiagnosticsStore>()
+            .get(&path)
+            .and_then(|diagnostic| diagnostic.value())
+    };
+    assert_eq!(value(&app, OpacityDiagnostics::ENTITIES), Some(2.0));
+    assert_eq!(value(&app, OpacityDiagnostics::FADING), Some(1.0));
+    assert_eq!(value(&app, OpacityDiagnostics::MAP_SIZE), Some(3.0));
+    assert_eq!(value(&app, OpacityDiagnostics::APPLIED), Some(3.0));
+}
+
+#[cfg(feature = "dev_tools")]
+#[test]
+fn debug_overlay() {
+    use bevy_mod_opacity::OpacityDebugOverlay;
+
+    let mut app = app();
+    app.world_mut()
+        .spawn((Name::new("card"), Node::default(), Opacity::new(0.5)));
+    app.update();
+    app.update();
+    let mut labels = app.world_mut().query::<&Text>();
+    let labels: Vec<_> = labels
+        .iter(app.world())
+        .map(|text| text.0.clone())
+        .collect();
+    assert_eq!(labels, vec!["card: 0.50 (computed 0.50)".to_string()]);
+
+    app.insert_resource(OpacityDebugOverlay(false));
+    app.update();
+    app.update();
+    assert_eq!(app.world_mut().query::<&Text>().iter(app.world()).len(), 0);
+}
+
+#[test]
+fn step_opacity() {
+    use bevy_mod_opacity::OpacityTestExt;
+    use std::time::Duration;
+
+    let mut app = app();
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default()))
+        .id();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .fade_out(0.4);
+    app.step_opacity(Duration::from_millis(100));
+    assert_eq!(alpha(&app, entity), 0.75);
+    app.step_opacity(Duration::from_millis(200));
+    assert_eq!(alpha(&app, entity), 0.25);
+    app.step_opacity(Duration::from_millis(100));
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn persistent_map() {
+    use bevy_mod_opacity::OpacityTarget;
+
+    let mut app = app();
+    let a = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let b = app
+        .world_mut()
+        .spawn((Opacity::new(0.25), Sprite::default()))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(a)))
+        .id();
+    let proxy = app
+        .world_mut()
+        .spawn((Sprite::default(), OpacityTarget(b)))
+        .id();
+    app.update();
+    let get = |app: &App, entity| app.world().resource::<OpacityMap>().get(entity);
+    assert_eq!(get(&app, child), Some(0.25));
+    assert_eq!(get(&app, proxy), Some(0.25));
+    app.world_mut().entity_mut(child).insert(ChildOf(b));
+    app.update();
+    assert_eq!(get(&app, a), Some(0.5));
+    assert_eq!(get(&app, child), Some(0.125));
+    app.world_mut().entity_mut(child).remove::<ChildOf>();
+    app.update();
+    assert_eq!(get(&app, child), Some(0.5));
+    app.world_mut().entity_mut(b).remove::<Opacity>();
+    app.update();
+    assert_eq!(get(&app, b), None);
+    assert_eq!(get(&app, proxy), None);
+    app.world_mut().entity_mut(a).despawn();
+    app.update();
+    assert_eq!(get(&app, a), None);
+    assert_eq!(get(&app, child), Some(0.5));
+    assert_eq!(app.world().resource::<OpacityMap>().iter().count(), 1);
+}
+
+#[test]
+fn opacity_changed_events() {
+    use bevy_mod_opacity::{OpacityChanged, OpacityConfig};
+
+    let mut app = app();
+    app.world_mut()
+        .resource_mut::<OpacityConfig>()
+        .change_epsilon = 0.1;
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(1.0), Sprite::default()))
+        .id();
+    let changes = |app: &mut App| {
+        app.world_mut()
+            .resource_mut::<Events<OpacityChanged>>()
+            .drain()
+            .collect::<Vec<_>>()
+    };
+    app.update();
+    assert!(changes(&mut app).is_empty());
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set(0.95);
+    app.update();
+    assert!(changes(&mut app).is_empty());
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.5);
+    app.update();
+    assert_eq!(
+        changes(&mut app),
+        [OpacityChanged {
+            entity,
+            old: 1.0,
+            new: 0.5
+        }]
+    );
+}
+
+#[test]
+fn apply_epsilon() {
+    use bevy_mod_opacity::OpacityConfig;
+
+    let mut app = app();
+    app.world_mut()
+        .resource_mut::<OpacityConfig>()
+        .apply_epsilon = 0.01;
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, entity), 0.5);
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set(0.505);
+    app.update();
+    assert_eq!(alpha(&app, entity), 0.5);
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set(0.52);
+    app.update();
+    assert_eq!(alpha(&app, entity), 0.52);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    app.update();
+    assert_eq!(alpha(&app, entity), 1.0);
+}
+
+#[test]
+fn unchanged_assets_not_written() {
+    let mut app = app();
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .add(ColorMaterial::default());
+    app.world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial2d(material.clone())));
+    let other = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    app.update();
+    let alpha = |app: &App| {
+        app.world()
+            .resource::<Assets<ColorMaterial>>()
+            .get(&material)
+            .unwrap()
+            .color
+            .alpha()
+    };
+    assert_eq!(alpha(&app), 0.5);
+    app.world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .get_mut(&material)
+        .unwrap()
+        .color
+        .set_alpha(1.0);
+    app.world_mut().get_mut::<Opacity>(other).unwrap().set(0.25);
+    app.update();
+    assert_eq!(alpha(&app), 1.0);
+}
+
+#[test]
+fn shared_asset_written_once() {
+    let mut app = app();
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .add(ColorMaterial::default());
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    for opacity in [1.0, 0.5] {
+        app.world_mut().spawn((
+            Opacity::new(opacity),
+            MeshMaterial2d(material.clone()),
+            ChildOf(root),
+        ));
+    }
+    let alpha = |app: &App| {
+        app.world()
+            .resource::<Assets<ColorMaterial>>()
+            .get(&material)
+            .unwrap()
+            .color
+            .alpha()
+    };
+    app.update();
+    let first = alpha(&app);
+    assert!(first == 0.5 || first == 0.25);
+    for _ in 0..4 {
+        app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.5);
+        app.update();
+        assert_eq!(alpha(&app), first);
+    }
+}
+
+#[test]
+fn fade_in_on_add() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::FadeInOnAdd;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            FadeInOnAdd(Duration::from_secs(1)),
+            Sprite::default(),
+        ))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, entity), 0.0);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(alpha(&app, entity), 0.5);
+}
+
+#[test]
+fn fade_subtree() {
+    use bevy_mod_opacity::{OpacityCommandsExt, OpacityInherit, OpacityRoot};
+
+    let mut app = app();
+    let root = app.world_mut().spawn(Opacity::OPAQUE).id();
+    let inherited = app.world_mut().spawn((Opacity::OPAQUE, ChildOf(root))).id();
+    let pinned = app
+        .world_mut()
+        .spawn((OpacityRoot, ChildOf(inherited)))
+        .id();
+    let skip = app
+        .world_mut()
+        .spawn((OpacityInherit::Skip, ChildOf(root)))
+        .id();
+    let skipped = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), ChildOf(skip)))
+        .id();
+    app.world_mut()
+        .commands()
+        .entity(root)
+        .fade_out_subtree(1.0);
+    app.world_mut().flush();
+    let opacity = |entity| *app.world().get::<Opacity>(entity).unwrap();
+    assert!(opacity(root).is_despawning());
+    assert!(!opacity(inherited).is_fading());
+    for entity in [pinned, skipped] {
+        assert!(opacity(entity).is_fading());
+        assert!(!opacity(entity).is_despawning());
+        assert_eq!(opacity(entity).get_target(), 0.0);
+    }
+    app.world_mut().commands().entity(root).fade_in_subtree(1.0);
+    app.world_mut().flush();
+    let opacity = |entity| *app.world().get::<Opacity>(entity).unwrap();
+    assert!(!opacity(root).is_despawning());
+    assert_eq!(opacity(skipped).get_target(), 1.0);
+}
+
+#[test]
+fn crossfade_synchronized() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{Crossfade, FadePaused};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let from = app.world_mut().spawn(Opacity::OPAQUE).id();
+    let to = app.world_mut().spawn_empty().id();
+    app.world_mut()
+        .commands()
+        .queue(Crossfade::new(from, to, 1.0).hide());
+    for _ in 0..4 {
+        app.update();
+    }
+    let opacity = |app: &App, entity| app.world().get::<Opacity>(entity).unwrap().get();
+    let paused = (opacity(&app, from), opacity(&app, to));
+    assert!((paused.0 + paused.1 - 1.0).abs() < 1e-5);
+    app.world_mut().entity_mut(from).insert(FadePaused);
+    for _ in 0..3 {
+        app.update();
+        assert_eq!((opacity(&app, from), opacity(&app, to)), paused);
+    }
+    app.world_mut().entity_mut(from).remove::<FadePaused>();
+    for _ in 0..8 {
+        app.update();
+    }
+    assert_eq!(opacity(&app, from), 0.0);
+    assert_eq!(opacity(&app, to), 1.0);
+}
+
+#[test]
+fn snapshot_restore() {
+    use bevy_mod_opacity::{OpacityCommandsExt, OpacitySnapshots};
+
+    let mut app = app();
+    let hud = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(hud)))
+        .id();
+    app.update();
+    app.world_mut().commands().entity(hud).snapshot_opacity();
+    app.world_mut().flush();
+    let snapshot = &app.world().resource::<OpacitySnapshots>().0[&hud];
+    assert_eq!(snapshot.get(hud), Some(Opacity::new(0.5)));
+    assert_eq!(snapshot.computed(child), Some(0.5));
+    app.world_mut().entity_mut(hud).insert(Opacity::INVISIBLE);
+    app.world_mut().entity_mut(child).insert(Opacity::new(0.25));
+    app.update();
+    assert_eq!(alpha(&app, child), 0.0);
+    app.world_mut().commands().entity(hud).restore_opacity();
+    app.update();
+    assert_eq!(alpha(&app, child), 0.5);
+    assert!(app.world().get::<Opacity>(child).is_none());
+    assert!(app.world().resource::<OpacitySnapshots>().0.is_empty());
+}
+
+#[test]
+fn fade_future() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{FadeOutcome, OpacityCommandsExt};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app.world_mut().spawn(Opacity::OPAQUE).id();
+    let cancelled = app
+        .world_mut()
+        .commands()
+        .entity(entity)
+        .fade_to_async(0.5, 1.0);
+    let completed = app
+        .world_mut()
+        .commands()
+        .entity(entity)
+        .fade_out_async(0.2);
+    app.update();
+    assert_eq!(cancelled.outcome(), Some(FadeOutcome::Cancelled));
+    assert_eq!(completed.outcome(), None);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app.world().get_entity(entity).is_err());
+    assert_eq!(bevy::tasks::block_on(completed), FadeOutcome::Completed);
+}
+
+#[test]
+fn fade_on_interaction() {
+    use bevy_mod_opacity::FadeOnInteraction;
+    use std::time::Duration;
+
+    let mut app = app();
+    let button = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            FadeOnInteraction::new(0.5, 1.0, 0.75, Duration::ZERO),
+        ))
+        .id();
+    let opacity = |app: &App| app.world().get::<Opacity>(button).unwrap().get();
+    app.update();
+    assert_eq!(opacity(&app), 0.5);
+    *app.world_mut().get_mut::<Interaction>(button).unwrap() = Interaction::Hovered;
+    app.update();
+    assert_eq!(opacity(&app), 1.0);
+    *app.world_mut().get_mut::<Interaction>(button).unwrap() = Interaction::Pressed;
+    app.update();
+    assert_eq!(opacity(&app), 0.75);
+}
+
+#[test]
+fn fade_on_visibility() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::FadeOnVisibility;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let dialog = app
+        .world_mut()
+        .spawn((
+            Visibility::Hidden,
+            FadeOnVisibility::new(Duration::from_millis(200)),
+        ))
+        .id();
+    app.update();
+    let visibility = |app: &App| *app.world().get::<Visibility>(dialog).unwrap();
+    let opacity = |app: &App| app.world().get::<Opacity>(dialog).unwrap().get();
+    *app.world_mut().get_mut::<Visibility>(dialog).unwrap() = Visibility::Visible;
+    app.update();
+    assert_eq!(opacity(&app), 0.5);
+    app.update();
+    assert_eq!(opacity(&app), 1.0);
+    *app.world_mut().get_mut::<Visibility>(dialog).unwrap() = Visibility::Hidden;
+    app.update();
+    assert_eq!(visibility(&app), Visibility::Visible);
+    assert!(app
+        .world()
+        .get::<FadeOnVisibility>(dialog)
+        .unwrap()
+        .is_hiding());
+    for _ in 0..2 {
+        app.update();
+    }
+    assert_eq!(visibility(&app), Visibility::Hidden);
+    assert_eq!(opacity(&app), 0.0);
+}
+
+#[test]
+fn disable_focus() {
+    use bevy::ui::FocusPolicy;
+    use bevy_mod_opacity::OpacityFocusThreshold;
+
+    let mut app = app();
+    app.insert_resource(OpacityFocusThreshold(0.1));
+    let button = app
+        .world_mut()
+        .spawn((Button, Opacity::INVISIBLE, Interaction::Hovered))
+        .id();
+    app.update();
+    let focus = |app: &App| *app.world().get::<FocusPolicy>(button).unwrap();
+    assert_eq!(focus(&app), FocusPolicy::Pass);
+    assert_eq!(app.world().get::<Interaction>(button), None);
+    app.world_mut().get_mut::<Opacity>(button).unwrap().set(1.0);
+    app.update();
+    assert_eq!(focus(&app), FocusPolicy::Block);
+    assert_eq!(
+        app.world().get::<Interaction>(button),
+        Some(&Interaction::None)
+    );
+}
+
+#[test]
+fn focus_click_through() {
+    use bevy::input::{mouse::MouseButton, touch::Touches, ButtonInput};
+    use bevy::ui::{
+        ui_focus_system, update::update_ui_context_system, ComputedNode, ComputedNodeTarget,
+        UiScale, UiStack,
+    };
+    use bevy::window::PrimaryWindow;
+
+    let mut app = app();
+    app.init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<Touches>()
+        .init_resource::<UiScale>()
+        .init_resource::<UiStack>()
+        .add_systems(
+            PreUpdate,
+            (update_ui_context_system, ui_focus_system).chain(),
+        );
+    let mut window = Window::default();
+    window.set_physical_cursor_position(Some((50.0, 50.0).into()));
+    app.world_mut().spawn((window, PrimaryWindow));
+    app.world_mut().spawn(Camera::default());
+    let mut button = |opacity| {
+        app.world_mut()
+            .spawn((
+                Button,
+                ComputedNode {
+                    size: Vec2::splat(100.0),
+                    ..Default::default()
+                },
+                ComputedNodeTarget::default(),
+                GlobalTransform::from_xyz(50.0, 50.0, 0.0),
+                InheritedVisibility::VISIBLE,
+                Opacity::new(opacity),
+            ))
+            .id()
+    };
+    let below = button(1.0);
+    let faded = button(0.0);
+    app.world_mut().resource_mut::<UiStack>().uinodes = vec![below, faded];
+    app.update();
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+    app.update();
+    let interaction = |app: &App, entity| app.world().get::<Interaction>(entity).copied();
+    assert_eq!(interaction(&app, faded), None);
+    assert_eq!(interaction(&app, below), Some(Interaction::Pressed));
+}
+
+#[test]
+fn visibility_events() {
+    use bevy_mod_opacity::{BecameInvisible, BecameVisible};
+
+    let mut app = app();
+    let entity = app.world_mut().spawn(Opacity::OPAQUE).id();
+    app.update();
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.0);
+    app.update();
+    let invisible: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Events<BecameInvisible>>()
+        .drain()
+        .collect();
+    assert_eq!(invisible, [BecameInvisible(entity)]);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.5);
+    app.update();
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    app.update();
+    let visible: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Events<BecameVisible>>()
+        .drain()
+        .collect();
+    assert_eq!(visible, [BecameVisible(entity)]);
+
+    // Neither the initial opacity nor a despawning fade out send an event.
+    app.world_mut().spawn(Opacity::INVISIBLE);
+    app.update();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .fade_out(0.0);
+    app.update();
+    app.update();
+    assert!(app.world().get_entity(entity).is_err());
+    assert!(app.world().resource::<Events<BecameInvisible>>().is_empty());
+}
+
+#[cfg(feature = "curve")]
+#[test]
+fn fade_curve() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{FadeCurve, OpacityCurve, ReducedMotion};
+    use std::time::Duration;
+
+    let mut app = app();
+    // The asset is registered once plugins are finished.
+    app.finish();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let curve = app
+        .world_mut()
+        .resource_mut::<Assets<OpacityCurve>>()
+        .add(OpacityCurve {
+            keyframes: vec![(0.0, 0.0), (0.2, 1.0), (0.4, 0.5)],
+        });
+    let entity = app.world_mut().spawn(FadeCurve::new(curve.clone())).id();
+    app.update();
+    let opacity = |app: &App| app.world().get::<Opacity>(entity).unwrap().get();
+    assert_eq!(opacity(&app), 0.0);
+    app.update();
+    assert_eq!(opacity(&app), 0.5);
+    app.world_mut()
+        .resource_mut::<Assets<OpacityCurve>>()
+        .get_mut(&curve)
+        .unwrap()
+        .keyframes[1]
+        .1 = 0.8;
+    app.update();
+    assert_eq!(opacity(&app), 0.8);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(opacity(&app), 0.5);
+    assert!(app.world().get::<FadeCurve>(entity).is_none());
+
+    app.insert_resource(ReducedMotion::Instant);
+    let instant = app.world_mut().spawn(FadeCurve::new(curve)).id();
+    app.update();
+    assert_eq!(app.world().get::<Opacity>(instant).unwrap().get(), 0.5);
+    assert!(app.world().get::<FadeCurve>(instant).is_none());
+}
+
+#[test]
+fn opacity_sequence() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{OpacitySequence, OpacitySequenceEvent};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let step = Duration::from_millis(200);
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::INVISIBLE,
+            OpacitySequence::new()
+                .fade_to(1.0, step)
+                .emit("shown")
+                .hold(step)
+                .fade_out(step)
+                .despawn(),
+        ))
+        .id();
+    let opacity = |app: &App| app.world().get::<Opacity>(entity).unwrap().get();
+    app.update();
+    app.update();
+    assert_eq!(opacity(&app), 0.5);
+    app.update();
+    assert_eq!(opacity(&app), 1.0);
+    // The next step starts once the fade reached its target.
+    app.update();
+    let events = app.world().resource::<Events<OpacitySequenceEvent>>();
+    let sent: Vec<_> = events.iter_current_update_events().cloned().collect();
+    assert_eq!(
+        sent,
+        [OpacitySequenceEvent {
+            entity,
+            name: "shown".into()
+        }]
+    );
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(opacity(&app), 0.0);
+    app.update();
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn opacity_sequence_clock() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{FadePaused, FadeUnscaled, OpacitySequence, ReducedMotion};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let step = Duration::from_millis(200);
+    let sequence = || {
+        OpacitySequence::new()
+            .fade_to(1.0, step)
+            .hold(step)
+            .despawn()
+    };
+    let scaled = app.world_mut().spawn((Opacity::INVISIBLE, sequence())).id();
+    let unscaled = app
+        .world_mut()
+        .spawn((Opacity::INVISIBLE, sequence(), FadeUnscaled))
+        .id();
+    let paused = app
+        .world_mut()
+        .spawn((Opacity::INVISIBLE, sequence(), FadePaused))
+        .id();
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    for _ in 0..6 {
+        app.update();
+    }
+    // Paused virtual time holds the sequence, `FadeUnscaled` runs on real time.
+    assert_eq!(app.world().get::<Opacity>(scaled).unwrap().get(), 0.0);
+    assert!(app.world().get_entity(unscaled).is_err());
+    // A paused fade does not advance its sequence.
+    app.world_mut().resource_mut::<Time<Virtual>>().unpause();
+    for _ in 0..6 {
+        app.update();
+    }
+    assert!(app.world().get_entity(scaled).is_err());
+    assert_eq!(app.world().get::<Opacity>(paused).unwrap().get(), 0.0);
+
+    app.insert_resource(ReducedMotion::Instant);
+    let instant = app
+        .world_mut()
+        .spawn((
+            Opacity::INVISIBLE,
+            OpacitySequence::new()
+                .fade_to(1.0, Duration::from_secs(10))
+                .fade_to(0.5, Duration::from_secs(10)),
+        ))
+        .id();
+    app.update();
+    assert_eq!(app.world().get::<Opacity>(instant).unwrap().get(), 0.5);
+    assert!(app.world().get::<OpacitySequence>(instant).is_none());
+}
+
+#[test]
+fn fade_out_after() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::FadeOutAfter;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app
+        .world_mut()
+        .spawn(FadeOutAfter::new(
+            Duration::from_millis(200),
+            Duration::from_millis(200),
+        ))
+        .id();
+    for _ in 0..3 {
+        app.update();
+    }
+    let opacity = app.world().get::<Opacity>(entity).unwrap();
+    assert!(opacity.is_despawning());
+    assert_eq!(opacity.get(), 0.5);
+    assert!(app.world().get::<FadeOutAfter>(entity).is_none());
+    for _ in 0..2 {
+        app.update();
+    }
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn fade_out_after_clock() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{FadeOutAfter, FadeUnscaled};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let after = FadeOutAfter::new(Duration::from_millis(200), Duration::from_millis(200));
+    let scaled = app.world_mut().spawn(after).id();
+    let unscaled = app.world_mut().spawn((after, FadeUnscaled)).id();
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    for _ in 0..3 {
+        app.update();
+    }
+    // Paused virtual time holds the lifetime, `FadeUnscaled` runs on real time.
+    assert!(app.world().get::<FadeOutAfter>(scaled).is_some());
+    assert!(app
+        .world()
+        .get::<Opacity>(unscaled)
+        .unwrap()
+        .is_despawning());
+}
+
+#[test]
+fn opacity_ext() {
+    use bevy::pbr::ExtendedMaterial;
+    use bevy_mod_opacity::OpacityExt;
+
+    let mut app = app();
+    app.init_asset::<ExtendedMaterial<StandardMaterial, OpacityExt>>()
+        .register_opacity_material3d::<ExtendedMaterial<StandardMaterial, OpacityExt>>();
+    let base = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    let material =
+        app.world_mut()
+            .run_system_once(
+                move |materials: Res<Assets<StandardMaterial>>,
+                      mut extended: ResMut<
+                    Assets<ExtendedMaterial<StandardMaterial, OpacityExt>>,
+                >| { OpacityExt::wrap(&base, &materials, &mut extended) },
+            )
+            .unwrap()
+            .unwrap();
+    app.world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial3d(material.clone())));
+    app.update();
+    let materials = app
+        .world()
+        .resource::<Assets<ExtendedMaterial<StandardMaterial, OpacityExt>>>();
+    let material = materials.get(&material).unwrap();
+    assert_eq!(material.extension.opacity, 0.5);
+    assert_eq!(material.base.base_color.alpha(), 1.0);
+}
+
+#[test]
+fn base_material_alpha() {
+    use bevy_mod_opacity::BaseMaterialAlphas;
+
+    let mut app = app();
+    let glass = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: Color::WHITE.with_alpha(0.5),
+            ..Default::default()
+        });
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), MeshMaterial3d(glass.clone())))
+        .id();
+    let alpha = |app: &App| {
+        app.world()
+            .resource::<Assets<StandardMaterial>>()
+            .get(&glass)
+            .unwrap()
+            .base_color
+            .alpha()
+    };
+    app.update();
+    assert_eq!(alpha(&app), 0.25);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    app.update();
+    assert_eq!(alpha(&app), 0.5);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.5);
+    app.update();
+    app.world_mut().despawn(entity);
+    app.update();
+    assert_eq!(alpha(&app), 0.5);
+    let bases = app
+        .world()
+        .resource::<BaseMaterialAlphas<StandardMaterial>>();
+    assert_eq!(bases.get(&glass), None);
+}
+
+#[test]
+fn late_spawn() {
+    use bevy_mod_opacity::OpacitySet;
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    app.update();
+    app.add_systems(
+        PostUpdate,
+        (move |mut commands: Commands| {
+            commands
+                .spawn((Opacity::new(0.5), Sprite::default(), ChildOf(root)))
+                .with_child(Sprite::default());
+        })
+        .after(OpacitySet::Apply)
+        .run_if(bevy::ecs::schedule::common_conditions::run_once),
+    );
+    app.update();
+    let mut sprites = app.world_mut().query_filtered::<&Sprite, With<ChildOf>>();
+    let alphas: Vec<_> = sprites
+        .iter(app.world())
+        .map(|sprite| sprite.color.alpha())
+        .collect();
+    assert_eq!(alphas, [0.25, 0.25]);
+}
+
+#[test]
+fn smooth_to() {
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app.world_mut().spawn(Opacity::INVISIBLE).id();
+    app.update();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .smooth_to(1.0, 10.0);
+    let opacity = |app: &App| *app.world().get::<Opacity>(entity).unwrap();
+    assert!(opacity(&app).is_fading());
+    app.update();
+    let first = opacity(&app).get();
+    assert!((first - (1.0 - (-1.0f32).exp())).abs() < 1e-5);
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .smooth_to(0.0, 10.0);
+    app.update();
+    assert!(opacity(&app).get() < first);
+    for _ in 0..20 {
+        app.update();
+    }
+    assert_eq!(opacity(&app).get(), 0.0);
+    assert!(!opacity(&app).is_fading());
+}
+
+#[test]
+fn spring_to() {
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app.world_mut().spawn(Opacity::INVISIBLE).id();
+    app.update();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .spring_to(1.0, 10.0);
+    let opacity = |app: &App| *app.world().get::<Opacity>(entity).unwrap();
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!((opacity(&app).get() - 0.6875).abs() < 1e-5);
+    // Velocity is carried over, so the spring decelerates instead of reversing instantly.
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .spring_to(0.0, 10.0);
+    app.update();
+    assert!((opacity(&app).get() - 0.5625).abs() < 1e-5);
+    for _ in 0..40 {
+        app.update();
+    }
+    assert_eq!(opacity(&app).get(), 0.0);
+    assert!(!opacity(&app).is_fading());
+}
+
+#[test]
+fn modify_set() {
+    use bevy_mod_opacity::OpacitySet;
+
+    fn halve(mut map: ResMut<OpacityMap>) {
+        for (_, opacity) in map.iter_updated_mut() {
+            *opacity *= 0.5;
+        }
+    }
+
+    let mut app = app();
+    app.add_systems(PostUpdate, halve.in_set(OpacitySet::Modify));
+    let a = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default()))
+        .id();
+    let b = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default()))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, a), 0.5);
+    assert_eq!(alpha(&app, b), 0.5);
+    // Entries that are not recomputed are not modified again.
+    app.world_mut().get_mut::<Opacity>(b).unwrap().set(0.5);
+    app.update();
+    app.update();
+    assert_eq!(alpha(&app, a), 0.5);
+    assert_eq!(alpha(&app, b), 0.25);
+    assert_eq!(app.world().resource::<OpacityMap>().get(a), Some(0.5));
+}
+
+#[cfg(feature = "post_process")]
+#[test]
+fn post_process_fade() {
+    use bevy_mod_opacity::PostProcessFade;
+
+    let mut app = app();
+    let camera = app
+        .world_mut()
+        .spawn((PostProcessFade::default(), Opacity::new(0.25)))
+        .id();
+    app.update();
+    let fade = app.world().get::<PostProcessFade>(camera).unwrap();
+    assert_eq!(fade.opacity(), 0.25);
+}
+
+#[test]
+fn alpha_cutoff() {
+    use bevy::render::alpha::AlphaMode;
+    use bevy_mod_opacity::{BaseAlphaCutoffs, OpacityMode};
+
+    let mut app = app();
+    let foliage = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            alpha_mode: AlphaMode::Mask(0.5),
+            ..Default::default()
+        });
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::new(0.5),
+            OpacityMode::Cutoff,
+            MeshMaterial3d(foliage.clone()),
+        ))
+        .id();
+    let material = |app: &App| {
+        app.world()
+            .resource::<Assets<StandardMaterial>>()
+            .get(&foliage)
+            .unwrap()
+            .clone()
+    };
+    app.update();
+    let AlphaMode::Mask(cutoff) = material(&app).alpha_mode else {
+        panic!("alpha mode changed");
+    };
+    assert!((cutoff - 0.75).abs() < 1e-5);
+    assert_eq!(material(&app).base_color.alpha(), 1.0);
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.0);
+    app.update();
+    let AlphaMode::Mask(cutoff) = material(&app).alpha_mode else {
+        panic!("alpha mode changed");
+    };
+    assert!(cutoff > 1.0);
+    app.world_mut().despawn(entity);
+    app.update();
+    assert_eq!(material(&app).alpha_mode, AlphaMode::Mask(0.5));
+    let cutoffs = app.world().resource::<BaseAlphaCutoffs>();
+    assert_eq!(cutoffs.get(&foliage), None);
+}
+
+#[test]
+fn fade_out_then() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::OpacityCommandsExt;
+    use std::time::Duration;
+
+    #[derive(Resource, Default)]
+    struct Completed(Vec<(Entity, f32)>);
+
+    fn on_faded(In(entity): In<Entity>, query: Query<&Opacity>, mut completed: ResMut<Completed>) {
+        // The entity is not despawned yet.
+        let opacity = query.get(entity).unwrap();
+        completed.0.push((entity, opacity.get()));
+    }
+
+    let mut app = app();
+    app.init_resource::<Completed>();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let system = app.world_mut().register_system(on_faded);
+    let entity = app.world_mut().spawn(Opacity::OPAQUE).id();
+    app.world_mut()
+        .commands()
+        .entity(entity)
+        .fade_out_then(0.2, system);
+    app.update();
+    app.update();
+    assert!(app.world().resource::<Completed>().0.is_empty());
+    app.update();
+    assert_eq!(app.world().resource::<Completed>().0, vec![(entity, 0.0)]);
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn fade_callback_cancelled() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::OnFadeComplete;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let mut opacity = Opacity::OPAQUE;
+    opacity.interpolate_to(0.0, 0.2);
+    let entity = app
+        .world_mut()
+        .spawn((
+            opacity,
+            OnFadeComplete::command(0.0, |mut entity: EntityWorldMut| {
+                entity.insert(Visibility::Hidden);
+            }),
+        ))
+        .id();
+    app.update();
+    app.update();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .interpolate_to(1.0, 0.2);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert!(app.world().get::<OnFadeComplete>(entity).is_none());
+    assert!(app.world().get::<Visibility>(entity).is_none());
+}
+
+#[test]
+fn opacity_then() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::OpacityThen;
+    use std::time::Duration;
+
+    #[derive(Event, Default)]
+    struct Faded;
+
+    #[derive(Resource, Default)]
+    struct Triggered(Vec<Entity>);
+
+    let mut app = app();
+    app.init_resource::<Triggered>();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.add_observer(
+        |trigger: Trigger<Faded>, mut triggered: ResMut<Triggered>| {
+            triggered.0.push(trigger.target());
+        },
+    );
+    let entity = app
+        .world_mut()
+        .spawn(
+            Opacity::new_fade_in(0.2)
+                .then_hold(0.2)
+                .then_fade_out(0.2)
+                .then_emit::<Faded>()
+                .then_despawn(),
+        )
+        .id();
+    let opacity = |app: &App| app.world().get::<Opacity>(entity).unwrap().get();
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(opacity(&app), 1.0);
+    app.update();
+    app.update();
+    assert_eq!(opacity(&app), 0.0);
+    assert!(app.world().resource::<Triggered>().0.is_empty());
+    app.update();
+    assert_eq!(app.world().resource::<Triggered>().0, vec![entity]);
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn ui_transition() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::UiTransition;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::INVISIBLE,
+            UiTransition::new(Duration::from_millis(400), EaseFunction::Linear),
+            Sprite::default(),
+        ))
+        .id();
+    app.update();
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    app.update();
+    assert!((alpha(&app, entity) - 0.25).abs() < 1e-5);
+    app.update();
+    assert!((alpha(&app, entity) - 0.5).abs() < 1e-5);
+    // Retargeting starts from the displayed value.
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.0);
+    app.update();
+    assert!((alpha(&app, entity) - 0.375).abs() < 1e-5);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(alpha(&app, entity), 0.0);
+    assert!(!app
+        .world()
+        .get::<UiTransition>(entity)
+        .unwrap()
+        .is_transitioning());
+}
+
+#[test]
+fn ui_transition_clock() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{FadeUnscaled, ReducedMotion, UiTransition};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let transition = UiTransition::new(Duration::from_millis(400), EaseFunction::Linear);
+    let scaled = app
+        .world_mut()
+        .spawn((Opacity::INVISIBLE, transition, Sprite::default()))
+        .id();
+    let unscaled = app
+        .world_mut()
+        .spawn((
+            Opacity::INVISIBLE,
+            transition,
+            FadeUnscaled,
+            Sprite::default(),
+        ))
+        .id();
+    app.update();
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    for entity in [scaled, unscaled] {
+        app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    }
+    app.update();
+    // Paused virtual time holds the transition, `FadeUnscaled` runs on real time.
+    assert_eq!(alpha(&app, scaled), 0.0);
+    assert!((alpha(&app, unscaled) - 0.25).abs() < 1e-5);
+
+    app.insert_resource(ReducedMotion::Instant);
+    app.world_mut().get_mut::<Opacity>(scaled).unwrap().set(0.5);
+    app.update();
+    assert_eq!(alpha(&app, scaled), 0.5);
+}
+
+#[test]
+fn skip_hidden() {
+    use bevy_mod_opacity::OpacityConfig;
+
+    let mut app = app();
+    app.world_mut().resource_mut::<OpacityConfig>().skip_hidden = true;
+    let child = app.world_mut().spawn(Sprite::default()).id();
+    let parent = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Visibility::Hidden, Sprite::default()))
+        .add_child(child)
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, parent), 1.0);
+    assert_eq!(alpha(&app, child), 1.0);
+    let computed = app
+        .world_mut()
+        .run_system_once(move |reader: OpacityReader| reader.get(child))
+        .unwrap();
+    assert_eq!(computed, None);
+    app.world_mut()
+        .entity_mut(parent)
+        .insert(Visibility::Inherited);
+    app.update();
+    assert_eq!(alpha(&app, parent), 0.5);
+    assert_eq!(alpha(&app, child), 0.5);
+    app.world_mut()
+        .entity_mut(parent)
+        .insert(Visibility::Hidden);
+    app.world_mut()
+        .get_mut::<Opacity>(parent)
+        .unwrap()
+        .set(0.25);
+    app.update();
+    assert_eq!(alpha(&app, child), 0.5);
+}
+
+#[test]
+fn apply_budget() {
+    use bevy_mod_opacity::{GlobalOpacity, OpacityConfig};
+
+    let mut app = app();
+    app.world_mut().resource_mut::<OpacityConfig>().apply_budget = Some(2);
+    let crowd: Vec<_> = (0..4)
+        .map(|_| {
+            app.world_mut()
+                .spawn((Opacity::OPAQUE, Sprite::default()))
+                .id()
+        })
+        .collect();
+    app.update();
+    app.world_mut().resource_mut::<GlobalOpacity>().0 = 0.5;
+    app.update();
+    let written = |app: &App| crowd.iter().filter(|e| alpha(app, **e) == 0.5).count();
+    assert_eq!(written(&app), 2);
+    app.update();
+    assert_eq!(written(&app), 4);
+
+    // Fading entities are written first.
+    let fading = crowd[3];
+    app.world_mut().resource_mut::<GlobalOpacity>().0 = 0.25;
+    app.world_mut()
+        .get_mut::<Opacity>(fading)
+        .unwrap()
+        .fade_out(10.0);
+    app.update();
+    assert!(alpha(&app, fading) < 0.5);
+    app.update();
+    app.update();
+    assert!(crowd.iter().all(|e| alpha(&app, *e) < 0.5));
+}
+
+#[test]
+fn render_opacity_map() {
+    use bevy::render::extract_resource::ExtractResource;
+    use bevy_mod_opacity::RenderOpacityMap;
+
+    let mut app = app();
+    let child = app.world_mut().spawn(Sprite::default()).id();
+    let parent = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .add_child(child)
+        .id();
+    app.update();
+    let extracted = RenderOpacityMap::extract_resource(app.world().resource::<OpacityMap>());
+    assert_eq!(extracted.get(parent), Some(0.5));
+    assert_eq!(extracted.get(child), Some(0.5));
+    assert_eq!(extracted.iter().count(), 2);
+}
+
+#[test]
+fn disable_shadows() {
+    use bevy::pbr::NotShadowCaster;
+    use bevy_mod_opacity::OpacityShadowThreshold;
+
+    let mut app = app();
+    app.insert_resource(OpacityShadowThreshold(0.5));
+    let mesh = app
+        .world_mut()
+        .spawn((Mesh3d::default(), Opacity::new(0.25)))
+        .id();
+    let caster = app
+        .world_mut()
+        .spawn((Mesh3d::default(), Opacity::new(0.25), NotShadowCaster))
+        .id();
+    app.update();
+    assert!(app.world().get::<NotShadowCaster>(mesh).is_some());
+    app.world_mut().get_mut::<Opacity>(mesh).unwrap().set(0.5);
+    app.update();
+    assert!(app.world().get::<NotShadowCaster>(mesh).is_some());
+    app.world_mut().get_mut::<Opacity>(mesh).unwrap().set(0.75);
+    app.world_mut().get_mut::<Opacity>(caster).unwrap().set(1.0);
+    app.update();
+    assert!(app.world().get::<NotShadowCaster>(mesh).is_none());
+    assert!(app.world().get::<NotShadowCaster>(caster).is_some());
+}
+
+#[test]
+fn fade_clear_color() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{ClearColorFadeFinished, FadeClearColor};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )))
+    .insert_resource(ClearColor(Color::BLACK));
+    let white = Color::linear_rgb(1.0, 1.0, 1.0);
+    app.world_mut()
+        .commands()
+        .queue(FadeClearColor::new(white, 0.2));
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world().resource::<ClearColor>().0,
+        Color::linear_rgb(0.5, 0.5, 0.5)
+    );
+    app.update();
+    assert_eq!(app.world().resource::<ClearColor>().0, white);
+    let events: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Events<ClearColorFadeFinished>>()
+        .drain()
+        .collect();
+    assert_eq!(
+        events,
+        vec![ClearColorFadeFinished {
+            camera: None,
+            color: white
+        }]
+    );
+}
+
+#[cfg(feature = "gizmos")]
+#[test]
+fn fade_gizmo_config() {
+    use bevy::gizmos::{GizmoAsset, GizmoPlugin};
+    use bevy_mod_opacity::{GizmoConfigFade, OpacityGizmoConfigExt};
+    use std::any::TypeId;
+
+    let mut app = app();
+    app.init_asset::<Shader>()
+        .add_plugins(GizmoPlugin)
+        .fade_gizmo_config::<DefaultGizmoConfigGroup>(GizmoConfigFade::Global);
+    app.insert_resource(bevy_mod_opacity::GlobalOpacity(0.5));
+    app.add_systems(Update, |mut gizmos: Gizmos| {
+        gizmos.line(Vec3::ZERO, Vec3::X, Color::WHITE);
+    });
+    app.update();
+    let mut assets = app.world_mut().resource_mut::<Assets<GizmoAsset>>();
+    let (_, asset) = assets
+        .iter()
+        .find(|(_, asset)| asset.config_typeid() == TypeId::of::<DefaultGizmoConfigGroup>())
+        .unwrap();
+    assert_eq!(asset.buffer().list_colors[0].alpha, 0.5);
+    // A copy that is not redrawn is faded once, not every frame.
+    let copy = asset.clone();
+    let copy = assets.add(copy);
+    for _ in 0..3 {
+        app.update();
+    }
+    let assets = app.world().resource::<Assets<GizmoAsset>>();
+    assert_eq!(
+        assets.get(&copy).unwrap().buffer().list_colors[0].alpha,
+        0.25
+    );
+    let alphas: Vec<_> = assets
+        .iter()
+        .filter(|(id, _)| *id != copy.id())
+        .map(|(_, asset)| asset.buffer().list_colors[0].alpha)
+        .collect();
+    assert_eq!(alphas, [0.5]);
+}
+
+#[test]
+fn default_fade_durations() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::{DefaultFadeDurations, OpacityCommandsExt};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )))
+    .insert_resource(DefaultFadeDurations {
+        show: 0.2,
+        hide: 0.4,
+    });
+    let shown = app.world_mut().spawn(Opacity::INVISIBLE).id();
+    let hidden = app.world_mut().spawn(Opacity::OPAQUE).id();
+    app.update();
+    app.world_mut().get_mut::<Opacity>(shown).unwrap().show();
+    app.world_mut().commands().entity(hidden).hide();
+    app.update();
+    let opacity = |app: &App, entity| app.world().get::<Opacity>(entity).unwrap().get();
+    assert_eq!(opacity(&app, shown), 0.5);
+    assert_eq!(opacity(&app, hidden), 0.75);
+    app.update();
+    assert_eq!(opacity(&app, shown), 1.0);
+    for _ in 0..2 {
+        app.update();
+    }
+    assert_eq!(opacity(&app, hidden), 0.0);
+    assert!(app.world().get_entity(hidden).is_ok());
+}
+
+#[test]
+fn opacity_easing() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::OpacityEasing;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity::INVISIBLE,
+            OpacityEasing(EaseFunction::QuadraticIn),
+            Sprite::default(),
+        ))
+        .id();
+    app.update();
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .fade_in(0.4);
+    app.update();
+    assert!((alpha(&app, entity) - 0.0625).abs() < 1e-5);
+    app.update();
+    assert!((alpha(&app, entity) - 0.25).abs() < 1e-5);
+    // Retargeting starts from the displayed opacity.
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .interpolate_to(0.0, 0.2);
+    app.update();
+    assert!((alpha(&app, entity) - 0.1875).abs() < 1e-5);
+    app.update();
+    assert_eq!(alpha(&app, entity), 0.0);
+}
+
+#[test]
+fn fade_for_duration() {
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let faded = app.world_mut().spawn(Opacity::INVISIBLE).id();
+    let invalid = app.world_mut().spawn(Opacity::INVISIBLE).id();
+    let negative = app.world_mut().spawn(Opacity::OPAQUE).id();
+    app.update();
+    app.world_mut()
+        .get_mut::<Opacity>(faded)
+        .unwrap()
+        .fade_in_for(Duration::from_millis(400));
+    app.world_mut()
+        .get_mut::<Opacity>(invalid)
+        .unwrap()
+        .fade_in(f32::NAN);
+    app.world_mut()
+        .get_mut::<Opacity>(negative)
+        .unwrap()
+        .interpolate_to(0.5, -1.0);
+    app.update();
+    let opacity = |app: &App, entity| app.world().get::<Opacity>(entity).unwrap().get();
+    assert_eq!(opacity(&app, faded), 0.25);
+    assert_eq!(opacity(&app, invalid), 1.0);
+    assert_eq!(opacity(&app, negative), 0.5);
+}
+
+#[test]
+fn register_opacity_filtered() {
+    #[derive(Component)]
+    struct CustomPipeline;
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        OpacityPlugin::default().without_builtin(),
+    ))
+    .register_opacity_filtered::<&mut Sprite, Without<CustomPipeline>>();
+    let faded = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let custom = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default(), CustomPipeline))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, faded), 0.5);
+    assert_eq!(alpha(&app, custom), 1.0);
+}
+
+#[test]
+fn opacity_ignore() {
+    use bevy_mod_opacity::OpacityIgnore;
+
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let ignored = app
+        .world_mut()
+        .spawn((Sprite::default(), OpacityIgnore, ChildOf(root)))
+        .id();
+    let leaf = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(ignored)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, root), 0.5);
+    assert_eq!(alpha(&app, ignored), 1.0);
+    assert_eq!(alpha(&app, leaf), 0.5);
+    assert_eq!(app.world().resource::<OpacityMap>().get(ignored), Some(0.5));
+}
+
+#[test]
+fn zero_duration_fades() {
+    use bevy_mod_opacity::OnFadeComplete;
+
+    #[derive(Component)]
+    struct Completed;
+
+    let mut app = app();
+    let faded = app
+        .world_mut()
+        .spawn((
+            Opacity::new_fade_in(0.0),
+            Sprite::default(),
+            OnFadeComplete::command(1.0, |mut entity: EntityWorldMut| {
+                entity.insert(Completed);
+            }),
+        ))
+        .id();
+    let unchanged = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), Sprite::default()))
+        .id();
+    let despawned = app.world_mut().spawn(Opacity::OPAQUE).id();
+    app.world_mut()
+        .get_mut::<Opacity>(unchanged)
+        .unwrap()
+        .interpolate_to(0.5, 0.0);
+    app.world_mut()
+        .get_mut::<Opacity>(despawned)
+        .unwrap()
+        .fade_out(-1.0);
+    // The first update does not advance time.
+    app.update();
+    assert_eq!(alpha(&app, faded), 1.0);
+    assert!(app.world().get::<Completed>(faded).is_some());
+    assert_eq!(alpha(&app, unchanged), 0.5);
+    assert!(!app.world().get::<Opacity>(unchanged).unwrap().is_fading());
+    assert!(app.world().get_entity(despawned).is_err());
+}
+
+#[test]
+fn opacity_buffer() {
+    use bevy::render::storage::ShaderStorageBuffer;
+    use bevy_mod_opacity::{GpuOpacity, OpacityBuffer};
+
+    let mut app = app();
+    app.init_asset::<ShaderStorageBuffer>()
+        .init_resource::<OpacityBuffer>();
+    let faded = app
+        .world_mut()
+        .spawn((Opacity::new(0.5), GpuOpacity::default()))
+        .id();
+    let unfaded = app.world_mut().spawn(GpuOpacity::default()).id();
+    app.update();
+    let slot = |app: &App, entity| {
+        app.world()
+            .get::<GpuOpacity>(entity)
+            .unwrap()
+            .slot()
+            .unwrap()
+    };
+    let (faded_slot, unfaded_slot) = (slot(&app, faded), slot(&app, unfaded));
+    assert_ne!(faded_slot, unfaded_slot);
+    let buffer = app.world().resource::<OpacityBuffer>();
+    assert_eq!(buffer.get(faded_slot), Some(0.5));
+    assert_eq!(buffer.get(unfaded_slot), Some(1.0));
+    let data = app
+        .world()
+        .resource::<Assets<ShaderStorageBuffer>>()
+        .get(buffer.handle())
+        .unwrap()
+        .data
+        .clone()
+        .unwrap();
+    assert_eq!(data.len(), 8);
+
+    app.world_mut().despawn(faded);
+    let spawned = app.world_mut().spawn(GpuOpacity::default()).id();
+    app.update();
+    assert_eq!(slot(&app, spawned), faded_slot);
+}
+
+#[test]
+fn staggered_fade() {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::StaggeredFade;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let parent = app
+        .world_mut()
+        .spawn(StaggeredFade::fade_in(
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+        ))
+        .id();
+    let first = app
+        .world_mut()
+        .spawn((Sprite::default(), ChildOf(parent)))
+        .id();
+    let second = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default(), ChildOf(parent)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, first), 0.0);
+    assert_eq!(alpha(&app, second), 0.0);
+    app.update();
+    assert_eq!(alpha(&app, first), 0.5);
+    assert_eq!(alpha(&app, second), 0.5);
+    assert!(app.world().get::<StaggeredFade>(parent).is_none());
+    app.update();
+    assert_eq!(alpha(&app, first), 1.0);
+    assert_eq!(alpha(&app, second), 1.0);
+}
+
+#[test]
+fn staggered_fade_clock() {
+    use bevy::{ecs::entity_disabling::Disabled, time::TimeUpdateStrategy};
+    use bevy_mod_opacity::{FadeUnscaled, ReducedMotion, StaggeredFade};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let stagger = StaggeredFade::fade_in(Duration::from_millis(100), Duration::ZERO);
+    let scaled = app.world_mut().spawn(stagger).id();
+    let unscaled = app.world_mut().spawn((stagger, FadeUnscaled)).id();
+    let mut children = Vec::new();
+    for parent in [scaled, unscaled] {
+        // A child the fade cannot reach still counts as started.
+        app.world_mut().spawn((Disabled, ChildOf(parent)));
+        let child = app.world_mut().spawn((Sprite::default(), ChildOf(parent)));
+        children.push(child.id());
+    }
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    for _ in 0..4 {
+        app.update();
+    }
+    // Paused virtual time holds the delays, `FadeUnscaled` runs on real time.
+    assert_eq!(alpha(&app, children[0]), 0.0);
+    assert_eq!(alpha(&app, children[1]), 1.0);
+    assert!(app.world().get::<StaggeredFade>(scaled).is_some());
+    assert!(app.world().get::<StaggeredFade>(unscaled).is_none());
+
+    app.insert_resource(ReducedMotion::Instant);
+    app.world_mut()
+        .entity_mut(scaled)
+        .insert(StaggeredFade::fade_in(
+            Duration::from_secs(10),
+            Duration::ZERO,
+        ));
+    app.update();
+    app.update();
+    assert_eq!(alpha(&app, children[0]), 1.0);
+    assert!(app.world().get::<StaggeredFade>(scaled).is_none());
+}
+
+#[test]
+fn global_opacity_layers() {
+    use bevy::render::view::RenderLayers;
+    use bevy_mod_opacity::{GlobalOpacity, GlobalOpacityLayers};
+
+    let mut app = app();
+    app.insert_resource(GlobalOpacity(0.5));
+    let world = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default()))
+        .id();
+    let minimap = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, Sprite::default(), RenderLayers::layer(1)))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, world), 0.5);
+    assert_eq!(alpha(&app, minimap), 0.5);
+    app.insert_resource(GlobalOpacityLayers(RenderLayers::layer(0)));
+    app.update();
+    assert_eq!(alpha(&app, world), 0.5);
+    assert_eq!(alpha(&app, minimap), 1.0);
+    app.world_mut().remove_resource::<GlobalOpacityLayers>();
+    app.update();
+    assert_eq!(alpha(&app, minimap), 0.5);
+}
+
+#[test]
+fn view_opacity() {
+    use bevy::render::extract_resource::ExtractResource;
+    use bevy_mod_opacity::{RenderOpacityMap, ViewOpacity};
+
+    let mut app = app();
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::new(0.25), Sprite::default()))
+        .id();
+    app.update();
+    let extracted = RenderOpacityMap::extract_resource(app.world().resource::<OpacityMap>());
+    assert_eq!(extracted.get_in_view(None, entity), Some(0.25));
+    let get = |view| extracted.get_in_view(Some(&view), entity);
+    assert_eq!(get(ViewOpacity::Computed), Some(0.25));
+    assert_eq!(get(ViewOpacity::Opaque), Some(1.0));
+    assert_eq!(get(ViewOpacity::AtLeast(0.5)), Some(0.5));
+}
+
+#[cfg(feature = "accessibility")]
+#[test]
+fn hide_accessibility_nodes() {
+    use bevy::a11y::AccessibilityNode;
+
+    let mut app = app();
+    let faded = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, AccessibilityNode(Default::default())))
+        .id();
+    let mut hidden = AccessibilityNode(Default::default());
+    hidden.set_hidden();
+    let hidden = app.world_mut().spawn((Opacity::OPAQUE, hidden)).id();
+    let is_hidden = |app: &App, entity| {
+        app.world()
+            .get::<AccessibilityNode>(entity)
+            .unwrap()
+            .is_hidden()
+    };
+    app.update();
+    assert!(!is_hidden(&app, faded));
+    for entity in [faded, hidden] {
+        app.world_mut().get_mut::<Opacity>(entity).unwrap().set(0.0);
+    }
+    app.update();
+    assert!(is_hidden(&app, faded));
+    for entity in [faded, hidden] {
+        app.world_mut().get_mut::<Opacity>(entity).unwrap().set(1.0);
+    }
+    app.update();
+    assert!(!is_hidden(&app, faded));
+    assert!(is_hidden(&app, hidden));
+}
+
+#[cfg(feature = "picking")]
+#[test]
+fn disable_picking() {
+    use bevy::picking::Pickable;
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let pickable = Pickable {
+        should_block_lower: false,
+        is_hoverable: true,
+    };
+    let mut opacity = Opacity::OPAQUE;
+    opacity.interpolate_to(0.0, 0.2);
+    let entity = app
+        .world_mut()
+        .spawn((opacity, Sprite::default(), pickable.clone()))
+        .id();
+    let get = |app: &App| app.world().get::<Pickable>(entity).cloned();
+    app.update();
+    app.update();
+    assert_eq!(get(&app), Some(pickable.clone()));
+    for _ in 0..2 {
+        app.update();
+    }
+    assert_eq!(alpha(&app, entity), 0.0);
+    assert_eq!(get(&app), Some(Pickable::IGNORE));
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .fade_in(0.2);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(alpha(&app, entity), 1.0);
+    assert_eq!(get(&app), Some(pickable));
+}
+
+#[cfg(feature = "animation")]
+#[test]
+fn animation_clip_property() {
+    use bevy::animation::{
+        animation_curves::{AnimatableCurve, AnimatableKeyframeCurve},
+        AnimationTarget, AnimationTargetId,
+    };
+    use bevy_mod_opacity::OpacityProperty;
+
+    let mut app = app();
+    app.add_plugins(AnimationPlugin);
+    let target = AnimationTargetId::from_name(&Name::new("fader"));
+    let mut clip = AnimationClip::default();
+    clip.add_curve_to_target(
+        target,
+        AnimatableCurve::new(
+            OpacityProperty,
+            AnimatableKeyframeCurve::new([(0.0, 1.0), (1.0, 0.0)]).unwrap(),
+        ),
+    );
+    let clip = app
+        .world_mut()
+        .resource_mut::<Assets<AnimationClip>>()
+        .add(clip);
+    let (graph, node) = AnimationGraph::from_clip(clip);
+    let graph = app
+        .world_mut()
+        .resource_mut::<Assets<AnimationGraph>>()
+        .add(graph);
+    let mut player = AnimationPlayer::default();
+    player.play(node).seek_to(0.75).pause();
+    let entity = app
+        .world_mut()
+        .spawn((
+            player,
+            AnimationGraphHandle(graph),
+            Opacity::OPAQUE,
+            Sprite::default(),
+        ))
+        .id();
+    app.world_mut().entity_mut(entity).insert(AnimationTarget {
+        id: target,
+        player: entity,
+    });
+    app.update();
+    app.update();
+    assert_eq!(app.world().get::<Opacity>(entity).unwrap().get(), 0.25);
+    assert_eq!(alpha(&app, entity), 0.25);
+}
+
+#[cfg(feature = "animation")]
+#[test]
+fn animation_graph_blend() {
+    use bevy::animation::{
+        animation_curves::{AnimatableCurve, AnimatableKeyframeCurve},
+        AnimationTarget, AnimationTargetId,
+    };
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::OpacityStateProperty;
+    use std::time::Duration;
+
+    let mut app = app();
+    // Keep the blended fade from advancing.
+    app.add_plugins(AnimationPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    let target = AnimationTargetId::from_name(&Name::new("fader"));
+    let mut clip = |opacity: Opacity| {
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                OpacityStateProperty,
+                AnimatableKeyframeCurve::new([(0.0, opacity), (1.0, opacity)]).unwrap(),
+            ),
+        );
+        app.world_mut()
+            .resource_mut::<Assets<AnimationClip>>()
+            .add(clip)
+    };
+    let shimmer = clip(Opacity::new(1.0));
+    let flash = clip(Opacity::new(0.5).and_fade_in(1.0));
+    let mut graph = AnimationGraph::new();
+    let blend = graph.add_blend(1.0, graph.root);
+    let nodes = [shimmer, flash].map(|clip| graph.add_clip(clip, 0.5, blend));
+    let graph = app
+        .world_mut()
+        .resource_mut::<Assets<AnimationGraph>>()
+        .add(graph);
+    let mut player = AnimationPlayer::default();
+    for node in nodes {
+        player.play(node).pause();
+    }
+    let entity = app
+        .world_mut()
+        .spawn((player, AnimationGraphHandle(graph), Opacity::INVISIBLE))
+        .id();
+    app.world_mut().entity_mut(entity).insert(AnimationTarget {
+        id: target,
+        player: entity,
+    });
+    app.update();
+    app.update();
+    let opacity = *app.world().get::<Opacity>(entity).unwrap();
+    assert_eq!(opacity.get(), 0.75);
+    assert_eq!(opacity.get_target(), 1.0);
+    assert!(opacity.is_fading());
+}
+
+#[cfg(feature = "serde_full")]
+#[test]
+fn serde_full_round_trip() {
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let mut opacity = Opacity::new(0.5);
+    opacity.fade_out(0.5);
+    let saved = ron::to_string(&opacity).unwrap();
+    let loaded: Opacity = ron::from_str(&saved).unwrap();
+    assert_eq!(loaded, opacity);
+    // The loaded fade continues and despawns the entity.
+    let entity = app.world_mut().spawn(loaded).id();
+    app.update();
+    app.update();
+    assert_eq!(app.world().get::<Opacity>(entity).unwrap().get(), 0.3);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[cfg(feature = "audio")]
+#[test]
+fn audio_volume() {
+    use bevy::audio::{AudioSink, AudioSinkPlayback, Volume};
+
+    let mut app = app();
+    let mut sink = AudioSink::new(rodio::Sink::new_idle().0);
+    sink.set_volume(Volume::Linear(0.8));
+    let torch = app.world_mut().spawn((Opacity::new(0.5), sink)).id();
+    let volume = |app: &App| {
+        app.world()
+            .get::<AudioSink>(torch)
+            .unwrap()
+            .volume()
+            .to_linear()
+    };
+    app.update();
+    assert_eq!(volume(&app), 0.4);
+    app.update();
+    assert_eq!(volume(&app), 0.4);
+    app.world_mut().get_mut::<Opacity>(torch).unwrap().set(1.0);
+    app.update();
+    assert_eq!(volume(&app), 0.8);
+}
+
+#[cfg(feature = "hanabi")]
+#[test]
+fn hanabi_effect_property() {
+    use bevy_hanabi::EffectProperties;
+    use bevy_mod_opacity::EFFECT_OPACITY;
+
+    let mut app = app();
+    let root = app.world_mut().spawn(Opacity::new(0.5)).id();
+    let effect = app
+        .world_mut()
+        .spawn((EffectProperties::default(), ChildOf(root)))
+        .id();
+    let property = |app: &App| {
+        app.world()
+            .get::<EffectProperties>(effect)
+            .unwrap()
+            .get_stored(EFFECT_OPACITY)
+    };
+    app.update();
+    assert_eq!(property(&app), Some(0.5.into()));
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(1.0);
+    app.update();
+    assert_eq!(property(&app), Some(1.0.into()));
+}
+
+#[test]
+#[cfg(feature = "ecs_tilemap")]
+fn tile_color_alpha() {
+    use bevy_ecs_tilemap::tiles::TileColor;
+    use bevy_mod_opacity::BaseTileAlpha;
+
+    let mut app = app();
+    let tilemap = app.world_mut().spawn(Opacity::new(0.5)).id();
+    let tile = app
+        .world_mut()
+        .spawn((
+            TileColor(Color::srgba(1.0, 1.0, 1.0, 0.5)),
+            ChildOf(tilemap),
+        ))
+        .id();
+    let alpha = |app: &App| app.world().get::<TileColor>(tile).unwrap().0.alpha();
+    app.update();
+    assert_eq!(alpha(&app), 0.25);
+    assert_eq!(
+        app.world().get::<BaseTileAlpha>(tile),
+        Some(&BaseTileAlpha(0.5))
+    );
+    app.world_mut()
+        .get_mut::<Opacity>(tilemap)
+        .unwrap()
+        .set(1.0);
+    app.update();
+    assert_eq!(alpha(&app), 0.5);
+}
+
+#[test]
+#[cfg(feature = "egui")]
+fn egui_context_visuals() {
+    use bevy_egui::{egui::Visuals, EguiContext};
+    use bevy_mod_opacity::BaseEguiVisuals;
+
+    let mut app = app();
+    let root = app.world_mut().spawn(Opacity::new(0.5)).id();
+    let context = app
+        .world_mut()
+        .spawn((EguiContext::default(), ChildOf(root)))
+        .id();
+    let visuals = |app: &mut App| {
+        let mut entity = app.world_mut().entity_mut(context);
+        let mut context = entity.get_mut::<EguiContext>().unwrap();
+        context.get_mut().style().visuals.clone()
+    };
+    let base = Visuals::dark();
+    app.update();
+    let faded = visuals(&mut app);
+    assert_eq!(faded.window_fill, base.window_fill.gamma_multiply(0.5));
+    assert_eq!(
+        faded.widgets.inactive.fg_stroke.color,
+        base.widgets.inactive.fg_stroke.color.gamma_multiply(0.5)
+    );
+    assert_eq!(
+        app.world().get::<BaseEguiVisuals>(context),
+        Some(&BaseEguiVisuals(base.clone()))
+    );
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(1.0);
+    app.update();
+    assert_eq!(visuals(&mut app), base);
+}
+
+#[test]
+#[cfg(feature = "tweening")]
+fn tweening_opacity_lens() {
+    use bevy::math::curve::EaseFunction;
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_mod_opacity::OpacityLens;
+    use bevy_tweening::{Animator, Tween};
+    use std::time::Duration;
+
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    let sprite = app
+        .world_mut()
+        .spawn((
+            Sprite::default(),
+            Opacity::INVISIBLE,
+            Animator::new(Tween::new(
+                EaseFunction::Linear,
+                Duration::from_secs(1),
+                OpacityLens {
+                    start: 0.0,
+                    end: 1.0,
+                },
+            )),
+        ))
+        .id();
+    app.update();
+    assert_eq!(alpha(&app, sprite), 0.0);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert!((alpha(&app, sprite) - 0.5).abs() < 1e-4);
+}
+
+#[test]
+#[cfg(feature = "lyon")]
+fn lyon_shape_colors() {
+    use bevy_mod_opacity::BaseShapeAlpha;
+    use bevy_prototype_lyon::prelude::*;
+
+    let mut app = app();
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .add(Color::WHITE);
+    let root = app.world_mut().spawn(Opacity::new(0.5)).id();
+    let shape = ShapeBuilder::with(&shapes::Circle {
+        radius: 10.0,
+        ..default()
+    })
+    .fill(Color::srgba(1.0, 0.0, 0.0, 0.5))
+    .stroke(Stroke::new(Color::BLACK, 2.0))
+    .build();
+    let shape = app
+        .world_mut()
+        .spawn((shape, MeshMaterial2d(material.clone()), ChildOf(root)))
+        .id();
+    let alphas = |app: &App| {
+        let shape = app.world().get::<Shape>(shape).unwrap();
+        (
+            shape.fill.unwrap().color.alpha(),
+            shape.stroke.unwrap().color.alpha(),
+        )
+    };
+    app.update();
+    assert_eq!(alphas(&app), (0.25, 0.5));
+    assert_eq!(
+        app.world().get::<BaseShapeAlpha>(shape),
+        Some(&BaseShapeAlpha {
+            fill: 0.5,
+            stroke: 1.0
+        })
+    );
+    // The shared material is left alone.
+    let materials = app.world().resource::<Assets<ColorMaterial>>();
+    assert_eq!(materials.get(&material).unwrap().color.alpha(), 1.0);
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(1.0);
+    app.update();
+    assert_eq!(alphas(&app), (0.5, 1.0));
+}
+
+#[test]
+#[cfg(feature = "inspector")]
+fn inspector_type_data() {
+    use bevy_inspector_egui::{
+        egui, inspector_egui_impls::InspectorEguiImpl, reflect_inspector::ui_for_value,
+    };
+
+    let app = app();
+    let registry = app.world().resource::<AppTypeRegistry>().read();
+    assert!(registry
+        .get_type_data::<InspectorEguiImpl>(std::any::TypeId::of::<Opacity>())
+        .is_some());
+    let mut opacity = Opacity::new_fade_in(1.0);
+    let mut changed = true;
+    let _ = egui::Context::default().run(egui::RawInput::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            changed = ui_for_value(&mut opacity, ui, &registry);
+        });
+    });
+    assert!(!changed);
+    assert_eq!(opacity, Opacity::new_fade_in(1.0));
+}
+
+#[test]
+#[cfg(feature = "avian2d")]
+fn disable_avian_colliders() {
+    use avian2d::prelude::{Collider, ColliderDisabled};
+    use bevy_mod_opacity::OpacityColliderThreshold;
+
+    let mut app = app();
+    app.insert_resource(OpacityColliderThreshold(0.5));
+    let root = app.world_mut().spawn(Opacity::new(0.75)).id();
+    let collider = app
+        .world_mut()
+        .spawn((Collider::circle(1.0), ChildOf(root)))
+        .id();
+    let user_disabled = app
+        .world_mut()
+        .spawn((Collider::circle(1.0), ColliderDisabled, ChildOf(root)))
+        .id();
+    app.update();
+    assert!(!app.world().entity(collider).contains::<ColliderDisabled>());
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(0.5);
+    app.update();
+    assert!(app.world().entity(collider).contains::<ColliderDisabled>());
+    app.world_mut().get_mut::<Opacity>(root).unwrap().set(1.0);
+    app.update();
+    assert!(!app.world().entity(collider).contains::<ColliderDisabled>());
+    assert!(app
+        .world()
+        .entity(user_disabled)
+        .contains::<ColliderDisabled>());
+}
+
+#[test]
+#[cfg(feature = "rapier2d")]
+fn disable_rapier_colliders() {
+    use bevy_mod_opacity::OpacityColliderThreshold;
+    use bevy_rapier2d::prelude::{Collider, ColliderDisabled};
+
+    let mut app = app();
+    let root = app.world_mut().spawn(Opacity::new(0.5)).id();
+    let collider = app
+        .world_mut()
+        .spawn((Collider::ball(1.0), ChildOf(root)))
+        .id();
+    app.update();
+    assert!(!app.world().entity(collider).contains::<ColliderDisabled>());
+    app.insert_resource(OpacityColliderThreshold(0.5));
+    app.update();
+    assert!(app.world().entity(collider).contains::<ColliderDisabled>());
+    app.insert_resource(OpacityColliderThreshold(0.0));
+    app.update();
+    assert!(!app.world().entity(collider).contains::<ColliderDisabled>());
+}
+
+#[test]
+fn opacity_interpolation_traits() {
+    use bevy::math::curve::{Curve, EaseFunction, EasingCurve};
+    use bevy::math::StableInterpolate;
+
+    let start = Opacity::from(0.25);
+    let end = Opacity::new(0.75);
+    assert_eq!(start.interpolate_stable(&end, 0.5).get(), 0.5);
+    let curve = EasingCurve::new(start, end, EaseFunction::Linear);
+    assert_eq!(curve.sample(0.0).unwrap(), start);
+    assert_eq!(curve.sample(1.0).unwrap(), end);
+    assert_eq!((end - start).get(), 0.5);
+    assert_eq!((start + end).get(), 1.0);
+    assert_eq!((end * 0.5).get(), 0.375);
+    assert_eq!((start * end).get(), 0.1875);
+    assert!(!(start + end.and_fade_in(1.0)).is_fading());
+}
+
+#[test]
+fn opacity_writer() {
+    use bevy_mod_opacity::OpacityWriter;
+
+    let mut app = app();
+    let faded = app.world_mut().spawn(Opacity::OPAQUE).id();
+    let missing = app.world_mut().spawn(Sprite::default()).id();
+    let despawned = app.world_mut().spawn_empty().id();
+    app.world_mut().despawn(despawned);
+    app.world_mut()
+        .run_system_once(move |mut writer: OpacityWriter| {
+            writer.set(faded, 0.5);
+            writer.set(missing, 0.25);
+            writer.interpolate_many([missing, despawned], 0.0, 1.0);
+        })
+        .unwrap();
+    let opacity = |app: &App, entity| *app.world().get::<Opacity>(entity).unwrap();
+    assert_eq!(opacity(&app, faded).get(), 0.5);
+    assert_eq!(opacity(&app, missing).get(), 0.25);
+    assert_eq!(opacity(&app, missing).get_target(), 0.0);
+}